@@ -0,0 +1,442 @@
+use crate::description::agent::Agent;
+use crate::description::energy::kwh;
+use crate::description::integration::IntegrationBom;
+use crate::description::job::Job;
+use crate::description::target::Target;
+use crate::description::units::Time;
+use crate::petri::cost::{CostCategory, CostFrequency, CostSet};
+use crate::petri::data::{Data, DataTag, Query};
+use crate::petri::transition::Transition;
+use enum_tag::EnumTag;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Tolerance (in units per period) below which a shortfall is ignored
+const SHORTFALL_TOLERANCE: f64 = 1e-6;
+// Steps of the walk through the task dependencies before they are taken to be cyclic
+const FIRING_WALK_LIMIT: usize = 10000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Allocation {
+    // Task UUID -> UUIDs of the agents assigned to it
+    pub tasks: HashMap<Uuid, Vec<Uuid>>,
+}
+
+impl Allocation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn assign(&mut self, task: Uuid, agents: Vec<Uuid>) {
+        self.tasks
+            .insert(task, agents.into_iter().sorted().dedup().collect());
+    }
+
+    pub fn agents(&self) -> Vec<Uuid> {
+        self.tasks
+            .values()
+            .flatten()
+            .cloned()
+            .sorted()
+            .dedup()
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskEvaluation {
+    pub task: Uuid,
+    pub agents: Vec<Uuid>,
+    pub transition: Uuid,
+    pub time: Time,
+    pub firings: f64, // firings per period
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationEvaluation {
+    pub allocation: Allocation,
+    // False if some task has no realizable transition for its assigned agents
+    pub feasible: bool,
+    pub tasks: Vec<TaskEvaluation>,
+    pub period: Time,
+    pub agent_busy_time: HashMap<Uuid, Time>,
+    // Time the bottleneck agent needs for one period's worth of work
+    pub cycle_time: Time,
+    pub required: HashMap<Uuid, f64>,
+    pub produced: HashMap<Uuid, f64>,
+    pub shortfall: HashMap<Uuid, f64>,
+    pub produced_value: f64,
+    pub once_cost: f64,
+    // Integration bill of materials of each allocated robot, for the tasks it was given
+    pub integration: HashMap<Uuid, IntegrationBom>,
+    pub extrapolated_monetary_cost: f64,
+    pub extrapolated_ergonomic_cost: f64,
+    // Standby energy of robots waiting on other agents, per period. Included in the monetary cost.
+    pub idle_energy_cost: f64,
+    pub score: f64,
+}
+
+impl AllocationEvaluation {
+    pub fn meets_demand(&self) -> bool {
+        self.feasible && self.total_shortfall() <= SHORTFALL_TOLERANCE
+    }
+
+    pub fn total_shortfall(&self) -> f64 {
+        self.shortfall.values().fold(0.0, |total, units| total + units)
+    }
+
+    pub fn utilization(&self, agent: &Uuid) -> f64 {
+        if self.period <= 0.0 {
+            return 0.0;
+        }
+        self.agent_busy_time.get(agent).unwrap_or(&0.0) / self.period
+    }
+}
+
+pub fn sum_costs(costs: &CostSet, frequency: CostFrequency, category: CostCategory) -> f64 {
+    costs
+        .iter()
+        .filter(|c| c.frequency == frequency && c.category == category)
+        .map(|c| c.value)
+        .sum()
+}
+
+fn transition_agents(transition: &Transition) -> Vec<Uuid> {
+    transition
+        .meta_data
+        .iter()
+        .filter(|d| d.tag() == DataTag::Agent)
+        .filter_map(|d| d.id())
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+impl Job {
+    // Units of each product required per period. Without a demand model, one of each product.
+    pub fn required_units(&self) -> HashMap<Uuid, f64> {
+        match &self.demand {
+            Some(demand) => demand.required(),
+            None => self
+                .targets
+                .iter()
+                .filter(|(_, target)| matches!(target, Target::Product { .. }))
+                .map(|(id, _)| (*id, 1.0))
+                .collect(),
+        }
+    }
+
+    // Walk backwards from the required products through the task outputs/dependencies
+    // to find how many times each task has to fire. Fails if the dependencies never bottom out.
+    pub fn task_firings(&self, required: &HashMap<Uuid, f64>) -> Result<HashMap<Uuid, f64>, String> {
+        let mut firings: HashMap<Uuid, f64> = HashMap::new();
+        let mut pending: Vec<(Uuid, f64)> = required.iter().map(|(k, v)| (*k, *v)).collect();
+        let mut iterations = 0;
+
+        while let Some((target_id, units)) = pending.pop() {
+            iterations += 1;
+            if iterations > FIRING_WALK_LIMIT {
+                return Err("The task dependencies form a cycle, so the task firings cannot be found".into());
+            }
+            if units <= 0.0 {
+                continue;
+            }
+            match self.targets.get(&target_id) {
+                Some(Target::Precursor { .. }) | Some(Target::Reusable { .. }) | None => continue,
+                _ => {}
+            }
+            let producer = self
+                .tasks
+                .values()
+                .sorted_by_key(|task| task.order)
+                .find(|task| task.output_target_count(&target_id) > 0);
+            if let Some(task) = producer {
+                let task_firings = units / task.output_target_count(&target_id) as f64;
+                *firings.entry(task.id).or_insert(0.0) += task_firings;
+                for (dependency, count) in task.dependencies.iter() {
+                    pending.push((*dependency, task_firings * *count as f64));
+                }
+            }
+        }
+        Ok(firings)
+    }
+
+    // Every combination of the task allocations left in the cost net
+    pub fn candidate_allocations(&self) -> Vec<Allocation> {
+        let cost_net = match &self.cost_net {
+            Some(net) => net,
+            None => return vec![],
+        };
+
+        let mut task_options: Vec<(Uuid, Vec<Vec<Uuid>>)> = vec![];
+        for task_id in self.tasks.keys().sorted() {
+            let options: Vec<Vec<Uuid>> = cost_net
+                .query_transitions(&vec![
+                    Query::Data(Data::Decide),
+                    Query::Data(Data::Task(*task_id)),
+                ])
+                .iter()
+                .map(|t| transition_agents(t))
+                .sorted()
+                .dedup()
+                .collect();
+            if options.is_empty() {
+                return vec![];
+            }
+            task_options.push((*task_id, options));
+        }
+
+        task_options
+            .iter()
+            .map(|(task_id, options)| options.iter().map(move |o| (*task_id, o.clone())))
+            .multi_cartesian_product()
+            .map(|assignments| {
+                let mut allocation = Allocation::new();
+                for (task_id, agents) in assignments {
+                    allocation.assign(task_id, agents);
+                }
+                allocation
+            })
+            .collect()
+    }
+
+    pub fn evaluate_allocation(&self, allocation: &Allocation) -> AllocationEvaluation {
+        let required = self.required_units();
+        let firings = self.task_firings(&required);
+        let mut evaluation = AllocationEvaluation {
+            allocation: allocation.clone(),
+            feasible: true,
+            tasks: vec![],
+            period: 0.0,
+            agent_busy_time: allocation.agents().iter().map(|a| (*a, 0.0)).collect(),
+            cycle_time: 0.0,
+            required: required.clone(),
+            produced: HashMap::new(),
+            shortfall: HashMap::new(),
+            produced_value: 0.0,
+            once_cost: 0.0,
+            integration: HashMap::new(),
+            extrapolated_monetary_cost: 0.0,
+            extrapolated_ergonomic_cost: 0.0,
+            idle_energy_cost: 0.0,
+            score: 0.0,
+        };
+
+        let cost_net = match &self.cost_net {
+            Some(net) => net,
+            None => {
+                evaluation.feasible = false;
+                return evaluation;
+            }
+        };
+        let firings = match firings {
+            Ok(firings) => firings,
+            Err(_) => {
+                evaluation.feasible = false;
+                return evaluation;
+            }
+        };
+
+        let mut chosen_transitions: Vec<&Transition> = vec![];
+        for task_id in self.tasks.keys().sorted() {
+            let agents = match allocation.tasks.get(task_id) {
+                Some(agents) => agents,
+                None => {
+                    evaluation.feasible = false;
+                    continue;
+                }
+            };
+
+            // Of the realizations of this task by these agents, pick the cheapest by the job weights
+            let chosen = cost_net
+                .query_transitions(&vec![Query::Data(Data::Task(*task_id))])
+                .into_iter()
+                .filter(|t| !t.has_data(&vec![Query::Data(Data::Decide)]))
+                .filter(|t| transition_agents(t) == *agents)
+                .min_by(|a, b| {
+                    self.weighted_cost(&a.cost)
+                        .partial_cmp(&self.weighted_cost(&b.cost))
+                        .unwrap_or(Ordering::Equal)
+                        .then(a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal))
+                });
+
+            let transition = match chosen {
+                Some(t) => t,
+                None => {
+                    evaluation.feasible = false;
+                    continue;
+                }
+            };
+
+            let task_firings = *firings.get(task_id).unwrap_or(&0.0);
+            for agent in agents.iter() {
+                *evaluation.agent_busy_time.entry(*agent).or_insert(0.0) +=
+                    task_firings * transition.time;
+            }
+            evaluation.extrapolated_monetary_cost += task_firings
+                * sum_costs(&transition.cost, CostFrequency::Extrapolated, CostCategory::Monetary);
+            evaluation.extrapolated_ergonomic_cost += task_firings
+                * sum_costs(&transition.cost, CostFrequency::Extrapolated, CostCategory::Ergonomic);
+            evaluation.tasks.push(TaskEvaluation {
+                task: *task_id,
+                agents: agents.clone(),
+                transition: transition.id,
+                time: transition.time,
+                firings: task_firings,
+            });
+            chosen_transitions.push(transition);
+        }
+
+        evaluation.once_cost = sum_costs(
+            &self.allocation_once_costs(allocation, &chosen_transitions),
+            CostFrequency::Once,
+            CostCategory::Monetary,
+        );
+        for agent in allocation.agents() {
+            if let Some(Agent::Robot(robot)) = self.agents.get(&agent) {
+                evaluation
+                    .integration
+                    .insert(agent, self.integration_bom(robot, &chosen_transitions));
+            }
+        }
+
+        evaluation.cycle_time = evaluation
+            .agent_busy_time
+            .values()
+            .cloned()
+            .fold(0.0, f64::max);
+        evaluation.period = match &self.demand {
+            Some(demand) => demand.period,
+            None => evaluation.cycle_time,
+        };
+
+        for agent in allocation.agents() {
+            if let Some(Agent::Robot(robot)) = self.agents.get(&agent) {
+                let idle = evaluation.period.max(evaluation.cycle_time) - evaluation.agent_busy_time[&agent];
+                evaluation.idle_energy_cost += kwh(robot.standby_power(), idle.max(0.0)) * self.shift_kwh_cost();
+            }
+        }
+        evaluation.extrapolated_monetary_cost += evaluation.idle_energy_cost;
+
+        // If the bottleneck agent is overloaded, only a fraction of the demand is produced
+        let achievable = if !evaluation.feasible {
+            0.0
+        } else if evaluation.cycle_time > evaluation.period && evaluation.cycle_time > 0.0 {
+            evaluation.period / evaluation.cycle_time
+        } else {
+            1.0
+        };
+        for (target_id, units) in required.iter() {
+            let produced = units * achievable;
+            evaluation.produced.insert(*target_id, produced);
+            if units - produced > SHORTFALL_TOLERANCE {
+                evaluation.shortfall.insert(*target_id, units - produced);
+            }
+            if let Some(target) = self.targets.get(target_id) {
+                evaluation.produced_value += produced * target.value();
+            }
+        }
+
+        evaluation.score = self.weights.monetary
+            * (evaluation.once_cost + evaluation.extrapolated_monetary_cost)
+            + self.weights.ergonomic * evaluation.extrapolated_ergonomic_cost;
+
+        evaluation
+    }
+
+    // One-time costs of taking on the agents of the allocation, given the transitions chosen for its tasks
+    pub fn allocation_once_costs(&self, allocation: &Allocation, chosen: &Vec<&Transition>) -> CostSet {
+        let mut costs = CostSet::new();
+        for agent in allocation.agents() {
+            // Robots are only integrated for the tasks they were given, not everything they could do
+            if let Some(Agent::Robot(robot)) = self.agents.get(&agent) {
+                costs.extend(self.robot_integration_costs(robot, chosen));
+            }
+            if let Some(cost_net) = &self.cost_net {
+                for transition in cost_net.query_transitions(&vec![Query::Data(Data::AgentAdd(agent))]) {
+                    costs.extend(transition.cost.iter().cloned());
+                }
+            }
+        }
+        costs
+    }
+
+    // Evaluate every candidate allocation, ordered so that allocations meeting the demand come first,
+    // then by the smallest shortfall, then by the weighted cost.
+    pub fn search_allocations(&self) -> Vec<AllocationEvaluation> {
+        let mut evaluations: Vec<AllocationEvaluation> = self
+            .candidate_allocations()
+            .iter()
+            .map(|allocation| self.evaluate_allocation(allocation))
+            .collect();
+        evaluations.sort_by(compare_evaluations);
+        evaluations
+    }
+
+    pub fn weighted_cost(&self, costs: &CostSet) -> f64 {
+        costs
+            .iter()
+            .map(|c| match c.category {
+                CostCategory::Monetary => self.weights.monetary * c.value,
+                CostCategory::Ergonomic => self.weights.ergonomic * c.value,
+            })
+            .sum()
+    }
+}
+
+pub fn compare_evaluations(a: &AllocationEvaluation, b: &AllocationEvaluation) -> Ordering {
+    b.meets_demand()
+        .cmp(&a.meets_demand())
+        .then(b.feasible.cmp(&a.feasible))
+        .then(
+            a.total_shortfall()
+                .partial_cmp(&b.total_shortfall())
+                .unwrap_or(Ordering::Equal),
+        )
+        .then(a.score.partial_cmp(&b.score).unwrap_or(Ordering::Equal))
+}
+
+#[test]
+fn task_firings_test() {
+    use crate::description::rating::Rating;
+
+    let mut job = Job::new("Firings".into(), 0.1);
+    let part = job.create_precursor_target("Part".into(), 1.0, 1.0, Rating::High, vec![], 1.0);
+    let sub = job.create_intermediate_target("Sub".into(), 1.0, 1.0, Rating::High, vec![]);
+    let product = job.create_product_target("Product".into(), 1.0, 1.0, Rating::High, vec![], 10.0);
+    let t1 = job.create_task("t1".into(), 1);
+    let t2 = job.create_task("t2".into(), 2);
+    job.add_task_dependency(t1, part, 1);
+    job.add_task_output(t1, sub, 2);
+    job.add_task_dependency(t2, sub, 3);
+    job.add_task_output(t2, product, 1);
+
+    let firings = job.task_firings(&job.required_units()).unwrap();
+    assert!((firings.get(&t2).unwrap() - 1.0).abs() < f64::EPSILON);
+    assert!((firings.get(&t1).unwrap() - 1.5).abs() < f64::EPSILON);
+
+    // A demand without a period can't be rescaled, so it is refused
+    assert!(job.add_product_demand(product, 40.0, 1.0, 0.0).is_err());
+    assert!(job.demand.is_none());
+    job.add_product_demand(product, 40.0, 1.0, 3600.0).unwrap();
+    let firings = job.task_firings(&job.required_units()).unwrap();
+    assert!((firings.get(&t2).unwrap() - 40.0).abs() < f64::EPSILON);
+    assert!((firings.get(&t1).unwrap() - 60.0).abs() < f64::EPSILON);
+
+    // Demand given over a shift is rescaled to the job's hourly period
+    let other = job.create_product_target("Other".into(), 1.0, 1.0, Rating::High, vec![], 10.0);
+    job.add_product_demand(other, 80.0, 1.0, 8.0 * 3600.0).unwrap();
+    assert!((job.required_units()[&other] - 10.0).abs() < 1e-9);
+
+    // A task consuming its own output never bottoms out
+    let t3 = job.create_task("t3".into(), 3);
+    job.add_task_dependency(t3, other, 1);
+    job.add_task_output(t3, other, 1);
+    assert!(job.task_firings(&job.required_units()).is_err());
+}
@@ -0,0 +1,122 @@
+use crate::analysis::allocation::AllocationEvaluation;
+use crate::description::agent::Agent;
+use crate::description::energy::kwh;
+use crate::description::job::Job;
+use crate::description::units::{Time, USD};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentEnergy {
+    pub active_time: Time,
+    pub idle_time: Time,
+    pub active_kwh: f64,
+    pub idle_kwh: f64,
+    pub cost: USD,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftEnergy {
+    pub cycles: usize,
+    // Robot UUID -> what it drew over the shift
+    pub agents: HashMap<Uuid, AgentEnergy>,
+    pub kwh: f64,
+    pub cost: USD,
+}
+
+impl Job {
+    // Run the allocation's cycle back to back over the shift. In each cycle every robot works for
+    // its busy time and then stands by until the bottleneck agent finishes (or the period ends).
+    pub fn simulate_shift_energy(&self, evaluation: &AllocationEvaluation) -> ShiftEnergy {
+        let cycle = evaluation.period.max(evaluation.cycle_time);
+        let mut energy = ShiftEnergy {
+            cycles: 0,
+            agents: HashMap::new(),
+            kwh: 0.0,
+            cost: 0.0,
+        };
+        let robots: Vec<_> = evaluation
+            .allocation
+            .agents()
+            .iter()
+            .filter_map(|id| match self.agents.get(id) {
+                Some(Agent::Robot(robot)) => Some(robot),
+                _ => None,
+            })
+            .sorted_by_key(|robot| robot.id)
+            .collect();
+        for robot in robots.iter() {
+            energy.agents.insert(robot.id, AgentEnergy::default());
+        }
+        if cycle <= 0.0 || robots.is_empty() {
+            return energy;
+        }
+
+        let end = self.shift.start + self.shift.length;
+        let mut time = self.shift.start;
+        while time < end {
+            let length = cycle.min(end - time);
+            for robot in robots.iter() {
+                let busy = evaluation.agent_busy_time.get(&robot.id).unwrap_or(&0.0).min(length);
+                let record = energy.agents.get_mut(&robot.id).unwrap();
+                record.active_time += busy;
+                record.idle_time += length - busy;
+                record.active_kwh += kwh(robot.energy_consumption, busy);
+                record.idle_kwh += kwh(robot.standby_power(), length - busy);
+                record.cost += self.energy_cost(robot.energy_consumption, time, busy)
+                    + self.energy_cost(robot.standby_power(), time + busy, length - busy);
+            }
+            energy.cycles += 1;
+            time += length;
+        }
+
+        energy.kwh = energy.agents.values().map(|a| a.active_kwh + a.idle_kwh).sum();
+        energy.cost = energy.agents.values().map(|a| a.cost).sum();
+        energy
+    }
+}
+
+#[test]
+fn simulate_shift_energy_test() {
+    use crate::analysis::allocation::Allocation;
+    use crate::analysis::fixtures::empty_evaluation;
+    use crate::constants::SEC_PER_HOUR;
+    use crate::description::energy::Tariff;
+    use crate::description::rating::Rating;
+
+    let mut job = Job::new("Shift".into(), 0.1);
+    let robot = job.create_robot_agent(
+        "Arm".into(), 0.8, 0.0, 3.0, Rating::Medium, 1.0, 0.001, Rating::High, 0.0, 20000.0, 1000.0, 1000.0,
+    );
+    job.set_robot_idle_power(robot, 200.0);
+    let mut allocation = Allocation::new();
+    allocation.tasks.insert(Uuid::new_v4(), vec![robot]);
+    // Busy for half of each one-hour cycle
+    let evaluation = AllocationEvaluation {
+        agent_busy_time: HashMap::from([(robot, SEC_PER_HOUR / 2.0)]),
+        cycle_time: SEC_PER_HOUR / 2.0,
+        ..empty_evaluation(allocation, SEC_PER_HOUR)
+    };
+
+    // Eight cycles over the default 8am-4pm shift
+    let energy = job.simulate_shift_energy(&evaluation);
+    assert_eq!(energy.cycles, 8);
+    let record = &energy.agents[&robot];
+    assert_eq!(record.active_time, 4.0 * SEC_PER_HOUR);
+    assert!((record.active_kwh - 4.0).abs() < 1e-9);
+    assert!((record.idle_kwh - 0.8).abs() < 1e-9);
+    assert!((energy.kwh - 4.8).abs() < 1e-9);
+    assert!((energy.cost - 0.48).abs() < 1e-9);
+
+    // Afternoon peak prices only apply to the energy drawn after noon
+    let mut tariff = Tariff::default();
+    tariff.add_period(12.0 * SEC_PER_HOUR, 18.0 * SEC_PER_HOUR, 0.3);
+    job.set_energy_tariff(tariff);
+    let peak = job.simulate_shift_energy(&evaluation);
+    assert_eq!(peak.kwh, energy.kwh);
+    assert!((peak.cost - (2.4 * 0.1 + 2.4 * 0.3)).abs() < 1e-9);
+}
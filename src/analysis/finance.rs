@@ -0,0 +1,211 @@
+use crate::analysis::allocation::{Allocation, AllocationEvaluation};
+use crate::description::agent::Agent;
+use crate::description::job::Job;
+use crate::description::units::USD;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinancialEvaluation {
+    pub allocation: Allocation,
+    // Net cash flow of each year, starting with the investment in year zero
+    pub cash_flows: Vec<USD>,
+    pub depreciation: Vec<USD>,
+    pub maintenance: USD, // per year
+    pub salvage: USD,
+    pub npv: USD,
+    pub irr: Option<f64>,
+    pub payback: Option<f64>, // years
+}
+
+pub fn npv(rate: f64, cash_flows: &[USD]) -> USD {
+    cash_flows
+        .iter()
+        .enumerate()
+        .map(|(year, flow)| flow / (1.0 + rate).powi(year as i32))
+        .sum()
+}
+
+// Discount rate at which the NPV is zero, found by bisection. None if the NPV does not change sign.
+pub fn irr(cash_flows: &[USD]) -> Option<f64> {
+    let (mut low, mut high) = (-0.99, 1.0);
+    let npv_low = npv(low, cash_flows);
+    // Very quick returns have very high rates, so widen the bracket until it holds a root
+    while npv(high, cash_flows).signum() == npv_low.signum() {
+        if high > 1e6 {
+            return None;
+        }
+        high *= 2.0;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        let npv_mid = npv(mid, cash_flows);
+        if npv_mid.signum() == npv_low.signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        if high - low < 1e-10 {
+            break;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+// Years until the cumulative (undiscounted) cash flow turns positive, interpolated within the year
+pub fn payback(cash_flows: &[USD]) -> Option<f64> {
+    let mut cumulative = 0.0;
+    for (year, flow) in cash_flows.iter().enumerate() {
+        let previous = cumulative;
+        cumulative += flow;
+        if cumulative >= 0.0 && year > 0 && previous < 0.0 {
+            return Some(year as f64 - 1.0 + -previous / flow);
+        }
+    }
+    None
+}
+
+impl Job {
+    // Yearly cash flows of running the allocation over the robots' lifetime
+    pub fn evaluate_finances(&self, evaluation: &AllocationEvaluation) -> FinancialEvaluation {
+        let config = &self.finance;
+        let periods_per_year = if evaluation.period > 0.0 {
+            config.operating_hours * 3600.0 / evaluation.period
+        } else {
+            0.0
+        };
+        let robots: Vec<_> = evaluation
+            .allocation
+            .agents()
+            .iter()
+            .filter_map(|id| match self.agents.get(id) {
+                Some(Agent::Robot(robot)) => Some(robot),
+                _ => None,
+            })
+            .collect();
+
+        // Robots and their integration are capitalized, anything else is expensed up front
+        let basis: USD = robots
+            .iter()
+            .map(|robot| {
+                robot.purchase_price
+                    + evaluation
+                        .integration
+                        .get(&robot.id)
+                        .map_or(0.0, |bom| bom.total())
+            })
+            .sum();
+        let maintenance: USD = robots.iter().map(|robot| robot.annual_maintenance_cost).sum();
+        let salvage = basis * config.salvage_fraction;
+        let depreciation = config.depreciation.schedule(basis, salvage, config.lifetime);
+
+        let operating = periods_per_year * (evaluation.produced_value - evaluation.extrapolated_monetary_cost) - maintenance;
+        let mut cash_flows = vec![-evaluation.once_cost];
+        for (year, charge) in depreciation.iter().enumerate() {
+            // Losses are assumed to offset other income, so the tax can go negative
+            let tax = config.tax_rate * (operating - charge);
+            let mut flow = operating - tax;
+            if year == config.lifetime - 1 {
+                let book_value = basis - depreciation.iter().sum::<USD>();
+                flow += salvage - config.tax_rate * (salvage - book_value);
+            }
+            cash_flows.push(flow);
+        }
+
+        FinancialEvaluation {
+            allocation: evaluation.allocation.clone(),
+            npv: npv(config.discount_rate, &cash_flows),
+            irr: irr(&cash_flows),
+            payback: payback(&cash_flows),
+            cash_flows,
+            depreciation,
+            maintenance,
+            salvage,
+        }
+    }
+
+    // Finances of every candidate allocation, in the order of the allocation search
+    pub fn search_finances(&self) -> Vec<FinancialEvaluation> {
+        self.search_allocations()
+            .iter()
+            .map(|evaluation| self.evaluate_finances(evaluation))
+            .collect()
+    }
+}
+
+#[test]
+fn finance_metrics_test() {
+    use crate::description::finance::Depreciation;
+
+    let flows = vec![-1000.0, 400.0, 400.0, 400.0];
+    assert!((npv(0.0, &flows) - 200.0).abs() < 1e-9);
+    let rate = irr(&flows).unwrap();
+    assert!(npv(rate, &flows).abs() < 1e-6);
+    assert!((rate - 0.0970).abs() < 1e-3);
+    assert!((payback(&flows).unwrap() - 2.5).abs() < 1e-9);
+    assert_eq!(payback(&vec![-1000.0, 100.0, 100.0]), None);
+    assert_eq!(irr(&vec![100.0, 100.0]), None);
+
+    let straight = Depreciation::StraightLine.schedule(1100.0, 100.0, 4);
+    assert_eq!(straight, vec![250.0; 4]);
+    let declining = Depreciation::DecliningBalance { rate: 2.0 }.schedule(1100.0, 100.0, 4);
+    assert!(declining[0] > declining[3]);
+    assert!((declining.iter().sum::<f64>() - 1000.0).abs() < 1e-9);
+}
+
+#[test]
+fn evaluate_finances_test() {
+    use crate::analysis::fixtures::empty_evaluation;
+    use crate::description::finance::{Depreciation, FinanceConfig};
+    use crate::description::integration::{IntegrationBom, IntegrationItem, IntegrationLine};
+    use crate::description::rating::Rating;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    let mut job = Job::new("Finances".into(), 0.1);
+    let robot = job.create_robot_agent(
+        "Arm".into(), 0.8, 0.0, 3.0, Rating::Medium, 1.0, 0.001, Rating::High, 0.0, 20000.0, 300.0, 1000.0,
+    );
+    job.set_finance_config(FinanceConfig {
+        lifetime: 4,
+        discount_rate: 0.1,
+        operating_hours: 1000.0,
+        depreciation: Depreciation::StraightLine,
+        salvage_fraction: 0.1,
+        tax_rate: 0.25,
+    });
+    let mut allocation = Allocation::new();
+    allocation.tasks.insert(Uuid::new_v4(), vec![robot]);
+    let bom = IntegrationBom {
+        lines: vec![IntegrationLine {
+            item: IntegrationItem::Programming,
+            description: "Programming".into(),
+            cost: 5000.0,
+        }],
+    };
+    let evaluation = AllocationEvaluation {
+        cycle_time: 3600.0,
+        produced_value: 10.0,
+        once_cost: 25000.0,
+        integration: HashMap::from([(robot, bom)]),
+        extrapolated_monetary_cost: 4.0,
+        ..empty_evaluation(allocation, 3600.0)
+    };
+
+    let finances = job.evaluate_finances(&evaluation);
+    // 1000 one-hour periods a year, less maintenance
+    let operating = 1000.0 * 6.0 - 1000.0;
+    // The purchase and integration are written down to the salvage value
+    assert_eq!(finances.salvage, 2500.0);
+    assert_eq!(finances.depreciation, vec![5625.0; 4]);
+    assert_eq!(finances.cash_flows.len(), 5);
+    assert_eq!(finances.cash_flows[0], -25000.0);
+    let yearly = operating - 0.25 * (operating - 5625.0);
+    assert!((finances.cash_flows[1] - yearly).abs() < 1e-9);
+    // Sold at book value in the last year, so the salvage is untaxed
+    assert!((finances.cash_flows[4] - yearly - 2500.0).abs() < 1e-9);
+    assert!((finances.npv - npv(0.1, &finances.cash_flows)).abs() < 1e-9);
+    assert!(finances.npv < 0.0);
+    assert!(finances.irr.unwrap() < 0.1);
+    assert_eq!(finances.payback, None);
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::analysis::allocation::{Allocation, AllocationEvaluation};
+use crate::description::anthropometry::Anthropometry;
+use crate::description::gender::Gender;
+use crate::description::job::Job;
+use crate::description::primitive::Primitive;
+use crate::description::rating::Rating;
+use crate::description::units::Time;
+
+// A job with a bench at hand height in front of where its workers stand. Returns the bench.
+pub fn bench_job(name: &str) -> (Job, Uuid) {
+    let mut job = Job::new(name.into(), 0.1);
+    job.create_standing_point_of_interest("Stand".into(), 0.0, 0.0, 0.0, None, None);
+    let bench = job.create_hand_point_of_interest("Bench".into(), 0.3, 0.0, 0.9, None, None);
+    (job, bench)
+}
+
+// A worker who can reach the bench, differing from the others only in their wage
+pub fn add_worker(job: &mut Job, name: &str, wage: f64) -> Uuid {
+    let measured = Anthropometry {
+        reach: Some(0.9),
+        ..Default::default()
+    };
+    job.create_human_agent_with_anthropometry(name.into(), 30.0, Gender::Male, Rating::High, wage, 500.0, measured)
+}
+
+// An arm that can reach the bench
+pub fn add_arm(job: &mut Job) -> Uuid {
+    job.create_robot_agent(
+        "Arm".into(), 1.2, 0.0, 3.0, Rating::Medium, 1.0, 0.001, Rating::High, 0.0, 20000.0, 300.0, 1000.0,
+    )
+}
+
+// A task positioning a part into a product at the bench. Returns the part, product and task.
+pub fn add_assembly(job: &mut Job, bench: Uuid) -> (Uuid, Uuid, Uuid) {
+    let part = job.create_precursor_target("Part".into(), 0.1, 1.0, Rating::High, vec![bench], 5.0);
+    let product = job.create_product_target("Product".into(), 0.1, 1.0, Rating::High, vec![bench], 10.0);
+    let task = job.create_task("Assemble".into(), 1);
+    job.add_task_dependency(task, part, 1);
+    job.add_task_output(task, product, 1);
+    job.add_task_primitive(task, Primitive::new_position(part, 90.0, 0.01));
+    (part, product, task)
+}
+
+// An evaluation of the allocation that does no work and costs nothing, to fill in as needed
+pub fn empty_evaluation(allocation: Allocation, period: Time) -> AllocationEvaluation {
+    AllocationEvaluation {
+        allocation,
+        feasible: true,
+        tasks: vec![],
+        period,
+        agent_busy_time: HashMap::new(),
+        cycle_time: 0.0,
+        required: HashMap::new(),
+        produced: HashMap::new(),
+        shortfall: HashMap::new(),
+        produced_value: 0.0,
+        once_cost: 0.0,
+        integration: HashMap::new(),
+        extrapolated_monetary_cost: 0.0,
+        extrapolated_ergonomic_cost: 0.0,
+        idle_energy_cost: 0.0,
+        score: 0.0,
+    }
+}
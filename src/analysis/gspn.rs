@@ -0,0 +1,223 @@
+use crate::analysis::allocation::Allocation;
+use crate::description::job::Job;
+use crate::description::target::Target;
+use crate::description::units::TokenCount;
+use crate::petri::data::{Data, Query};
+use crate::petri::net::PetriNet;
+use crate::petri::place::Place;
+use crate::petri::token::TokenSet;
+use crate::petri::transition::{Signature, Timing, Transition};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Tangible markings explored before the analysis gives up
+const MAX_STATES: usize = 100000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GspnAnalysis {
+    pub states: usize,
+    // Product target UUID -> units per second
+    pub throughput: HashMap<Uuid, f64>,
+    // Agent UUID -> share of the time working on a task or blocked holding a finished one
+    pub utilization: HashMap<Uuid, f64>,
+    // Intermediate target UUID -> mean units waiting in its buffer
+    pub wip: HashMap<Uuid, f64>,
+    // Task UUID -> completions per second
+    pub task_rates: HashMap<Uuid, f64>,
+}
+
+impl Job {
+    // Net of the allocation running continuously: precursors are always available, intermediates wait
+    // in buffers with room for `wip_limit` units, and each agent works on one task at a time. A task
+    // starts immediately once its agents and inputs are free and there is room for its outputs, and
+    // finishes with the timing of the transition chosen for it by the allocation evaluation.
+    pub fn allocation_gspn(&self, allocation: &Allocation, wip_limit: TokenCount) -> Result<PetriNet, String> {
+        let evaluation = self.evaluate_allocation(allocation);
+        if !evaluation.feasible {
+            return Err("The allocation leaves some task without a transition".into());
+        }
+        let cost_net = self.cost_net.as_ref().ok_or("The job has no cost net")?;
+
+        let mut net = PetriNet::new(format!("{} (GSPN)", self.name));
+        let mut agent_places: HashMap<Uuid, Uuid> = HashMap::new();
+        for agent in allocation.agents() {
+            let name = self.agents.get(&agent).map_or("Agent".into(), |a| a.name());
+            let place = Place::new(format!("Agent: {}", name), TokenSet::Finite, vec![Data::Agent(agent)]);
+            agent_places.insert(agent, place.id);
+            net.initial_marking.insert(place.id, 1);
+            net.places.insert(place.id, place);
+        }
+        let mut target_places: HashMap<Uuid, Uuid> = HashMap::new();
+        // Intermediate target UUID -> place holding the free room in its buffer
+        let mut room_places: HashMap<Uuid, Uuid> = HashMap::new();
+        for target in self.targets.values().sorted_by_key(|target| target.id()) {
+            let tokens = match target {
+                Target::Precursor { .. } | Target::Reusable { .. } => TokenSet::Infinite,
+                Target::Intermediate { .. } => TokenSet::Finite,
+                Target::Product { .. } => TokenSet::Sink,
+            };
+            let place = Place::new(format!("Target: {}", target.name()), tokens, vec![Data::Target(target.id())]);
+            target_places.insert(target.id(), place.id);
+            net.initial_marking.insert(place.id, 0);
+            net.places.insert(place.id, place);
+            if matches!(target, Target::Intermediate { .. }) {
+                let room = Place::new(format!("Target: {} (room)", target.name()), TokenSet::Finite, vec![]);
+                room_places.insert(target.id(), room.id);
+                net.initial_marking.insert(room.id, wip_limit);
+                net.places.insert(room.id, room);
+            }
+        }
+
+        for task_evaluation in evaluation.tasks.iter() {
+            let task = &self.tasks[&task_evaluation.task];
+            let chosen = &cost_net.transitions[&task_evaluation.transition];
+            let busy = Place::new(format!("Task: {}", task.name), TokenSet::Finite, vec![Data::Task(task.id)]);
+            let busy_id = busy.id;
+            net.initial_marking.insert(busy_id, 0);
+            net.places.insert(busy_id, busy);
+
+            let agents: HashMap<Uuid, Signature> = task_evaluation
+                .agents
+                .iter()
+                .map(|agent| (agent_places[agent], Signature::Static(1)))
+                .collect();
+            // Starting takes the inputs out of their buffers, freeing room, and reserves room for the outputs
+            let mut inputs = agents.clone();
+            let mut reserved: HashMap<Uuid, Signature> = vec![(busy_id, Signature::Static(1))].into_iter().collect();
+            for (dependency, count) in task.dependencies.iter() {
+                if let Some(place) = target_places.get(dependency) {
+                    inputs.insert(*place, Signature::Static(*count));
+                }
+                if let Some(room) = room_places.get(dependency) {
+                    reserved.insert(*room, Signature::Static(*count));
+                }
+            }
+            let mut outputs = agents;
+            for (output, count) in task.output.iter() {
+                if let Some(place) = target_places.get(output) {
+                    outputs.insert(*place, Signature::Static(*count));
+                }
+                if let Some(room) = room_places.get(output) {
+                    inputs.insert(*room, Signature::Static(*count));
+                }
+            }
+
+            let mut start = Transition::new(
+                format!("Start: {}", task.name),
+                inputs,
+                reserved,
+                vec![Data::Task(task.id)],
+                0.0,
+                vec![],
+            );
+            start.set_timing(Timing::Immediate { weight: 1.0 });
+            net.transitions.insert(start.id, start);
+
+            let mut finish = Transition::new(
+                format!("Finish: {}", task.name),
+                vec![(busy_id, Signature::Static(1))].into_iter().collect(),
+                outputs,
+                vec![Data::Task(task.id), Data::Simulation],
+                chosen.time,
+                vec![],
+            );
+            finish.set_timing(chosen.timing());
+            net.transitions.insert(finish.id, finish);
+        }
+        Ok(net)
+    }
+
+    // Steady-state throughput, utilization and work in progress of the allocation running continuously
+    pub fn analyze_allocation_gspn(
+        &self,
+        allocation: &Allocation,
+        wip_limit: TokenCount,
+    ) -> Result<GspnAnalysis, String> {
+        let net = self.allocation_gspn(allocation, wip_limit)?;
+        let steady = net.steady_state(&HashMap::new(), MAX_STATES)?;
+
+        let mut analysis = GspnAnalysis {
+            states: steady.states,
+            throughput: HashMap::new(),
+            utilization: HashMap::new(),
+            wip: HashMap::new(),
+            task_rates: HashMap::new(),
+        };
+        for transition in net.query_transitions(&vec![Query::Data(Data::Simulation)]) {
+            let task = match transition.meta_data.iter().find_map(|d| match d {
+                Data::Task(task) => Some(*task),
+                _ => None,
+            }) {
+                Some(task) => task,
+                None => continue,
+            };
+            let rate = steady.throughput[&transition.id];
+            analysis.task_rates.insert(task, rate);
+            for (output, count) in self.tasks[&task].output.iter() {
+                if let Some(Target::Product { .. }) = self.targets.get(output) {
+                    *analysis.throughput.entry(*output).or_insert(0.0) += rate * *count as f64;
+                }
+            }
+        }
+        for place in net.places.values() {
+            for data in place.meta_data.iter() {
+                match data {
+                    Data::Agent(agent) => {
+                        analysis.utilization.insert(*agent, 1.0 - steady.mean_tokens[&place.id]);
+                    }
+                    Data::Target(target) if matches!(self.targets.get(target), Some(Target::Intermediate { .. })) => {
+                        analysis.wip.insert(*target, steady.mean_tokens[&place.id]);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(analysis)
+    }
+}
+
+#[test]
+fn allocation_gspn_test() {
+    use crate::analysis::fixtures::{add_worker, bench_job};
+    use crate::description::primitive::Primitive;
+    use crate::description::rating::Rating;
+
+    // Two workers in a line, with room for one part between them
+    let (mut job, bench) = bench_job("Line");
+    let first = add_worker(&mut job, "First", 24.0);
+    let second = add_worker(&mut job, "Second", 24.0);
+    let part = job.create_precursor_target("Part".into(), 0.1, 1.0, Rating::High, vec![bench], 5.0);
+    let sub = job.create_intermediate_target("Sub".into(), 0.1, 1.0, Rating::High, vec![bench]);
+    let product = job.create_product_target("Product".into(), 0.1, 1.0, Rating::High, vec![bench], 10.0);
+    let assemble = job.create_task("Assemble".into(), 1);
+    job.add_task_dependency(assemble, part, 1);
+    job.add_task_output(assemble, sub, 1);
+    job.add_task_primitive(assemble, Primitive::new_position(part, 90.0, 0.01));
+    let finish = job.create_task("Finish".into(), 2);
+    job.add_task_dependency(finish, sub, 1);
+    job.add_task_output(finish, product, 1);
+    job.add_task_primitive(finish, Primitive::new_position(sub, 90.0, 0.001));
+    job.create_petri_nets();
+
+    let mut allocation = Allocation::new();
+    allocation.tasks.insert(assemble, vec![first]);
+    allocation.tasks.insert(finish, vec![second]);
+    let evaluation = job.evaluate_allocation(&allocation);
+    let rate = |task: Uuid| 1.0 / evaluation.tasks.iter().find(|t| t.task == task).unwrap().time;
+    let (a, b) = (rate(assemble), rate(finish));
+
+    // The first worker holds room for its part while working, so the line is in one of three states:
+    // only the first working, both working, or the first blocked on a full buffer while the second works.
+    // Balancing the flows between them gives p1 a = p2 b and p3 b = p2 a.
+    let analysis = job.analyze_allocation_gspn(&allocation, 1).unwrap();
+    assert_eq!(analysis.states, 3);
+    let p2 = 1.0 / (1.0 + b / a + a / b);
+    let throughput = p2 * (a + b);
+    assert!((analysis.throughput[&product] - throughput).abs() < 1e-9);
+    assert!((analysis.task_rates[&assemble] - throughput).abs() < 1e-9);
+    assert!((analysis.utilization[&second] - throughput / b).abs() < 1e-9);
+    assert!((analysis.wip[&sub] - p2 * a / b).abs() < 1e-9);
+}
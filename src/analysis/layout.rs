@@ -0,0 +1,189 @@
+use crate::analysis::allocation::{compare_evaluations, Allocation, AllocationEvaluation};
+use crate::description::job::Job;
+use itertools::Itertools;
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Smallest step (meters) the search refines down to
+const MIN_STEP: f64 = 0.01;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutOptimization {
+    pub allocation: Allocation,
+    // Movable POI UUID -> position in the improved layout
+    pub positions: HashMap<Uuid, Vector3<f64>>,
+    pub initial: AllocationEvaluation,
+    pub optimized: AllocationEvaluation,
+    // Change in weighted cost, negative when the layout improved
+    pub cost_delta: f64,
+    pub evaluations: usize,
+}
+
+impl Job {
+    pub fn movable_points_of_interest(&self) -> Vec<Uuid> {
+        self.points_of_interest
+            .iter()
+            .filter(|(_, poi)| poi.location().movable.is_some())
+            .map(|(id, _)| *id)
+            .sorted()
+            .collect()
+    }
+
+    pub fn apply_layout(&mut self, positions: &HashMap<Uuid, Vector3<f64>>) {
+        for (poi, position) in positions.iter() {
+            self.set_point_of_interest_position(*poi, *position);
+        }
+    }
+
+    // Rebuild the nets for a copy of the job with the POIs moved, and evaluate the allocation on it.
+    // None if the move makes POI volumes collide.
+    pub fn evaluate_layout(
+        &self,
+        allocation: &Allocation,
+        positions: &HashMap<Uuid, Vector3<f64>>,
+    ) -> Option<AllocationEvaluation> {
+        let mut job = self.clone();
+        job.apply_layout(positions);
+        if job.layout_conflicts().len() > self.layout_conflicts().len() {
+            return None;
+        }
+        job.create_petri_nets();
+        Some(job.evaluate_allocation(allocation))
+    }
+
+    // Pattern search over the positions of the movable POIs: try a step either way along each axis,
+    // keep any move that improves the allocation, and halve the steps once nothing does.
+    pub fn optimize_layout(
+        &self,
+        allocation: &Allocation,
+        max_evaluations: usize,
+    ) -> Result<LayoutOptimization, String> {
+        let movable = self.movable_points_of_interest();
+        if movable.is_empty() {
+            return Err("No points of interest are movable".into());
+        }
+
+        let current: HashMap<Uuid, Vector3<f64>> = movable
+            .iter()
+            .map(|id| (*id, self.points_of_interest.get(id).unwrap().position()))
+            .collect();
+        let initial = match self.evaluate_layout(allocation, &current) {
+            Some(evaluation) => evaluation,
+            None => return Err("The current layout could not be evaluated".into()),
+        };
+        let mut evaluations = 1;
+
+        // Start from the current layout, pulled into the allowed ranges
+        let mut steps: HashMap<Uuid, Vector3<f64>> = HashMap::new();
+        let mut best_positions: HashMap<Uuid, Vector3<f64>> = HashMap::new();
+        for id in movable.iter() {
+            let range = self.points_of_interest.get(id).unwrap().location().movable.clone().unwrap();
+            best_positions.insert(*id, range.clamp(&current[id]));
+            steps.insert(*id, (range.max - range.min) / 4.0);
+        }
+        let mut best = if best_positions == current {
+            initial.clone()
+        } else {
+            evaluations += 1;
+            match self.evaluate_layout(allocation, &best_positions) {
+                Some(evaluation) => evaluation,
+                None => return Err("The allowed ranges put points of interest in conflict".into()),
+            }
+        };
+
+        while evaluations < max_evaluations
+            && steps.values().any(|step| step.iter().any(|s| *s >= MIN_STEP))
+        {
+            let mut improved = false;
+            for id in movable.iter() {
+                let range = self.points_of_interest.get(id).unwrap().location().movable.clone().unwrap();
+                for axis in 0..3 {
+                    let step = steps[id][axis];
+                    if step < MIN_STEP {
+                        continue;
+                    }
+                    for direction in [1.0, -1.0] {
+                        if evaluations >= max_evaluations {
+                            break;
+                        }
+                        let mut position = best_positions[id];
+                        position[axis] += direction * step;
+                        let position = range.clamp(&position);
+                        if position == best_positions[id] {
+                            continue;
+                        }
+                        let mut candidate = best_positions.clone();
+                        candidate.insert(*id, position);
+                        evaluations += 1;
+                        if let Some(evaluation) = self.evaluate_layout(allocation, &candidate) {
+                            if compare_evaluations(&evaluation, &best) == Ordering::Less {
+                                best = evaluation;
+                                best_positions = candidate;
+                                improved = true;
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            if !improved {
+                for step in steps.values_mut() {
+                    *step /= 2.0;
+                }
+            }
+        }
+
+        // Never report a layout that is worse than the one we started with
+        if compare_evaluations(&initial, &best) != Ordering::Greater {
+            best = initial.clone();
+            best_positions = current;
+        }
+
+        Ok(LayoutOptimization {
+            allocation: allocation.clone(),
+            positions: best_positions,
+            cost_delta: best.score - initial.score,
+            initial,
+            optimized: best,
+            evaluations,
+        })
+    }
+}
+
+#[test]
+fn optimize_layout_test() {
+    use crate::constants::MAX_HAND_WORK_DISTANCE;
+    use crate::description::gender::Gender;
+    use crate::description::primitive::Primitive;
+    use crate::description::rating::Rating;
+
+    let mut job = Job::new("Layout".into(), 0.1);
+    job.create_human_agent("Worker".into(), 30.0, Gender::Male, Rating::High, 24.0, 500.0);
+    job.create_standing_point_of_interest("Stand".into(), 0.0, 0.0, 0.0, None, None);
+    // Held out in front, but it may slide along x to right under the worker's hands
+    let bench = job.create_hand_point_of_interest("Bench".into(), 0.1, 0.0, 0.9, None, None);
+    job.set_point_of_interest_movable(bench, Vector3::new(-0.1, 0.0, 0.9), Vector3::new(0.4, 0.0, 0.9));
+    let part = job.create_precursor_target("Part".into(), 0.1, 4.0, Rating::High, vec![bench], 5.0);
+    let product = job.create_product_target("Product".into(), 0.1, 4.0, Rating::High, vec![bench], 10.0);
+    let task = job.create_task("Assemble".into(), 1);
+    job.add_task_dependency(task, part, 1);
+    job.add_task_output(task, product, 1);
+    job.add_task_primitive(task, Primitive::new_hold(part));
+    job.add_task_primitive(task, Primitive::new_force(part, 40.0));
+    job.create_petri_nets();
+
+    let allocation = job.search_allocations().remove(0).allocation;
+    let result = job.optimize_layout(&allocation, 40).unwrap();
+    assert!(result.cost_delta < 0.0);
+    assert!(result.optimized.score < result.initial.score);
+    assert!(result.evaluations <= 40);
+
+    let position = result.positions[&bench];
+    let range = job.points_of_interest[&bench].location().movable.clone().unwrap();
+    assert_eq!(range.clamp(&position), position);
+    assert!(position.x.abs() < MAX_HAND_WORK_DISTANCE);
+}
@@ -0,0 +1,10 @@
+pub mod allocation;
+pub mod energy;
+pub mod finance;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod gspn;
+pub mod layout;
+pub mod population;
+pub mod sensitivity;
+pub mod uncertainty;
@@ -0,0 +1,284 @@
+use crate::analysis::allocation::Allocation;
+use crate::description::agent::{Agent, CostProfiler, HumanInfo};
+use crate::description::anthropometry::{acromial_height_ratio, Measure};
+use crate::description::job::Job;
+use crate::description::poi::PointOfInterest;
+use crate::petri::data::{Data, Query};
+use crate::petri::transition::Transition;
+use itertools::Itertools;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Keeps sampled percentiles away from the infinite tails of the distributions
+const PERCENTILE_BOUND: f64 = 1e-4;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionCoverage {
+    pub transition: Uuid,
+    pub name: String,
+    pub agent: Uuid,
+    pub percent_reachable: f64,
+    pub percent_within_mvc: f64,
+    // Workers who can both reach every location and stay at or below MVC = 1
+    pub percent_capable: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PopulationCoverage {
+    pub samples: usize,
+    pub transitions: Vec<TransitionCoverage>,
+}
+
+impl PopulationCoverage {
+    // The transition the smallest share of the population can perform, if any
+    pub fn limiting(&self) -> Option<&TransitionCoverage> {
+        self.transitions.iter().min_by(|a, b| {
+            a.percent_capable
+                .partial_cmp(&b.percent_capable)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    }
+}
+
+fn sample_percentile<R: Rng>(rng: &mut R) -> f64 {
+    rng.gen_range(PERCENTILE_BOUND..1.0 - PERCENTILE_BOUND)
+}
+
+// (standing, hand) POI pairs an agent has to reach while performing the transition
+fn reach_pairs(transition: &Transition, agent: &Uuid) -> Vec<(Uuid, Uuid)> {
+    // POI meta data is keyed (poi, agent)
+    let find = |f: fn(&Data) -> Option<Uuid>| -> Option<Uuid> {
+        transition.meta_data.iter().find_map(|d| match d.secondary() {
+            Some(id) if id == *agent => f(d),
+            _ => None,
+        })
+    };
+    let standing = find(|d| match d {
+        Data::Standing(poi, _) => Some(*poi),
+        _ => None,
+    });
+    let hand = find(|d| match d {
+        Data::Hand(poi, _) => Some(*poi),
+        _ => None,
+    });
+    let from_standing = find(|d| match d {
+        Data::FromStandingPOI(poi, _) => Some(*poi),
+        _ => None,
+    });
+    let to_standing = find(|d| match d {
+        Data::ToStandingPOI(poi, _) => Some(*poi),
+        _ => None,
+    });
+    let from_hand = find(|d| match d {
+        Data::FromHandPOI(poi, _) => Some(*poi),
+        _ => None,
+    });
+    let to_hand = find(|d| match d {
+        Data::ToHandPOI(poi, _) => Some(*poi),
+        _ => None,
+    });
+
+    vec![
+        (standing, hand),
+        (from_standing.or(standing), from_hand),
+        (to_standing.or(standing), to_hand),
+    ]
+    .into_iter()
+    .filter_map(|pair| match pair {
+        (Some(s), Some(h)) => Some((s, h)),
+        _ => None,
+    })
+    .unique()
+    .collect()
+}
+
+impl Job {
+    // A worker drawn from the population table with the template's identity, gender and skill.
+    // Body dimensions share one size percentile, since stature, reach and shoulder height are
+    // strongly correlated. Weight and strength are drawn independently.
+    pub fn sample_virtual_worker<R: Rng>(&self, template: &HumanInfo, rng: &mut R) -> HumanInfo {
+        let size = sample_percentile(rng);
+        let mass = sample_percentile(rng);
+        let lookup = |measure: Measure, p: f64, fallback: f64| {
+            self.population
+                .percentile(&self.region, &template.gender, &measure, p)
+                .unwrap_or(fallback)
+        };
+
+        let mut worker = template.clone();
+        worker.height = lookup(Measure::Height, size, template.height);
+        worker.reach = lookup(Measure::Reach, size, template.reach);
+        worker.acromial_height = lookup(
+            Measure::AcromialHeight,
+            size,
+            worker.height - acromial_height_ratio(&template.gender) * worker.height,
+        );
+        worker.weight = lookup(Measure::Weight, mass, template.weight);
+        worker.strength_percentile = Some(sample_percentile(rng));
+        worker
+    }
+
+    // Transitions the humans in this allocation may fire: the chosen realization of each of
+    // their tasks, plus any travel, reach, move and carry transitions they can take.
+    fn human_transitions(&self, allocation: &Allocation) -> Vec<(Uuid, Transition)> {
+        let cost_net = match &self.cost_net {
+            Some(net) => net,
+            None => return vec![],
+        };
+        let evaluation = self.evaluate_allocation(allocation);
+        let chosen: HashMap<Uuid, Vec<Uuid>> = evaluation
+            .tasks
+            .iter()
+            .map(|t| (t.transition, t.agents.clone()))
+            .collect();
+
+        let mut transitions = vec![];
+        for agent_id in allocation.agents() {
+            if !matches!(self.agents.get(&agent_id), Some(Agent::Human(_))) {
+                continue;
+            }
+            for transition in cost_net.query_transitions(&vec![Query::Data(Data::Action(agent_id))]) {
+                let is_task = transition
+                    .meta_data
+                    .iter()
+                    .any(|d| matches!(d, Data::Task(_)));
+                let in_allocation = chosen
+                    .get(&transition.id)
+                    .is_some_and(|agents| agents.contains(&agent_id));
+                if !is_task || in_allocation {
+                    transitions.push((agent_id, transition.clone()));
+                }
+            }
+        }
+        transitions.sort_by(|a, b| a.1.name.cmp(&b.1.name).then(a.0.cmp(&b.0)));
+        transitions
+    }
+
+    fn can_reach(&self, worker: &HumanInfo, pairs: &[(Uuid, Uuid)]) -> bool {
+        let agent = self.age_adjusted(&Agent::Human(worker.clone()));
+        pairs.iter().all(|(standing, hand)| {
+            let standing_poi: Option<&PointOfInterest> = self.points_of_interest.get(standing);
+            let hand_poi: Option<&PointOfInterest> = self.points_of_interest.get(hand);
+            match (standing_poi, hand_poi) {
+                (Some(s), Some(h)) => s.reachability(h, &agent),
+                _ => true,
+            }
+        })
+    }
+
+    pub fn population_coverage(&self, allocation: &Allocation, samples: usize) -> PopulationCoverage {
+        self.population_coverage_with_rng(allocation, samples, &mut rand::thread_rng())
+    }
+
+    // Monte Carlo estimate of the share of the population able to perform each human-assigned
+    // transition of the allocation. The cost net must already have been created.
+    pub fn population_coverage_with_rng<R: Rng>(
+        &self,
+        allocation: &Allocation,
+        samples: usize,
+        rng: &mut R,
+    ) -> PopulationCoverage {
+        let transitions = self.human_transitions(allocation);
+        let mut reachable: Vec<usize> = vec![0; transitions.len()];
+        let mut within_mvc: Vec<usize> = vec![0; transitions.len()];
+        let mut capable: Vec<usize> = vec![0; transitions.len()];
+
+        for _ in 0..samples {
+            // One virtual worker per human, reused across all of that human's transitions
+            let workers: HashMap<Uuid, HumanInfo> = allocation
+                .agents()
+                .iter()
+                .filter_map(|id| match self.agents.get(id) {
+                    Some(Agent::Human(human)) => Some((*id, self.sample_virtual_worker(human, rng))),
+                    _ => None,
+                })
+                .collect();
+
+            for (idx, (agent_id, transition)) in transitions.iter().enumerate() {
+                let worker = &workers[agent_id];
+                let reaches = self.can_reach(worker, &reach_pairs(transition, agent_id));
+                let (_costs, meta_data) = worker.cost_set(transition, self);
+                let mvc_ok = meta_data.iter().all(|d| match d {
+                    Data::MVC(_, mvc) => *mvc <= 1.0,
+                    _ => true,
+                });
+                reachable[idx] += reaches as usize;
+                within_mvc[idx] += mvc_ok as usize;
+                capable[idx] += (reaches && mvc_ok) as usize;
+            }
+        }
+
+        let percent = |count: usize| {
+            if samples == 0 {
+                0.0
+            } else {
+                100.0 * count as f64 / samples as f64
+            }
+        };
+        PopulationCoverage {
+            samples,
+            transitions: transitions
+                .iter()
+                .enumerate()
+                .map(|(idx, (agent_id, transition))| TransitionCoverage {
+                    transition: transition.id,
+                    name: transition.name.clone(),
+                    agent: *agent_id,
+                    percent_reachable: percent(reachable[idx]),
+                    percent_within_mvc: percent(within_mvc[idx]),
+                    percent_capable: percent(capable[idx]),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[test]
+fn population_coverage_test() {
+    use crate::description::anthropometry::Anthropometry;
+    use crate::description::gender::Gender;
+    use crate::description::primitive::Primitive;
+    use crate::description::rating::Rating;
+    use rand::SeedableRng;
+
+    let mut job = Job::new("Coverage".into(), 0.1);
+    // The planned worker is long-armed enough for the layout
+    let measured = Anthropometry {
+        reach: Some(0.9),
+        ..Default::default()
+    };
+    let human = job.create_human_agent_with_anthropometry(
+        "Worker".into(),
+        30.0,
+        Gender::Male,
+        Rating::High,
+        24.0,
+        500.0,
+        measured,
+    );
+    job.create_standing_point_of_interest("Stand".into(), 0.0, 0.0, 0.0, None, None);
+    // Below the shoulder, at about the median male reach
+    let bench = job.create_hand_point_of_interest("Bench".into(), 0.76, 0.0, 0.9, None, None);
+    let part = job.create_precursor_target("Part".into(), 0.1, 1.0, Rating::High, vec![bench], 5.0);
+    let product = job.create_product_target("Product".into(), 0.1, 1.0, Rating::High, vec![bench], 10.0);
+    let task = job.create_task("Assemble".into(), 1);
+    job.add_task_dependency(task, part, 1);
+    job.add_task_output(task, product, 1);
+    job.add_task_primitive(task, Primitive::new_hold(part));
+    job.create_petri_nets();
+
+    let allocation = job.search_allocations().remove(0).allocation;
+    assert_eq!(allocation.agents(), vec![human]);
+    let coverage = job.population_coverage_with_rng(&allocation, 200, &mut rand::rngs::StdRng::seed_from_u64(7));
+    let again = job.population_coverage_with_rng(&allocation, 200, &mut rand::rngs::StdRng::seed_from_u64(7));
+    assert_eq!(coverage, again);
+
+    // Only some of the population can reach the bench
+    let limiting = coverage.limiting().unwrap();
+    assert!(limiting.percent_reachable > 10.0 && limiting.percent_reachable < 90.0);
+    assert!(limiting.percent_capable <= limiting.percent_reachable);
+}
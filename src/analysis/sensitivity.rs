@@ -0,0 +1,241 @@
+use crate::analysis::allocation::{Allocation, AllocationEvaluation};
+use crate::description::agent::Agent;
+use crate::description::job::Job;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use uuid::Uuid;
+
+// Bisection steps taken to narrow down where the chosen allocation changes
+const TIPPING_ITERATIONS: usize = 12;
+
+// A job parameter that can be swept
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum Parameter {
+    // Contain Agent UUID
+    HourlyWage(Uuid),
+    PurchasePrice(Uuid),
+    KwhCost,
+    // Only affects the strength of workers without a measured percentile, since anthropometry is
+    // resolved when a human is created
+    TargetPop,
+    ErgonomicWeight,
+    MonetaryWeight,
+}
+
+impl Parameter {
+    pub fn get(&self, job: &Job) -> Option<f64> {
+        match self {
+            Parameter::HourlyWage(agent) => match job.agents.get(agent) {
+                Some(Agent::Human(human)) => Some(human.hourly_wage),
+                _ => None,
+            },
+            Parameter::PurchasePrice(agent) => match job.agents.get(agent) {
+                Some(Agent::Robot(robot)) => Some(robot.purchase_price),
+                _ => None,
+            },
+            Parameter::KwhCost => Some(job.kwh_cost),
+            Parameter::TargetPop => Some(job.target_pop),
+            Parameter::ErgonomicWeight => Some(job.weights.ergonomic),
+            Parameter::MonetaryWeight => Some(job.weights.monetary),
+        }
+    }
+
+    pub fn set(&self, job: &mut Job, value: f64) {
+        match self {
+            Parameter::HourlyWage(agent) => {
+                if let Some(Agent::Human(human)) = job.agents.get_mut(agent) {
+                    human.hourly_wage = value;
+                }
+            }
+            Parameter::PurchasePrice(agent) => {
+                if let Some(Agent::Robot(robot)) = job.agents.get_mut(agent) {
+                    robot.purchase_price = value;
+                }
+            }
+            Parameter::KwhCost => job.kwh_cost = value,
+            Parameter::TargetPop => job.set_target_population(value),
+            Parameter::ErgonomicWeight => job.set_ergonomic_weight(value),
+            Parameter::MonetaryWeight => job.set_monetary_weight(value),
+        }
+    }
+
+    // Weights only change how allocations are scored, the rest change the costs in the cost net
+    fn changes_costs(&self) -> bool {
+        !matches!(self, Parameter::ErgonomicWeight | Parameter::MonetaryWeight)
+    }
+}
+
+// Once and extrapolated monetary cost of an allocation
+fn monetary_cost(evaluation: &AllocationEvaluation) -> f64 {
+    evaluation.once_cost + evaluation.extrapolated_monetary_cost
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepPoint {
+    pub value: f64,
+    pub best: AllocationEvaluation,
+}
+
+impl SweepPoint {
+    pub fn monetary_cost(&self) -> f64 {
+        monetary_cost(&self.best)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TippingPoint {
+    // The parameter value (to within the bisection) above which `to` is chosen over `from`
+    pub value: f64,
+    pub from: Allocation,
+    pub to: Allocation,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sweep {
+    pub parameter: Parameter,
+    pub points: Vec<SweepPoint>,
+    pub tipping_points: Vec<TippingPoint>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TornadoBar {
+    pub parameter: Parameter,
+    pub low: f64,
+    pub high: f64,
+    // Monetary cost of the best allocation at each end of the range, and at the job as it is
+    pub low_cost: f64,
+    pub high_cost: f64,
+    pub base_cost: f64,
+}
+
+impl TornadoBar {
+    pub fn swing(&self) -> f64 {
+        (self.high_cost - self.low_cost).abs()
+    }
+}
+
+impl Job {
+    // Best allocation of a copy of the job with the parameter set to the value
+    fn best_allocation_at(&self, parameter: &Parameter, value: f64) -> Result<AllocationEvaluation, String> {
+        let mut job = self.clone();
+        parameter.set(&mut job, value);
+        if parameter.changes_costs() {
+            job.cost_net = Some(job.create_cost_net());
+        }
+        job.search_allocations()
+            .into_iter()
+            .next()
+            .ok_or(format!("No allocation is possible with {:?} at {}", parameter, value))
+    }
+
+    // Re-optimize the allocation at each value, and find where between them the choice changes.
+    // Only changes between neighboring values are seen: if the choice moves away and back between
+    // two of them (A to B to A), no tipping point is reported, so sample where the costs cross often.
+    // When it changes more than once between two values, the bisection finds one of the changes.
+    pub fn sweep_parameter(&self, parameter: Parameter, values: Vec<f64>) -> Result<Sweep, String> {
+        if parameter.get(self).is_none() {
+            return Err(format!("The job has no parameter {:?}", parameter));
+        }
+        let mut values = values;
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let mut sweep = Sweep {
+            parameter: parameter.clone(),
+            points: vec![],
+            tipping_points: vec![],
+        };
+        for value in values {
+            let best = self.best_allocation_at(&parameter, value)?;
+            if let Some(previous) = sweep.points.last() {
+                if previous.best.allocation != best.allocation {
+                    let (mut low, mut high) = (previous.value, value);
+                    for _ in 0..TIPPING_ITERATIONS {
+                        let middle = (low + high) / 2.0;
+                        if self.best_allocation_at(&parameter, middle)?.allocation == previous.best.allocation {
+                            low = middle;
+                        } else {
+                            high = middle;
+                        }
+                    }
+                    sweep.tipping_points.push(TippingPoint {
+                        value: (low + high) / 2.0,
+                        from: previous.best.allocation.clone(),
+                        to: best.allocation.clone(),
+                    });
+                }
+            }
+            sweep.points.push(SweepPoint { value, best });
+        }
+        Ok(sweep)
+    }
+
+    // Monetary cost of the best allocation at either end of each parameter's range, largest swing first
+    pub fn tornado(&self, ranges: Vec<(Parameter, f64, f64)>) -> Result<Vec<TornadoBar>, String> {
+        let base = self
+            .search_allocations()
+            .into_iter()
+            .next()
+            .ok_or("No allocation is possible")?;
+        let base_cost = monetary_cost(&base);
+
+        let mut bars = vec![];
+        for (parameter, low, high) in ranges {
+            if parameter.get(self).is_none() {
+                return Err(format!("The job has no parameter {:?}", parameter));
+            }
+            bars.push(TornadoBar {
+                low_cost: monetary_cost(&self.best_allocation_at(&parameter, low)?),
+                high_cost: monetary_cost(&self.best_allocation_at(&parameter, high)?),
+                parameter,
+                low,
+                high,
+                base_cost,
+            });
+        }
+        bars.sort_by(|a, b| b.swing().partial_cmp(&a.swing()).unwrap_or(Ordering::Equal));
+        Ok(bars)
+    }
+}
+
+#[test]
+fn sweep_and_tornado_test() {
+    use crate::analysis::fixtures::{add_assembly, add_worker, bench_job};
+
+    // Two workers who differ only in their wage, so the cheaper one is chosen
+    let (mut job, bench) = bench_job("Sensitivity");
+    let first = add_worker(&mut job, "First", 20.0);
+    let second = add_worker(&mut job, "Second", 30.0);
+    add_assembly(&mut job, bench);
+    job.create_petri_nets();
+
+    // The choice tips to the second worker once the first earns more than the second's 30
+    let sweep = job.sweep_parameter(Parameter::HourlyWage(first), vec![50.0, 10.0, 40.0, 20.0]).unwrap();
+    assert_eq!(sweep.points.iter().map(|p| p.value).collect::<Vec<f64>>(), vec![10.0, 20.0, 40.0, 50.0]);
+    assert_eq!(sweep.points[1].best.allocation.agents(), vec![first]);
+    assert_eq!(sweep.points[2].best.allocation.agents(), vec![second]);
+    assert_eq!(sweep.tipping_points.len(), 1);
+    assert!((sweep.tipping_points[0].value - 30.0).abs() < 0.01);
+
+    // Above 30, the first worker's wage no longer matters. The second's only matters below the first's 20.
+    let bars = job
+        .tornado(vec![
+            (Parameter::KwhCost, 0.05, 0.5),
+            (Parameter::HourlyWage(second), 10.0, 50.0),
+            (Parameter::HourlyWage(first), 10.0, 50.0),
+        ])
+        .unwrap();
+    let order: Vec<Parameter> = bars.iter().map(|bar| bar.parameter.clone()).collect();
+    assert_eq!(
+        order,
+        vec![Parameter::HourlyWage(first), Parameter::HourlyWage(second), Parameter::KwhCost]
+    );
+    assert!((bars[0].swing() - 2.0 * bars[1].swing()).abs() < 1e-6 * bars[0].swing());
+    assert_eq!(bars[2].swing(), 0.0);
+    // A dearer second worker leaves the choice, and its cost, as it is
+    assert_eq!(bars[1].high_cost, bars[1].base_cost);
+}
@@ -0,0 +1,198 @@
+use crate::analysis::allocation::AllocationEvaluation;
+use crate::description::job::Job;
+use crate::petri::cost::{CostCategory, CostFrequency, CostSet, Uncertainty};
+use crate::petri::transition::Transition;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostInterval {
+    pub mean: f64,
+    pub p5: f64,
+    pub p95: f64,
+}
+
+impl CostInterval {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self { mean: 0.0, p5: 0.0, p95: 0.0 };
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+        Self {
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+            p5: percentile(0.05),
+            p95: percentile(0.95),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UncertainEvaluation {
+    pub evaluation: AllocationEvaluation,
+    // Weighted total cost (the score) of each draw
+    pub samples: Vec<f64>,
+    pub score: CostInterval,
+    // Once and extrapolated monetary cost
+    pub monetary: CostInterval,
+}
+
+impl UncertainEvaluation {
+    // Probability that this allocation scores lower than the other, over their draws taken in pairs.
+    // Ties count half. Pairs should share their cost factors, as they do within `search_allocations_uncertain`,
+    // so that only the differences between the allocations are compared.
+    pub fn probability_better(&self, other: &UncertainEvaluation) -> f64 {
+        let pairs = self.samples.len().min(other.samples.len());
+        if pairs == 0 {
+            return 0.5;
+        }
+        let wins: f64 = self
+            .samples
+            .iter()
+            .zip(other.samples.iter())
+            .map(|(a, b)| match a.partial_cmp(b) {
+                Some(Ordering::Less) => 1.0,
+                Some(Ordering::Equal) => 0.5,
+                _ => 0.0,
+            })
+            .sum();
+        wins / pairs as f64
+    }
+}
+
+fn sample_costs(
+    costs: &CostSet,
+    frequency: CostFrequency,
+    category: CostCategory,
+    factors: &HashMap<Uncertainty, f64>,
+) -> f64 {
+    costs
+        .iter()
+        .filter(|c| c.frequency == frequency && c.category == category)
+        .map(|c| c.sample(factors))
+        .sum()
+}
+
+impl Job {
+    // Propagate the uncertainty in the costs of the allocation's chosen transitions by Monte Carlo
+    pub fn evaluate_uncertainty_with_rng<R: Rng>(
+        &self,
+        evaluation: &AllocationEvaluation,
+        samples: usize,
+        rng: &mut R,
+    ) -> UncertainEvaluation {
+        let factors: Vec<HashMap<Uncertainty, f64>> =
+            (0..samples).map(|_| self.cost_uncertainty.sample_factors(rng)).collect();
+        self.evaluate_uncertainty_with_factors(evaluation, &factors)
+    }
+
+    // One draw of the allocation's costs for each set of factors. Every cost of a kind is scaled by the
+    // same factor within a draw.
+    pub fn evaluate_uncertainty_with_factors(
+        &self,
+        evaluation: &AllocationEvaluation,
+        factors: &[HashMap<Uncertainty, f64>],
+    ) -> UncertainEvaluation {
+        // Firings per period and chosen transition of each task
+        let chosen: Vec<(f64, &Transition)> = match &self.cost_net {
+            Some(cost_net) => evaluation
+                .tasks
+                .iter()
+                .filter_map(|task| cost_net.transitions.get(&task.transition).map(|t| (task.firings, t)))
+                .collect(),
+            None => vec![],
+        };
+        let once_costs = self.allocation_once_costs(
+            &evaluation.allocation,
+            &chosen.iter().map(|(_, transition)| *transition).collect(),
+        );
+
+        let mut scores = vec![];
+        let mut monetary = vec![];
+        for factors in factors.iter() {
+            let once = sample_costs(&once_costs, CostFrequency::Once, CostCategory::Monetary, factors);
+            // Standby energy is not uncertain
+            let mut extrapolated_monetary = evaluation.idle_energy_cost;
+            let mut extrapolated_ergonomic = 0.0;
+            for (firings, transition) in chosen.iter() {
+                extrapolated_monetary += firings
+                    * sample_costs(&transition.cost, CostFrequency::Extrapolated, CostCategory::Monetary, factors);
+                extrapolated_ergonomic += firings
+                    * sample_costs(&transition.cost, CostFrequency::Extrapolated, CostCategory::Ergonomic, factors);
+            }
+            monetary.push(once + extrapolated_monetary);
+            scores.push(
+                self.weights.monetary * (once + extrapolated_monetary)
+                    + self.weights.ergonomic * extrapolated_ergonomic,
+            );
+        }
+
+        UncertainEvaluation {
+            evaluation: evaluation.clone(),
+            score: CostInterval::from_samples(&scores),
+            monetary: CostInterval::from_samples(&monetary),
+            samples: scores,
+        }
+    }
+
+    // Every candidate allocation, in the order of `search_allocations`, with its cost uncertainty.
+    // All of them are evaluated under the same draws, so that their samples can be compared in pairs.
+    pub fn search_allocations_uncertain(&self, samples: usize) -> Vec<UncertainEvaluation> {
+        self.search_allocations_uncertain_with_rng(samples, &mut rand::thread_rng())
+    }
+
+    pub fn search_allocations_uncertain_with_rng<R: Rng>(&self, samples: usize, rng: &mut R) -> Vec<UncertainEvaluation> {
+        let factors: Vec<HashMap<Uncertainty, f64>> =
+            (0..samples).map(|_| self.cost_uncertainty.sample_factors(rng)).collect();
+        self.search_allocations()
+            .iter()
+            .map(|evaluation| self.evaluate_uncertainty_with_factors(evaluation, &factors))
+            .collect()
+    }
+}
+
+#[test]
+fn cost_interval_test() {
+    let samples: Vec<f64> = (0..=100).map(|i| i as f64).collect();
+    let interval = CostInterval::from_samples(&samples);
+    assert_eq!(interval.mean, 50.0);
+    assert_eq!(interval.p5, 5.0);
+    assert_eq!(interval.p95, 95.0);
+}
+
+#[test]
+fn common_factors_test() {
+    use crate::analysis::fixtures::{add_assembly, add_worker, bench_job};
+    use crate::description::uncertainty::CostUncertainty;
+    use crate::petri::cost::CostDistribution;
+    use rand::SeedableRng;
+
+    // Two workers who differ only in their wage
+    let (mut job, bench) = bench_job("Uncertainty");
+    let cheap = add_worker(&mut job, "Cheap", 20.0);
+    let dear = add_worker(&mut job, "Dear", 40.0);
+    add_assembly(&mut job, bench);
+    job.set_cost_uncertainty(CostUncertainty {
+        labor: Some(CostDistribution::Uniform { min: 0.5, max: 1.5 }),
+        ..Default::default()
+    });
+    job.create_petri_nets();
+
+    let evaluations = job.search_allocations_uncertain_with_rng(200, &mut rand::rngs::StdRng::seed_from_u64(7));
+    let find = |agent| {
+        evaluations
+            .iter()
+            .find(|e| e.evaluation.allocation.agents() == vec![agent])
+            .unwrap()
+    };
+    let (cheap, dear) = (find(cheap), find(dear));
+    assert!(cheap.score.p95 > dear.score.p5);
+    // Under the same draws of the labor factor, the cheaper worker always wins
+    assert_eq!(cheap.probability_better(dear), 1.0);
+    assert_eq!(dear.probability_better(cheap), 0.0);
+}
@@ -10,6 +10,9 @@ pub const DISTANCE_PER_PACE: f64 = 1.19;
 // Number of seconds in an hour
 pub const SEC_PER_HOUR: f64 = 3600.0;
 
+// Weight in Newtons of a kilogram. Masses are stored in kg.
+pub const GRAVITY: f64 = 9.81;
+
 // distances for categorizing work type
 pub const MAX_HAND_WORK_DISTANCE: f64 = 0.05;
 pub const MAX_ARM_WORK_DISTANCE: f64 = 0.45;
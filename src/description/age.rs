@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use super::agent::Agent;
+use super::job::Job;
+
+// Capacity relative to a worker in their twenties and thirties, applying from `min_age` upward
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgeBand {
+    pub min_age: f64,
+    pub strength: f64,
+    pub reach: f64,
+    pub walking_speed: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgeFactors {
+    pub bands: Vec<AgeBand>,
+    pub adjust_reach: bool,
+    pub adjust_walking_speed: bool,
+}
+
+impl Default for AgeFactors {
+    // Approximate decline in maximal voluntary strength, functional reach and self-selected
+    // walking speed with age. Strength is always adjusted; reach and walking speed are opt-in.
+    fn default() -> Self {
+        let band = |min_age, strength, reach, walking_speed| AgeBand {
+            min_age,
+            strength,
+            reach,
+            walking_speed,
+        };
+        Self {
+            bands: vec![
+                band(0.0, 1.0, 1.0, 1.0),
+                band(40.0, 0.95, 1.0, 1.0),
+                band(50.0, 0.88, 0.99, 0.97),
+                band(60.0, 0.80, 0.98, 0.93),
+                band(70.0, 0.72, 0.97, 0.87),
+            ],
+            adjust_reach: false,
+            adjust_walking_speed: false,
+        }
+    }
+}
+
+impl AgeFactors {
+    // No age adjustment at all
+    pub fn none() -> Self {
+        Self {
+            bands: vec![],
+            adjust_reach: false,
+            adjust_walking_speed: false,
+        }
+    }
+
+    pub fn band(&self, age: f64) -> Option<&AgeBand> {
+        self.bands
+            .iter()
+            .filter(|b| b.min_age <= age)
+            .max_by(|a, b| a.min_age.partial_cmp(&b.min_age).unwrap())
+    }
+
+    pub fn strength(&self, age: f64) -> f64 {
+        self.band(age).map_or(1.0, |b| b.strength)
+    }
+
+    pub fn reach(&self, age: f64) -> f64 {
+        match self.adjust_reach {
+            true => self.band(age).map_or(1.0, |b| b.reach),
+            false => 1.0,
+        }
+    }
+
+    pub fn walking_speed(&self, age: f64) -> f64 {
+        match self.adjust_walking_speed {
+            true => self.band(age).map_or(1.0, |b| b.walking_speed),
+            false => 1.0,
+        }
+    }
+}
+
+impl Job {
+    // The agent as seen by reachability checks, with reach scaled for age when enabled
+    pub fn age_adjusted(&self, agent: &Agent) -> Agent {
+        match agent {
+            Agent::Human(human_info) => {
+                let mut adjusted = human_info.clone();
+                adjusted.reach *= self.age_factors.reach(human_info.age);
+                Agent::Human(adjusted)
+            }
+            Agent::Robot(_) => agent.clone(),
+        }
+    }
+}
+
+#[test]
+fn age_band_test() {
+    let mut factors = AgeFactors::default();
+    assert_eq!(factors.strength(25.0), 1.0);
+    assert_eq!(factors.strength(55.0), 0.88);
+    assert_eq!(factors.strength(85.0), 0.72);
+    assert_eq!(factors.reach(65.0), 1.0);
+
+    factors.adjust_reach = true;
+    assert_eq!(factors.reach(65.0), 0.98);
+    assert_eq!(AgeFactors::none().strength(65.0), 1.0);
+}
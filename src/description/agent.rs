@@ -1,13 +1,12 @@
 use crate::constants::{
-    DISTANCE_PER_PACE, MAX_ARM_WORK_DISTANCE, MAX_HAND_WORK_DISTANCE, MAX_SHOULDER_WORK_DISTANCE,
-    SEC_PER_HOUR, TMU_PER_SECOND,
+    MAX_ARM_WORK_DISTANCE, MAX_HAND_WORK_DISTANCE, MAX_SHOULDER_WORK_DISTANCE, SEC_PER_HOUR,
 };
 use crate::description::job::Job;
 use crate::description::poi::PointOfInterest;
 use crate::description::primitive::Primitive;
 use crate::description::rating::Rating;
 use crate::description::units::Time;
-use crate::petri::cost::{Cost, CostCategory, CostFrequency, CostSet};
+use crate::petri::cost::{Cost, CostCategory, CostFrequency, CostSet, Uncertainty};
 use crate::petri::data::{Data, DataTag, Query};
 use crate::petri::transition::Transition;
 use nalgebra::{Vector2, Vector3};
@@ -19,7 +18,19 @@ use uuid::Uuid;
 use statrs::distribution::{Normal, ContinuousCDF};
 use statrs::statistics::Distribution;
 
+use super::energy::kwh;
+use super::labor::LaborBurden;
+use super::fitts::{effective_width, target_width, FittsCoefficients};
 use super::gender::Gender;
+use super::urdf::ReachabilityMap;
+use super::motion::{combine_segments, Blending, Coordination, MotionProfile};
+use super::robot_error::RobotErrorModel;
+use super::safety::{SafetyMode, SharedZone};
+use super::tool::{Tool, DEFAULT_ACTUATION_TIME};
+use super::mtm::{
+    Fit, GraspCase, MoveCase, MtmBreakdown, MtmElement, PressureCase, ReachCase, ReleaseCase,
+    SMALL_WEIGHT,
+};
 use super::target::{self, Target};
 use super::units::{TokenCount, Watts, USD};
 
@@ -45,6 +56,9 @@ impl Agent {
         energy_consumption: Watts,    // watts
         annual_maintenance_cost: USD, //dollars
     ) -> Self {
+        let fitts = Some(FittsCoefficients::robot(&agility));
+        let arm_motion = MotionProfile::arm(&agility);
+        let base_motion = MotionProfile::base(&agility);
         return Agent::Robot(RobotInfo {
             id: Uuid::new_v4(),
             name,
@@ -58,7 +72,17 @@ impl Agent {
             mobile_speed,
             purchase_price,
             energy_consumption,
+            idle_power: None,
             annual_maintenance_cost,
+            fitts,
+            arm_motion,
+            base_motion,
+            coordination: Coordination::Sequential,
+            blending: Blending::StopAndGo,
+            reachability_map: None,
+            safety: SafetyMode::Fenced,
+            tools: vec![],
+            error_model: None,
         });
     }
 
@@ -86,6 +110,9 @@ impl Agent {
             skill,
             hourly_wage,
             labor_cost,
+            labor_burden: None,
+            strength_percentile: None,
+            fitts: None,
         });
     }
 
@@ -118,7 +145,28 @@ pub struct RobotInfo {
     pub mobile_speed: f64,
     pub purchase_price: USD,
     pub energy_consumption: Watts,
+    // Standby draw while waiting on other agents. Without it, a fraction of the active draw.
+    pub idle_power: Option<Watts>,
     pub annual_maintenance_cost: USD,
+    // Approach time to the target tolerance. Without it, motions take distance / speed.
+    pub fitts: Option<FittsCoefficients>,
+    #[serde(default = "MotionProfile::default_arm")]
+    pub arm_motion: MotionProfile,
+    #[serde(default = "MotionProfile::default_base")]
+    pub base_motion: MotionProfile,
+    #[serde(default)]
+    pub coordination: Coordination,
+    #[serde(default)]
+    pub blending: Blending,
+    // Workspace sampled from the robot's URDF. Without it, reach is a sphere.
+    pub reachability_map: Option<ReachabilityMap>,
+    #[serde(default)]
+    pub safety: SafetyMode,
+    // End-of-arm tools it can mount. Without any, it uses a stock gripper within its payload.
+    #[serde(default)]
+    pub tools: Vec<Uuid>,
+    // Overrides the job's robot error model
+    pub error_model: Option<RobotErrorModel>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -136,6 +184,11 @@ pub struct HumanInfo {
     pub skill: Rating,
     pub hourly_wage: USD,
     pub labor_cost: USD,
+    // Overrides the job's labor burden
+    pub labor_burden: Option<LaborBurden>,
+    pub strength_percentile: Option<f64>,
+    // Pointing time for reaches and moves. Without it, the MTM-1 distance tables are used.
+    pub fitts: Option<FittsCoefficients>,
 }
 
 pub trait CostProfiler {
@@ -143,29 +196,36 @@ pub trait CostProfiler {
     fn cost_set(&self, transition: &Transition, job: &Job) -> (CostSet, Vec<Data>);
 }
 
-impl CostProfiler for HumanInfo {
-    fn execution_time(&self, transition: &Transition, job: &Job) -> Time {
+impl HumanInfo {
+    // The MTM elements of the longest single primitive or primitive pair in the transition
+    pub fn time_breakdown(&self, transition: &Transition, job: &Job) -> MtmBreakdown {
         let assigned_primitives = get_assigned_primitives(transition, job, self.id);
 
-        let mut max_time = 0.0;
+        let mut longest = MtmBreakdown::new();
 
         for primitive in assigned_primitives.iter() {
             let temp_vec = vec![*primitive];
-            let single_time = get_human_time_for_primitive(temp_vec, transition, job, self);
-            if single_time > max_time {
-                max_time = single_time;
+            let single = get_human_mtm_for_primitive(temp_vec, transition, job, self);
+            if single.total_tmu() > longest.total_tmu() {
+                longest = single;
             }
 
             for primitive_two in assigned_primitives.iter() {
                 let temp_vec = vec![*primitive, *primitive_two];
-                let doubles_time = get_human_time_for_primitive(temp_vec, transition, job, self);
-                if doubles_time > max_time {
-                    max_time = doubles_time;
+                let doubles = get_human_mtm_for_primitive(temp_vec, transition, job, self);
+                if doubles.total_tmu() > longest.total_tmu() {
+                    longest = doubles;
                 }
             }
         }
 
-        return max_time;
+        return longest;
+    }
+}
+
+impl CostProfiler for HumanInfo {
+    fn execution_time(&self, transition: &Transition, job: &Job) -> Time {
+        self.time_breakdown(transition, job).seconds()
     }
 
     fn cost_set(&self, transition: &Transition, job: &Job) -> (CostSet, Vec<Data>) {
@@ -182,8 +242,9 @@ impl CostProfiler for HumanInfo {
         if execution_time > 0.0 {
             ergo_cost_set.push(Cost {
                 frequency: CostFrequency::Extrapolated,
-                value: self.hourly_wage * execution_time / SEC_PER_HOUR,
+                value: job.labor_cost(self).hourly * execution_time / SEC_PER_HOUR,
                 category: CostCategory::Monetary,
+                uncertainty: job.cost_uncertainty.of(Uncertainty::Labor),
             });
         }
 
@@ -198,10 +259,12 @@ impl CostProfiler for HumanInfo {
             .collect();
 
         if transition.has_data(&vec![Query::Data(Data::AgentAdd(self.id))]) {
+            // Hiring, training and onboarding
             ergo_cost_set.push(Cost {
                 frequency: CostFrequency::Once,
-                value: self.labor_cost,
+                value: job.labor_cost(self).once,
                 category: CostCategory::Monetary,
+                uncertainty: job.cost_uncertainty.of(Uncertainty::Labor),
             });
         }
 
@@ -212,6 +275,7 @@ impl CostProfiler for HumanInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Monetary,
+                        uncertainty: None,
                     });
                 }
                 Data::Produce(_target_id, cost) => {
@@ -219,6 +283,7 @@ impl CostProfiler for HumanInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: -cost,
                         category: CostCategory::Monetary,
+                        uncertainty: None,
                     });
                 }
                 _ => {}
@@ -226,6 +291,8 @@ impl CostProfiler for HumanInfo {
         }
 
         let mut new_ergo_meta_data: Vec<Data> = Vec::new();
+        // Reach bands scale with the reach the worker has at their age, as in reachability
+        let reach = self.reach * job.age_factors.reach(self.age);
 
         for primitive in assigned_primitives.iter() {
             // for primitive in &assigned_primitives {
@@ -247,6 +314,7 @@ impl CostProfiler for HumanInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Ergonomic,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::Ergonomic),
                     });
 
                     let mut new_data = vec_ergo_meta_data(self, dist, cost);
@@ -283,63 +351,50 @@ impl CostProfiler for HumanInfo {
                     from_shoulder_pos.z = from_shoulder_pos.z + self.acromial_height;
                     let hand_travel_vector = to_hand_info.position() - to_shoulder_pos;
                     let hand_travel_distance = hand_travel_vector.norm();
-                    let total_distance_traveled = (to_standing_info.position().clone() - from_standing_info.position().clone())
-                    .norm();
+                    let total_distance_traveled = standing_travel_distance(job, from_standing, to_standing, false);
 
                     let mut denom = 0.0;
                     if is_one_hand {
-                        if hand_travel_distance < 0.5*self.reach {
+                        if hand_travel_distance < 0.5*reach {
                             if self.gender == Gender::Female {
-                                let n = Normal::new(105.0, 32.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 105.0, 32.0);
                             } else {
-                                let n = Normal::new(232.5, 54.5).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 232.5, 54.5);
                             }
-                        } else if hand_travel_distance < 0.75*self.reach {
+                        } else if hand_travel_distance < 0.75*reach {
                             if self.gender == Gender::Female {
-                                let n = Normal::new(73.5, 25.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 73.5, 25.0);
                             } else {
-                                let n = Normal::new(118.5, 25.5).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 118.5, 25.5);
                             }
-                        } else if hand_travel_distance <= self.reach {
+                        } else if hand_travel_distance <= reach {
                             
                             if self.gender == Gender::Female {
-                                let n = Normal::new(46.0, 13.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 46.0, 13.0);
                             } else {
-                                let n = Normal::new(75.5, 13.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 75.5, 13.0);
                             }
                         } else {
                             denom += 0.001;
                         }
                     } else {
-                        if hand_travel_distance < 0.5*self.reach {
+                        if hand_travel_distance < 0.5*reach {
                             if self.gender == Gender::Female {
-                                let n = Normal::new(210.0, 64.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 210.0, 64.0);
                             } else {
-                                let n = Normal::new(465.0, 109.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 465.0, 109.0);
                             }
-                        } else if hand_travel_distance < 0.75*self.reach {
+                        } else if hand_travel_distance < 0.75*reach {
                             if self.gender == Gender::Female {
-                                let n = Normal::new(147.0, 50.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 147.0, 50.0);
                             } else {
-                                let n = Normal::new(237.0, 51.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 237.0, 51.0);
                             }
-                        } else if hand_travel_distance <= self.reach {
+                        } else if hand_travel_distance <= reach {
                             if self.gender == Gender::Female {
-                                let n = Normal::new(92.0, 26.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 92.0, 26.0);
                             } else {
-                                let n = Normal::new(151.0, 26.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 151.0, 26.0);
                             }
                         } else {
                             denom += 0.001;
@@ -352,6 +407,7 @@ impl CostProfiler for HumanInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Ergonomic,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::Ergonomic),
                     });
 
                     new_ergo_meta_data.push(Data::ErgoArm(self.id, cost));
@@ -410,55 +466,43 @@ impl CostProfiler for HumanInfo {
                     } else {
                         if is_one_hand {
                             if horizontal_hand_shoulder_distance < 0.45 {
-                                if reach_distance < 0.5*self.reach {
+                                if reach_distance < 0.5*reach {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(130.5, 43.5).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 130.5, 43.5);
                                     } else {
-                                        let n = Normal::new(295.0, 70.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 295.0, 70.0);
                                     }
-                                } else if reach_distance < 0.75*self.reach {
+                                } else if reach_distance < 0.75*reach {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(91.0, 31.5).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 91.0, 31.5);
                                     } else {
-                                        let n = Normal::new(147.5, 33.5).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 147.5, 33.5);
                                     }
                                 } else {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(59.0, 16.5).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 59.0, 16.5);
                                     } else {
-                                        let n = Normal::new(92.0, 17.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 92.0, 17.0);
                                     }
                                 }
                             } else if horizontal_hand_shoulder_distance < 2.0 {
-                                if reach_distance < 0.5*self.reach {
+                                if reach_distance < 0.5*reach {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(87.5, 29.5).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 87.5, 29.5);
                                     } else {
-                                        let n = Normal::new(192.0, 48.5).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 192.0, 48.5);
                                     }
-                                } else if reach_distance < 0.75*self.reach {
+                                } else if reach_distance < 0.75*reach {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(68.5, 17.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 68.5, 17.0);
                                     } else {
-                                        let n = Normal::new(145.0, 45.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 145.0, 45.0);
                                     }
                                 } else {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(46.5, 9.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 46.5, 9.0);
                                     } else {
-                                        let n = Normal::new(99.5, 39.5).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 99.5, 39.5);
                                     }
                                 }
                             } else {
@@ -466,55 +510,43 @@ impl CostProfiler for HumanInfo {
                             }
                         } else {
                             if horizontal_hand_shoulder_distance < 0.45 {
-                                if reach_distance < 0.5*self.reach {
+                                if reach_distance < 0.5*reach {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(261.0, 87.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 261.0, 87.0);
                                     } else {
-                                        let n = Normal::new(590.0, 140.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 590.0, 140.0);
                                     }
-                                } else if reach_distance < 0.75*self.reach {
+                                } else if reach_distance < 0.75*reach {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(182.0, 63.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 182.0, 63.0);
                                     } else {
-                                        let n = Normal::new(295.0, 67.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 295.0, 67.0);
                                     }
                                 } else {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(118.0, 33.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 118.0, 33.0);
                                     } else {
-                                        let n = Normal::new(184.0, 34.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 184.0, 34.0);
                                     }
                                 }
                             } else if horizontal_hand_shoulder_distance < 2.0 {
-                                if reach_distance < 0.5*self.reach {
+                                if reach_distance < 0.5*reach {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(175.0, 59.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 175.0, 59.0);
                                     } else {
-                                        let n = Normal::new(384.0, 97.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 384.0, 97.0);
                                     }
-                                } else if reach_distance < 0.75*self.reach {
+                                } else if reach_distance < 0.75*reach {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(137.0, 59.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 137.0, 59.0);
                                     } else {
-                                        let n = Normal::new(290.0, 90.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 290.0, 90.0);
                                     }
                                 } else {
                                     if self.gender == Gender::Female {
-                                        let n = Normal::new(93.0, 18.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 93.0, 18.0);
                                     } else {
-                                        let n = Normal::new(199.0, 79.0).unwrap();
-                                        denom += n.inverse_cdf(job.target_pop);
+                                        denom += strength_capacity(self, job, 199.0, 79.0);
                                     }
                                 }
                             } else {
@@ -539,6 +571,7 @@ impl CostProfiler for HumanInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Ergonomic,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::Ergonomic),
                     });
 
                     let mut new_data = vec_ergo_meta_data(self, horizontal_distance, cost);
@@ -569,19 +602,15 @@ impl CostProfiler for HumanInfo {
                     let mut denom = 0.0;
                     if volume > 0.406 {
                         if self.gender == Gender::Female {
-                            let n = Normal::new(308.0, 61.2).unwrap();
-                            denom += n.inverse_cdf(job.target_pop);
+                            denom += strength_capacity(self, job, 308.0, 61.2);
                         } else {
-                            let n = Normal::new(487.5, 109.1).unwrap();
-                            denom += n.inverse_cdf(job.target_pop);
+                            denom += strength_capacity(self, job, 487.5, 109.1);
                         }
                     } else {
                         if self.gender == Gender::Female {
-                            let n = Normal::new(68.6, 12.2).unwrap();
-                            denom += n.inverse_cdf(job.target_pop);
+                            denom += strength_capacity(self, job, 68.6, 12.2);
                         } else {
-                            let n = Normal::new(92.2, 13.6).unwrap();
-                            denom += n.inverse_cdf(job.target_pop);
+                            denom += strength_capacity(self, job, 92.2, 13.6);
                         }
                     }
 
@@ -592,6 +621,7 @@ impl CostProfiler for HumanInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Ergonomic,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::Ergonomic),
                     });
 
                     let mut new_data =
@@ -610,14 +640,7 @@ impl CostProfiler for HumanInfo {
                     ..
                 } => {
                     // Consider Travel Calculation
-
-                    // Retrieve data
-                    let from_standing_info = job.points_of_interest.get(from_standing).unwrap();
-                    let to_standing_info = job.points_of_interest.get(to_standing).unwrap();
-
-                    // Compute travel vector/distance
-                    let travel_vector = to_standing_info.position() - from_standing_info.position();
-                    let travel_distance = travel_vector.norm();
+                    let travel_distance = standing_travel_distance(job, from_standing, to_standing, false);
                     new_ergo_meta_data.push(Data::StandTravelDistance(*id, travel_distance));
                 }
                 Primitive::Hold { target, id, .. } => {
@@ -641,21 +664,17 @@ impl CostProfiler for HumanInfo {
                              // elbow at 90 degrees
 
                             if self.gender == Gender::Female {
-                                let n = Normal::new(105.0, 32.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 105.0, 32.0);
                             } else {
-                                let n = Normal::new(232.5, 54.5).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 232.5, 54.5);
                             }
                         } else {
                             // shoulder at 90 degrees
 
                             if self.gender == Gender::Female {
-                                let n = Normal::new(46.0, 13.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 46.0, 13.0);
                             } else {
-                                let n = Normal::new(75.5, 13.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 75.5, 13.0);
                             }
                         }
                     } else {
@@ -663,21 +682,17 @@ impl CostProfiler for HumanInfo {
                             // elbow at 90  degrees
 
                             if self.gender == Gender::Female {
-                                let n = Normal::new(210.0, 64.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 210.0, 64.0);
                             } else {
-                                let n = Normal::new(465.0, 109.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 465.0, 109.0);
                             }
                         } else {
                             // shoulder at 90 degrees
 
                             if self.gender == Gender::Female {
-                                let n = Normal::new(92.0, 26.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 92.0, 26.0);
                             } else {
-                                let n = Normal::new(151.0, 26.0).unwrap();
-                                denom += n.inverse_cdf(job.target_pop);
+                                denom += strength_capacity(self, job, 151.0, 26.0);
                             }
                         }
                     }
@@ -688,6 +703,7 @@ impl CostProfiler for HumanInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Ergonomic,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::Ergonomic),
                     });
 
                     let mut new_data =
@@ -704,26 +720,51 @@ impl CostProfiler for HumanInfo {
             }
         }
 
+        // Cost for error, on the same basis as the robot's
+        let max_error_cost = get_error_loss(transition, job);
+        let exertion = new_ergo_meta_data
+            .iter()
+            .filter_map(|d| match d {
+                Data::MVC(_, mvc) => Some(*mvc),
+                _ => None,
+            })
+            .fold(0.0, f64::max);
+        for primitive in assigned_primitives.iter() {
+            let probability = job
+                .human_error_model
+                .probability(primitive, &self.skill, job, exertion);
+            if probability > 0.0 {
+                ergo_cost_set.push(Cost {
+                    frequency: CostFrequency::Extrapolated,
+                    value: probability * max_error_cost,
+                    category: CostCategory::Monetary,
+                    uncertainty: job.cost_uncertainty.of(Uncertainty::HumanError),
+                });
+            }
+        }
+
         (ergo_cost_set, new_ergo_meta_data)
     }
 }
 
-impl CostProfiler for RobotInfo {
-    fn execution_time(&self, transition: &Transition, job: &Job) -> Time {
+// Timing and costs given the job's shared zones, so that net generation can find them once
+impl RobotInfo {
+    pub fn execution_time_in(&self, transition: &Transition, job: &Job, zones: &[SharedZone]) -> Time {
         let assigned_primitives = get_assigned_primitives(transition, job, self.id);
+        let robot = &job.safety_adjusted(self, transition, zones);
 
         let mut max_time = 0.0;
 
         for primitive in assigned_primitives.iter() {
             let temp_vec = vec![*primitive];
-            let single_time = get_robot_time_for_primitive(temp_vec, transition, job, self);
+            let single_time = get_robot_time_for_primitive(temp_vec, transition, job, robot);
             if single_time > max_time {
                 max_time = single_time;
             }
 
             for primitive_two in assigned_primitives.iter() {
                 let temp_vec = vec![*primitive, *primitive_two];
-                let doubles_time = get_robot_time_for_primitive(temp_vec, transition, job, self);
+                let doubles_time = get_robot_time_for_primitive(temp_vec, transition, job, robot);
                 if doubles_time > max_time {
                     max_time = doubles_time;
                 }
@@ -733,7 +774,7 @@ impl CostProfiler for RobotInfo {
         return max_time;
     }
 
-    fn cost_set(&self, transition: &Transition, job: &Job) -> (CostSet, Vec<Data>) {
+    pub fn cost_set_in(&self, transition: &Transition, job: &Job, zones: &[SharedZone]) -> (CostSet, Vec<Data>) {
         let assigned_primitives: Vec<&Primitive> = transition
             .meta_data
             .iter()
@@ -745,38 +786,31 @@ impl CostProfiler for RobotInfo {
 
         // If this contains data, it's for pruning the non-possible transitions.
         let mut robo_ergo_costs: Vec<Data> = Vec::new();
-        
-        // Add one-time purchasing cost (if the transition adds the agent)
+
+        // Add one-time purchasing cost (if the transition adds the agent). Integration depends on the
+        // tasks the robot is given, so it is priced when an allocation is evaluated.
         if transition.has_data(&vec![Query::Data(Data::AgentAdd(self.id))]) {
             robot_cost_set.push(Cost {
                 frequency: CostFrequency::Once,
                 value: self.purchase_price,
                 category: CostCategory::Monetary,
-            });
-            
-            // Cost for integration (4-6x)
-            // https://www.engineering.com/time-and-money-how-much-do-industrial-robots-cost/
-            // TODO: update the value based on needed systems (i.e. integration breakdown - vision, pick+place, etc))
-            robot_cost_set.push(Cost {
-                frequency: CostFrequency::Once,
-                value: self.purchase_price * 5.0,
-                category: CostCategory::Monetary,
+                uncertainty: None,
             });
         }
 
-        
-
         // Add electricity cost
-        let execution_time = self.execution_time(transition, job);
+        let execution_time = self.execution_time_in(transition, job, zones);
         if execution_time > 0.0 {
             robot_cost_set.push(Cost {
                 frequency: CostFrequency::Extrapolated,
-                value: (self.energy_consumption * execution_time / SEC_PER_HOUR) * job.kwh_cost, // cost is $/kWh
+                value: kwh(self.energy_consumption, execution_time) * job.shift_kwh_cost(),
                 category: CostCategory::Monetary,
+                uncertainty: job.cost_uncertainty.of(Uncertainty::Energy),
             });
         }
 
-        let max_error_cost = get_produced_value(job);
+        let max_error_cost = get_error_loss(transition, job);
+        let error_model = job.error_model_for(self);
 
         // Cost for error
         for primitive in assigned_primitives.iter() {
@@ -790,13 +824,11 @@ impl CostProfiler for RobotInfo {
                     to_hand,
                 } => {
                     let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
-                    let cost = get_robot_error_rate_independent(to_hand_info.structure(), to_hand_info.variability(), self.sensing.clone()) * max_error_cost;
-                    // let cost = get_robot_error_rate_exponential(to_hand_info.structure(), to_hand_info.variability(), self.sensing);
-                    // let cost = get_robot_error_rate_multiplicative(to_hand_info.structure(), to_hand_info.variability(), self.sensing);
+                    let cost = error_model.rate(&to_hand_info.structure(), &to_hand_info.variability(), &self.sensing) * max_error_cost;
 
                     let target_info = job.targets.get(target).unwrap();
 
-                    if target_info.weight() > (self.payload * 9.81) {
+                    if !robot_handles(self, transition, job, target_info) {
                         robo_ergo_costs.push(Data::MVC(*id, 0.001));
                     }
 
@@ -805,6 +837,7 @@ impl CostProfiler for RobotInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Monetary,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::RobotError),
                     });
                 }
                 Primitive::Move {
@@ -815,13 +848,11 @@ impl CostProfiler for RobotInfo {
                     to_hand,
                 } => {
                     let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
-                    let cost = get_robot_error_rate_independent(to_hand_info.structure(), to_hand_info.variability(), self.sensing.clone()) * max_error_cost;
-                    // let cost = get_robot_error_rate_exponential(to_hand_info.structure(), to_hand_info.variability(), self.sensing);
-                    // let cost = get_robot_error_rate_multiplicative(to_hand_info.structure(), to_hand_info.variability(), self.sensing);
+                    let cost = error_model.rate(&to_hand_info.structure(), &to_hand_info.variability(), &self.sensing) * max_error_cost;
 
                     let target_info = job.targets.get(target).unwrap();
 
-                    if target_info.weight() > (self.payload * 9.81) {
+                    if !robot_handles(self, transition, job, target_info) {
                         robo_ergo_costs.push(Data::MVC(*id, 0.001));
                     }
 
@@ -830,6 +861,7 @@ impl CostProfiler for RobotInfo {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Monetary,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::RobotError),
                     });
                 }
                 Primitive::Travel {
@@ -840,83 +872,30 @@ impl CostProfiler for RobotInfo {
                     to_hand,
                 } => {
                     let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
-                    let cost = get_robot_error_rate_independent(to_hand_info.structure(), to_hand_info.variability(), self.sensing.clone()) * max_error_cost;
-                    // let cost = get_robot_error_rate_exponential(to_hand_info.structure(), to_hand_info.variability(), self.sensing);
-                    // let cost = get_robot_error_rate_multiplicative(to_hand_info.structure(), to_hand_info.variability(), self.sensing);
+                    let cost = error_model.rate(&to_hand_info.structure(), &to_hand_info.variability(), &self.sensing) * max_error_cost;
 
                     // error cost
                     robot_cost_set.push(Cost {
                         frequency: CostFrequency::Extrapolated,
                         value: cost,
                         category: CostCategory::Monetary,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::RobotError),
                     });
                 }
                 Primitive::Hold { id, target } => {
                     let target_info = job.targets.get(target).unwrap();
 
-                    if target_info.weight() > (self.payload * 9.81) {
+                    if !robot_handles(self, transition, job, target_info) {
                         robo_ergo_costs.push(Data::MVC(*id, 0.001));
                     }
                 }
-                Primitive::Inspect { skill, ..} => {
-                    let mut cost = 0.0;
-                    if *skill == Rating::High && self.sensing == Rating::High {
-                        cost += 0.001;
-                    } else if *skill == Rating::High && self.sensing == Rating::Medium {
-                        cost += 0.02;
-                    } else if *skill == Rating::High && self.sensing == Rating::Low {
-                        cost += 0.1;
-                    } else if *skill == Rating::Medium && self.sensing == Rating::High {
-                        cost += 0.00075;
-                    } else if *skill == Rating::Medium && self.sensing == Rating::Medium {
-                        cost += 0.006;
-                    } else if *skill == Rating::Medium && self.sensing == Rating::Low {
-                        cost += 0.015;
-                    } else if *skill == Rating::Low && self.sensing == Rating::High {
-                        cost += 0.0005;
-                    } else if *skill == Rating::Low && self.sensing == Rating::Medium {
-                        cost += 0.002;
-                    } else if *skill == Rating::Low && self.sensing == Rating::Low {
-                        cost += 0.005;
-                    }
-                    cost *= max_error_cost;
-
-                    // error cost
-                    robot_cost_set.push(Cost {
-                        frequency: CostFrequency::Extrapolated,
-                        value: cost,
-                        category: CostCategory::Monetary,
-                    });
-                }
-                Primitive::Selection { skill, ..} => {
-                    
-                    let mut cost = 0.0;
-                    if *skill == Rating::High && self.sensing == Rating::High {
-                        cost += 0.001;
-                    } else if *skill == Rating::High && self.sensing == Rating::Medium {
-                        cost += 0.02;
-                    } else if *skill == Rating::High && self.sensing == Rating::Low {
-                        cost += 0.1;
-                    } else if *skill == Rating::Medium && self.sensing == Rating::High {
-                        cost += 0.00075;
-                    } else if *skill == Rating::Medium && self.sensing == Rating::Medium {
-                        cost += 0.006;
-                    } else if *skill == Rating::Medium && self.sensing == Rating::Low {
-                        cost += 0.015;
-                    } else if *skill == Rating::Low && self.sensing == Rating::High {
-                        cost += 0.0005;
-                    } else if *skill == Rating::Low && self.sensing == Rating::Medium {
-                        cost += 0.002;
-                    } else if *skill == Rating::Low && self.sensing == Rating::Low {
-                        cost += 0.005;
-                    }
-                    cost *= max_error_cost;
-
+                Primitive::Inspect { skill, .. } | Primitive::Selection { skill, .. } => {
                     // error cost
                     robot_cost_set.push(Cost {
                         frequency: CostFrequency::Extrapolated,
-                        value: cost,
+                        value: job.robot_judgement.rate(skill, &self.sensing) * max_error_cost,
                         category: CostCategory::Monetary,
+                        uncertainty: job.cost_uncertainty.of(Uncertainty::RobotError),
                     });
                 }
                 _ => {
@@ -929,6 +908,16 @@ impl CostProfiler for RobotInfo {
     }
 }
 
+impl CostProfiler for RobotInfo {
+    fn execution_time(&self, transition: &Transition, job: &Job) -> Time {
+        self.execution_time_in(transition, job, &job.shared_zones())
+    }
+
+    fn cost_set(&self, transition: &Transition, job: &Job) -> (CostSet, Vec<Data>) {
+        self.cost_set_in(transition, job, &job.shared_zones())
+    }
+}
+
 fn get_grade(point1: Vector3<f64>, point2: Vector3<f64>) -> f64 {
     let distance = (point1 - point2).norm();
     let height = point1.z - point2.z;
@@ -943,8 +932,28 @@ fn get_grade(point1: Vector3<f64>, point2: Vector3<f64>) -> f64 {
     }
 }
 
+// Value lost to an error in the transition: what a defect from its task costs downstream, or
+// the value of the products when it belongs to no task
+fn get_error_loss(transition: &Transition, job: &Job) -> f64 {
+    match transition.meta_data.iter().find(|d| d.tag() == DataTag::Task).and_then(|d| d.id()) {
+        Some(task) if job.tasks.contains_key(&task) => job.defect_loss(&task),
+        _ => get_produced_value(job),
+    }
+}
+
 fn get_produced_value(job: &Job) -> f64 {
     let mut total_value = 0.0;
+
+    // With a demand model, an error puts the expected value of one unit of the product mix at risk
+    if let Some(demand) = &job.demand {
+        for (id, target) in job.targets.iter() {
+            if let Target::Product { .. } = target {
+                total_value += target.value() * demand.mix_share(id);
+            }
+        }
+        return total_value;
+    }
+
     for (id, target) in job.targets.iter() {
         if let Target::Product { id, name, size, weight, symmetry, pois, value } = target {
             total_value += target.value();
@@ -954,137 +963,6 @@ fn get_produced_value(job: &Job) -> f64 {
     return total_value;
 }
 
-fn robot_error_rate_base(structure: Rating, variability: Rating) -> f64 {
-    if structure == Rating::High && variability == Rating::High {
-        return 0.6;
-    } else if structure == Rating::High && variability == Rating::Medium {
-        return 0.3;
-    } else if structure == Rating::High && variability == Rating::Low {
-        return 0.1;
-    } else if structure == Rating::Medium && variability == Rating::High {
-        return 0.75;
-    } else if structure == Rating::Medium && variability == Rating::Medium {
-        return 0.5;
-    } else if structure == Rating::Medium && variability == Rating::Low {
-        return 0.25;
-    } else if structure == Rating::Low && variability == Rating::High {
-        return 1.0;
-    } else if structure == Rating::Low && variability == Rating::Medium {
-        return 0.75;
-    } else {
-        return 0.4;
-    }
-}
-
-fn get_robot_error_rate_multiplicative(structure: Rating, variability: Rating, sensing: Rating) -> f64 {
-    let base_value = robot_error_rate_base(structure, variability);
-
-    if sensing == Rating::Low {
-        return base_value;
-    } else if sensing == Rating::Medium {
-        return 0.1 * base_value;
-    }
-
-    return 0.01 * base_value;
-}
-
-fn rating_to_f64(rating: Rating) -> f64 {
-    if rating == Rating::Low {
-        return 3.0;
-    } else if rating == Rating::Medium {
-        return 2.0;
-    }
-
-    return 1.0;
-}
-
-fn get_robot_error_rate_exponential(structure: Rating, variability: Rating, sensing: Rating) -> f64 {
-    return ((-4.0+rating_to_f64(variability)).exp()) + (-rating_to_f64(structure)).exp() + (-rating_to_f64(sensing)).exp();
-}
-
-fn get_robot_error_rate_independent(structure: Rating, variability: Rating, sensing: Rating) -> f64 {
-    if sensing == Rating::Low {
-        // not very resiliant to the environment (error prone)
-
-        if structure == Rating::High && variability == Rating::High {
-            return 0.5;
-        } else if structure == Rating::High && variability == Rating::Medium {
-            return 0.20;
-        } else if structure == Rating::High && variability == Rating::Low {
-            return 0.01;
-        } else if structure == Rating::Medium && variability == Rating::High {
-            return 0.8;
-        } else if structure == Rating::Medium && variability == Rating::Medium {
-            return 0.4;
-        } else if structure == Rating::Medium && variability == Rating::Low {
-            return 0.15;
-        } else if structure == Rating::Low && variability == Rating::High {
-            return 0.95;
-        } else if structure == Rating::Low && variability == Rating::Medium {
-            return 0.7;
-        } else {
-            return 0.3;
-        }
-    } else if sensing == Rating::Medium {
-        // moderately resiliant to the environment (somewhat error prone)
-
-        if structure == Rating::High && variability == Rating::High {
-            return 0.04;
-        } else if structure == Rating::High && variability == Rating::Medium {
-            return 0.015;
-        } else if structure == Rating::High && variability == Rating::Low {
-            return 0.004;
-        } else if structure == Rating::Medium && variability == Rating::High {
-            return 0.06;
-        } else if structure == Rating::Medium && variability == Rating::Medium {
-            return 0.02;
-        } else if structure == Rating::Medium && variability == Rating::Low {
-            return 0.01;
-        } else if structure == Rating::Low && variability == Rating::High {
-            return 0.1;
-        } else if structure == Rating::Low && variability == Rating::Medium {
-            return 0.06;
-        } else {
-            return 0.015;
-        }
-    } else {
-        // most resiliant to the environment (least prone to error)
-
-        if structure == Rating::High && variability == Rating::High {
-            return 0.006;
-        } else if structure == Rating::High && variability == Rating::Medium {
-            return 0.003;
-        } else if structure == Rating::High && variability == Rating::Low {
-            return 0.001;
-        } else if structure == Rating::Medium && variability == Rating::High {
-            return 0.0075;
-        } else if structure == Rating::Medium && variability == Rating::Medium {
-            return 0.005;
-        } else if structure == Rating::Medium && variability == Rating::Low {
-            return 0.0025;
-        } else if structure == Rating::Low && variability == Rating::High {
-            return 0.01;
-        } else if structure == Rating::Low && variability == Rating::Medium {
-            return 0.0075;
-        } else {
-            return 0.004;
-        }
-    }
-}
-
-const HUMAN_WEIGHT_FACTORS_MAPPING: [(f64, f64, f64, f64); 9] = [
-    // (LOWER BOUND, UPPER BOUND, FACTOR, CONSTANT)
-    (11.0853, 33.354, 1.06, 2.2),
-    (33.354, 55.6227, 1.11, 3.9),
-    (55.6227, 77.8914, 1.17, 5.6),
-    (77.8914, 100.1601, 1.22, 7.4),
-    (100.1601, 122.625, 1.28, 9.1),
-    (122.625, 144.5994, 1.33, 10.8),
-    (144.5994, 166.77, 1.39, 12.5),
-    (166.77, 188.9406, 1.44, 14.3),
-    (188.9406, f64::INFINITY, 1.5, 16.0),
-];
-
 fn get_assigned_primitives<'t>(
     transition: &'t Transition,
     job: &'t Job,
@@ -1166,6 +1044,14 @@ fn get_hand_stand_locations(
     return (hand_location, stand_location);
 }
 
+// Strength (N) at the worker's own percentile if known, otherwise at the job's target population,
+// scaled for age
+fn strength_capacity(agent: &HumanInfo, job: &Job, mean: f64, sd: f64) -> f64 {
+    let n = Normal::new(mean, sd).unwrap();
+    let capacity = n.inverse_cdf(agent.strength_percentile.unwrap_or(job.target_pop));
+    capacity * job.age_factors.strength(agent.age)
+}
+
 fn get_force_mvc(
     transition: &Transition,
     magnitude: &f64,
@@ -1187,105 +1073,81 @@ fn get_force_mvc(
     if !is_one_hand && *magnitude >= 0.0 {
         if hand_distance_to_floor < 0.5 {
             if agent.gender == Gender::Female {
-                let n = Normal::new(246.0, 51.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 246.0, 51.0);
             } else {
-                let n = Normal::new(362.0, 112.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 362.0, 112.0);
             }
         } else if hand_distance_to_floor < 1.0 {
             if agent.gender == Gender::Female {
-                let n = Normal::new(339.0, 76.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 339.0, 76.0);
             } else {
-                let n = Normal::new(520.0, 174.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 520.0, 174.0);
             }
         } else {
             if agent.gender == Gender::Female {
-                let n = Normal::new(272.0, 68.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 272.0, 68.0);
             } else {
-                let n = Normal::new(482.0, 165.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 482.0, 165.0);
             }
         }
     } else if !is_one_hand && *magnitude < 0.0 {
         if hand_distance_to_floor < 0.5 {
             if agent.gender == Gender::Female {
-                let n = Normal::new(209.0, 82.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 209.0, 82.0);
             } else {
-                let n = Normal::new(356.0, 61.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 356.0, 61.0);
             }
         } else if hand_distance_to_floor < 1.0 {
             if agent.gender == Gender::Female {
-                let n = Normal::new(521.0, 95.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 521.0, 95.0);
             } else {
-                let n = Normal::new(763.0, 202.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 763.0, 202.0);
             }
         } else {
             if agent.gender == Gender::Female {
-                let n = Normal::new(427.0, 76.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 427.0, 76.0);
             } else {
-                let n = Normal::new(744.0, 243.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 744.0, 243.0);
             }
         }
     } else if *magnitude >= 0.0 {
         if hand_distance_to_floor < ((1.0 - 0.336)*agent.height) {
             if agent.gender == Gender::Female {
-                let n = Normal::new(108.0, 18.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 108.0, 18.0);
             } else {
-                let n = Normal::new(147.0, 25.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 147.0, 25.0);
             }
         } else if hand_distance_to_floor < ((1.0 - 0.182)*agent.height) {
             if agent.gender == Gender::Female {
-                let n = Normal::new(107.0, 16.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 107.0, 16.0);
             } else {
-                let n = Normal::new(136.0, 26.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 136.0, 26.0);
             }
         } else {
             if agent.gender == Gender::Female {
-                let n = Normal::new(144.0, 25.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 144.0, 25.0);
             } else {
-                let n = Normal::new(201.0, 53.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 201.0, 53.0);
             }
         }
     } else {
         if hand_distance_to_floor < ((1.0 - 0.336)*agent.height) {
             if agent.gender == Gender::Female {
-                let n = Normal::new(128.0, 27.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 128.0, 27.0);
             } else {
-                let n = Normal::new(168.0, 33.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 168.0, 33.0);
             }
         } else if hand_distance_to_floor < ((1.0 - 0.182)*agent.height) {
             if agent.gender == Gender::Female {
-                let n = Normal::new(122.0, 29.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 122.0, 29.0);
             } else {
-                let n = Normal::new(145.0, 25.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 145.0, 25.0);
             }
         } else {
             if agent.gender == Gender::Female {
-                let n = Normal::new(178.0, 40.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 178.0, 40.0);
             } else {
-                let n = Normal::new(241.0, 61.0).unwrap();
-                denom += n.inverse_cdf(job.target_pop);
+                denom += strength_capacity(agent, job, 241.0, 61.0);
             }
         }
     }
@@ -1308,25 +1170,62 @@ fn vec_ergo_meta_data(agent: &HumanInfo, dist: f64, cost: f64) -> Vec<Data> {
     return result;
 }
 
-fn get_human_time_for_primitive(
+// Eye height as a fraction of stature
+const EYE_HEIGHT_RATIO: f64 = 0.936;
+
+// Bending is needed when a hand location is outside neutral reach and below the shoulder
+fn needs_bend(standing_poi: &PointOfInterest, hand_poi: &PointOfInterest, agent: &HumanInfo) -> bool {
+    let shoulder_height = standing_poi.position().z + agent.acromial_height;
+    !get_is_within_neutral_reach(standing_poi, hand_poi, agent.acromial_height, agent.reach)
+        && hand_poi.position().z < shoulder_height
+}
+
+// Reaches and moves use Fitts' law when the worker has coefficients, otherwise the MTM-1 tables
+fn human_motion_element(agent: &HumanInfo, element: MtmElement, width: f64) -> MtmElement {
+    let coefficients = match &agent.fitts {
+        Some(coefficients) => coefficients.clone(),
+        None => return element,
+    };
+    let (distance, weight) = match &element {
+        MtmElement::Reach { distance, .. } => (*distance, 0.0),
+        MtmElement::Move {
+            distance, weight, ..
+        } => (*distance, *weight),
+        _ => return element,
+    };
+    MtmElement::Pointing {
+        distance,
+        width,
+        weight,
+        coefficients,
+    }
+}
+
+// Positioning has to land within the tolerance of the hand location it happens at
+fn position_width(transition: &Transition, job: &Job, agent_id: Uuid, target: &Target) -> f64 {
+    let hand_poi = transition.meta_data.iter().find_map(|d| match d {
+        Data::Hand(poi_id, id) if *id == agent_id => job.points_of_interest.get(poi_id),
+        _ => None,
+    });
+    match hand_poi {
+        Some(poi) => target_width(poi, Some(target)),
+        None => target.size(),
+    }
+}
+
+fn get_human_mtm_for_primitive(
     assigned_primitives: Vec<&Primitive>,
     transition: &Transition,
     job: &Job,
     agent: &HumanInfo,
-) -> Time {
-    return match (assigned_primitives.len(), assigned_primitives.first()) {
-        (0, _) => {
-            // This is a no-op, so just return 0
-            0.0
-        }
-        (1, None) => {
-            // Technically impossible, but we cover it anyway. Return 0
-            0.0
-        }
+) -> MtmBreakdown {
+    let mut mtm = MtmBreakdown::new();
+    let walking_scale = 1.0 / job.age_factors.walking_speed(agent.age);
+
+    match (assigned_primitives.len(), assigned_primitives.first()) {
         (
             1,
             Some(Primitive::Carry {
-                target,
                 from_standing,
                 to_standing,
                 from_hand,
@@ -1334,51 +1233,31 @@ fn get_human_time_for_primitive(
                 ..
             }),
         ) => {
-            // Consider Carry Calculation
-
-            let mut tmu: f64 = 0.0;
-
             // Retrieve data
             let from_standing_info = job.points_of_interest.get(from_standing).unwrap();
             let to_standing_info = job.points_of_interest.get(to_standing).unwrap();
             let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
             let from_hand_info = job.points_of_interest.get(from_hand).unwrap();
 
-            // Grasp Time in TMU
-            tmu += 2.0;
-
-            // If the source hand is below the reachable area, based on standing location, add a time penalty
-            if get_is_within_neutral_reach(
-                from_standing_info,
-                from_hand_info,
-                agent.acromial_height,
-                agent.reach,
-            ) {
-                tmu += 30.5;
+            mtm.push(MtmElement::Grasp(GraspCase::G1A));
+            if needs_bend(from_standing_info, from_hand_info, agent) {
+                mtm.push(MtmElement::Bend);
             }
 
-            // Compute travel vector/distance
-            let travel_vector = to_standing_info.position() - from_standing_info.position();
-            let travel_distance = travel_vector.norm();
-            tmu += 17.0 * (travel_distance / 1.19);
-
-            // If the target hand is below the reachable area, based on standing location, add a tmu penalty
-            if get_is_within_neutral_reach(
-                to_standing_info,
-                to_hand_info,
-                agent.acromial_height,
-                agent.reach,
-            ) {
-                tmu += 30.5;
+            // Walking while carrying uses the loaded pace
+            let travel_distance = standing_travel_distance(job, from_standing, to_standing, false);
+            mtm.push_scaled(
+                MtmElement::Walk {
+                    distance: travel_distance,
+                    loaded: true,
+                },
+                walking_scale,
+            );
+
+            if needs_bend(to_standing_info, to_hand_info, agent) {
+                mtm.push(MtmElement::Bend);
             }
-
-            // Release time
-            tmu += 2.0;
-
-            // Convert from TMU to seconds
-            let time = tmu * TMU_PER_SECOND;
-
-            return time;
+            mtm.push(MtmElement::Release(ReleaseCase::RL1));
         }
         (
             1,
@@ -1390,121 +1269,46 @@ fn get_human_time_for_primitive(
                 ..
             }),
         ) => {
-            // Consider Move Calculation
-
-            let mut tmu: f64 = 0.0;
-
             // Retrieve data
             let standing_info = job.points_of_interest.get(standing).unwrap();
             let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
             let from_hand_info = job.points_of_interest.get(from_hand).unwrap();
             let target_info = job.targets.get(target).unwrap();
 
-            // Compute travel vector/distance
-            let motion_vector = to_hand_info.position() - from_hand_info.position();
-            let motion_distance = motion_vector.norm();
-
-            // Calculate Grasp Time
-            tmu += 2.0;
-
-            // If the source hand is below the reachable area, based on standing location, add a tmu penalty
-            if get_is_within_neutral_reach(
-                standing_info,
-                from_hand_info,
-                agent.acromial_height,
-                agent.reach,
-            ) {
-                tmu += 30.5;
+            mtm.push(MtmElement::Grasp(GraspCase::G1A));
+            if needs_bend(standing_info, from_hand_info, agent) {
+                mtm.push(MtmElement::Bend);
             }
-
-            // Movement tmu
-            let mut movement_tmu = 0.0;
-            match to_hand_info.variability() {
-                Rating::High => {
-                    if motion_distance < 0.0254 {
-                        movement_tmu += 2.0
-                    } else if 0.0254 <= motion_distance && motion_distance <= 0.1016 {
-                        movement_tmu += 3.6866 * motion_distance.powf(0.6146)
-                    } else if 0.1016 <= motion_distance && motion_distance <= 0.762 {
-                        movement_tmu += 5.959169 + 0.690797 * motion_distance
-                    } else {
-                        movement_tmu +=
-                            5.959169 + 0.690797 * motion_distance + 0.7 * (motion_distance - 0.762)
-                    }
-                }
-                Rating::Medium => {
-                    if motion_distance < 0.0254 {
-                        movement_tmu += 2.0
-                    } else if 0.0254 <= motion_distance && motion_distance <= 0.1016 {
-                        movement_tmu += 2.5 * motion_distance.powf(0.681)
-                    } else if 0.1016 <= motion_distance && motion_distance <= 0.762 {
-                        movement_tmu += 4.309488 + 0.71666 * motion_distance
-                    } else {
-                        movement_tmu +=
-                            4.309488 + 0.71666 * motion_distance + 0.7 * (motion_distance - 0.762)
-                    }
-                }
-                Rating::Low => {
-                    if motion_distance < 0.0254 {
-                        movement_tmu += 2.0
-                    } else if 0.0254 <= motion_distance && motion_distance <= 0.0762 {
-                        movement_tmu += 2.5 * motion_distance.powf(0.681)
-                    } else if 0.0762 <= motion_distance && motion_distance <= 0.762 {
-                        movement_tmu += 4.333601 + 0.440266 * motion_distance
-                    } else {
-                        movement_tmu +=
-                            4.333601 + 0.440266 * motion_distance + 0.4 * (motion_distance - 0.762)
-                    }
-                }
+            mtm.push(human_motion_element(
+                agent,
+                MtmElement::Move {
+                    case: MoveCase::from_variability(&to_hand_info.variability()),
+                    distance: (to_hand_info.position() - from_hand_info.position()).norm(),
+                    weight: target_info.weight(),
+                },
+                target_width(to_hand_info, Some(target_info)),
+            ));
+            if needs_bend(standing_info, to_hand_info, agent) {
+                mtm.push(MtmElement::Bend);
             }
-
-            // Depending on the weight of the object, add a tmu penalty
-            let weight = target_info.weight();
-            for (lower, upper, factor, constant) in HUMAN_WEIGHT_FACTORS_MAPPING {
-                if lower < weight && weight <= upper {
-                    movement_tmu += factor * weight + constant;
-                    break;
-                }
-            }
-            tmu += movement_tmu;
-
-            if get_is_within_neutral_reach(
-                standing_info,
-                to_hand_info,
-                agent.acromial_height,
-                agent.reach,
-            ) {
-                tmu += 30.5;
-            }
-
-            // Release tmu
-            tmu += 2.0;
-
-            let time = tmu * TMU_PER_SECOND;
-            return time;
+            mtm.push(MtmElement::Release(ReleaseCase::RL1));
         }
         (
             1,
             Some(Primitive::Travel {
                 from_standing,
                 to_standing,
-                from_hand,
-                to_hand,
                 ..
             }),
         ) => {
-            // Consider Travel Calculation
-
-            // Retrieve data
-            let from_standing_info = job.points_of_interest.get(from_standing).unwrap();
-            let to_standing_info = job.points_of_interest.get(to_standing).unwrap();
-
-            // Compute travel vector/distance
-            let travel_vector = to_standing_info.position() - from_standing_info.position();
-            let travel_distance = travel_vector.norm();
-
-            // Compute carry time
-            return (15.0 * (travel_distance / DISTANCE_PER_PACE)) * TMU_PER_SECOND;
+            let travel_distance = standing_travel_distance(job, from_standing, to_standing, false);
+            mtm.push_scaled(
+                MtmElement::Walk {
+                    distance: travel_distance,
+                    loaded: false,
+                },
+                walking_scale,
+            );
         }
         (
             1,
@@ -1512,426 +1316,168 @@ fn get_human_time_for_primitive(
                 from_hand, to_hand, ..
             }),
         ) => {
-            // Consider Reach Calculation
-
-            // Retrieve data
             let from_hand_info = job.points_of_interest.get(from_hand).unwrap();
             let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
-
-            let motion_vector = to_hand_info.position() - from_hand_info.position();
-            let motion_distance = motion_vector.norm();
-
-            return match to_hand_info.variability() {
-                Rating::High => {
-                    if motion_distance < 0.0254 {
-                        2.0 * TMU_PER_SECOND
-                    } else if 0.0254 <= motion_distance && motion_distance <= 0.1016 {
-                        3.6866 * 39.37 * motion_distance.powf(0.6146) * TMU_PER_SECOND
-                    } else if 0.1016 <= motion_distance && motion_distance <= 0.762 {
-                        (5.959169 + 0.690797 * 39.37 * motion_distance) * TMU_PER_SECOND
-                    } else {
-                        (5.959169
-                            + 0.690797 * 39.37 * motion_distance
-                            + 0.7 * (39.37 * motion_distance - 0.762))
-                            * TMU_PER_SECOND
-                    }
-                }
-                Rating::Medium => {
-                    if motion_distance < 0.0254 {
-                        2.0 * TMU_PER_SECOND
-                    } else if 0.0254 <= motion_distance && motion_distance <= 0.1016 {
-                        2.5 * 39.37 * motion_distance.powf(0.681) * TMU_PER_SECOND
-                    } else if 0.1016 <= motion_distance && motion_distance <= 0.762 {
-                        (4.309488 + 0.71666 * 39.37 * motion_distance) * TMU_PER_SECOND
-                    } else {
-                        (4.309488
-                            + 0.71666 * 38.37 * motion_distance
-                            + 0.7 * (39.37 * motion_distance - 0.762))
-                            * TMU_PER_SECOND
-                    }
-                }
-                Rating::Low => {
-                    if motion_distance < 0.0254 {
-                        2.0 * TMU_PER_SECOND
-                    } else if 0.0254 <= motion_distance && motion_distance <= 0.0762 {
-                        2.5 * 39.37 * motion_distance.powf(0.681) * TMU_PER_SECOND
-                    } else if 0.0762 <= motion_distance && motion_distance <= 0.762 {
-                        (4.333601 + 0.440266 * 39.37 * motion_distance) * TMU_PER_SECOND
-                    } else {
-                        (4.333601
-                            + 0.440266 * 39.37 * motion_distance
-                            + 0.4 * (39.37 * motion_distance - 0.762))
-                            * TMU_PER_SECOND
-                    }
-                }
-            };
+            mtm.push(human_motion_element(
+                agent,
+                MtmElement::Reach {
+                    case: ReachCase::from_variability(&to_hand_info.variability()),
+                    distance: (to_hand_info.position() - from_hand_info.position()).norm(),
+                },
+                target_width(to_hand_info, None),
+            ));
         }
         (
             1,
             Some(Primitive::Force {
-                id,
-                target,
-                magnitude,
-                ..
+                target, magnitude, ..
             }),
         ) => {
-            // Consider Force Calculation
-            let mut tmu: f64 = 0.0;
-
-            let target_info = job.targets.get(target).unwrap();
-            let weight = target_info.weight();
+            let weight = job.targets.get(target).unwrap().weight();
 
             if *magnitude >= 0.0 {
                 // Apply force, dwell minimum, and release
-                return 10.6 * TMU_PER_SECOND;
-            }
-
-            // grasp TMU
-            tmu += 2.0;
-
-            let (mvc, _hand_to_floor_dist, _dist, _is_one_hand) =
-                get_force_mvc(transition, magnitude, agent, job, weight);
-
-            if mvc < 0.2 {
-                if weight < 8.9271 {
-                    tmu += 4.0;
-                } else {
-                    tmu += 5.7;
-                }
-            } else if mvc < 0.5 {
-                if weight < 8.9271 {
-                    tmu += 7.5;
-                } else {
-                    tmu += 11.8;
-                }
+                mtm.push(MtmElement::ApplyPressure(PressureCase::A));
             } else {
-                if weight < 8.9271 {
-                    tmu += 22.9;
-                } else {
-                    tmu += 34.7;
-                }
+                // Pulling free is a disengage, classed by how much strength it takes
+                let (mvc, _hand_to_floor_dist, _dist, _is_one_hand) =
+                    get_force_mvc(transition, magnitude, agent, job, weight);
+                mtm.push(MtmElement::Grasp(GraspCase::G1A));
+                mtm.push(MtmElement::Disengage {
+                    fit: Fit::from_mvc(mvc),
+                    easy: weight < SMALL_WEIGHT,
+                });
+                mtm.push(MtmElement::Release(ReleaseCase::RL1));
             }
-
-            // release TMU
-            tmu += 2.0;
-
-            // Convert TMU to seconds
-            let time = tmu * TMU_PER_SECOND;
-
-            return time;
         }
         (
             1,
             Some(Primitive::Position {
-                id,
                 target,
                 degrees,
                 displacement,
                 ..
             }),
         ) => {
-            // Consider Force Calculation
-            let mut tmu: f64 = 0.0;
-
-            let deg: f64 = *degrees;
-            let dis: f64 = *displacement;
-
             let target_info = job.targets.get(target).unwrap();
             let weight = target_info.weight();
 
-            // Grasp tmu
-            tmu += 2.0;
-
-            if deg > 0.0 {
-                if weight < 8.9271 {
-                    tmu += 1.4927 + 0.043878 * deg;
-                } else if weight < 44.5374 {
-                    tmu += 2.3463636 + 0.0689090 * deg;
-                } else {
-                    tmu += 4.4781818 + 0.131636 * deg;
-                }
+            mtm.push(MtmElement::Grasp(GraspCase::G1A));
+            if *degrees > 0.0 {
+                mtm.push(MtmElement::Turn {
+                    degrees: *degrees,
+                    weight,
+                });
             }
-
-            if dis > 0.0 {
-                let mut movement_tmu = 0.0;
-                if dis < 0.0254 {
-                    movement_tmu += 2.0;
-                } else if 0.0254 <= dis && dis <= 0.127 {
-                    movement_tmu += 2.91 * dis.powf(0.660);
-                } else if 0.1016 <= dis && dis <= 0.762 {
-                    movement_tmu += 5.647749 + 0.625535 * dis;
-                } else {
-                    movement_tmu +=
-                        5.647749 + 0.625535 * dis + 0.6 * (dis - 0.762);
-                }
-
-                // Depending on the weight of the object, add a tmu penalty
-                let weight = target_info.weight();
-                for (lower, upper, factor, constant) in HUMAN_WEIGHT_FACTORS_MAPPING {
-                    if lower < weight && weight <= upper {
-                        movement_tmu += factor * weight + constant;
-                        break;
-                    }
-                }
-                tmu += movement_tmu;
-    
+            // The move tables have no tolerance term, so fine placement is always timed by Fitts' law
+            if *displacement > 0.0 {
+                mtm.push(MtmElement::Pointing {
+                    distance: *displacement,
+                    width: position_width(transition, job, agent.id, target_info),
+                    weight,
+                    coefficients: agent.fitts.clone().unwrap_or_else(FittsCoefficients::human),
+                });
             }
-
-            // Release tmu
-            tmu += 2.0;
-
-            // Convert TMU to seconds
-            let time = tmu * TMU_PER_SECOND;
-
-            return time;
+            // Seating the part within a toleranced location
+            let tolerance = transition.meta_data.iter().find_map(|d| match d {
+                Data::Hand(poi_id, id) if *id == agent.id => job.points_of_interest.get(poi_id).and_then(|poi| poi.tolerance()),
+                _ => None,
+            });
+            if let Some(tolerance) = tolerance {
+                mtm.push(MtmElement::Position {
+                    fit: Fit::from_tolerance(tolerance),
+                    symmetry: target_info.symmetry(),
+                    easy: weight < SMALL_WEIGHT,
+                });
+            }
+            mtm.push(MtmElement::Release(ReleaseCase::RL1));
         }
-        (
-            1,
-            Some(Primitive::Inspect {
-                id, target, skill, ..
-            }),
-        ) => {
-            // this primitive's time will be based on the time of the primitives coupled with it
-            0.0
+        (1, Some(Primitive::Inspect { target, .. })) => {
+            // Scan across the target from eye height, then focus on it
+            let has_hand = transition
+                .meta_data
+                .iter()
+                .any(|d| matches!(d, Data::Hand(_, agent_id) if *agent_id == agent.id));
+            if has_hand {
+                let (hand_location, stand_location) = get_hand_stand_locations(transition, agent, job);
+                let eyes = stand_location + Vector3::new(0.0, 0.0, agent.height * EYE_HEIGHT_RATIO);
+                mtm.push(MtmElement::EyeTravel {
+                    travel: job.targets.get(target).unwrap().size(),
+                    distance: (hand_location - eyes).norm(),
+                });
+            }
+            mtm.push(MtmElement::EyeFocus);
         }
         (1, Some(Primitive::Selection { target, .. })) => {
-            let target_object = job.targets.get(target).unwrap();
-            let target_size = target_object.size();
-
-            if target_size < 0.00635 {
-                return 12.9 * TMU_PER_SECOND;
-            } else if target_size < 0.0254 {
-                return 9.1 * TMU_PER_SECOND;
-            }
-            return 7.3 * TMU_PER_SECOND;
+            let target_size = job.targets.get(target).unwrap().size();
+            mtm.push(MtmElement::Grasp(GraspCase::jumbled(target_size)));
         }
-        (2, Some(Primitive::Position { id, target, .. })) => {
-            return match assigned_primitives.last() {
-                Some(Primitive::Force {
-                    id,
-                    target,
-                    magnitude,
-                }) => {
-                    let target_info = job.targets.get(target).unwrap();
-                    let weight = target_info.weight();
-                    let mut tmu = 0.0;
-
-                    // Grasp TMU
-                    tmu += 2.0;
-
-                    let (mvc, _hand_to_floor_dist, _dist, _is_one_hand) =
-                        get_force_mvc(transition, magnitude, agent, job, weight);
-
-                    if mvc < 0.2 {
-                        match target_info.symmetry() {
-                            Rating::High => {
-                                if weight < 8.9271 {
-                                    tmu += 5.6;
-                                } else {
-                                    tmu += 11.2;
-                                }
-                            }
-                            Rating::Medium => {
-                                if weight < 8.9271 {
-                                    tmu += 9.1;
-                                } else {
-                                    tmu += 14.7;
-                                }
-                            }
-                            Rating::Low => {
-                                if weight < 8.9271 {
-                                    tmu += 10.4;
-                                } else {
-                                    tmu += 16.0;
-                                }
-                            }
-                        }
-                    } else if mvc < 0.5 {
-                        match target_info.symmetry() {
-                            Rating::High => {
-                                if weight < 8.9271 {
-                                    tmu += 16.2;
-                                } else {
-                                    tmu += 21.8;
-                                }
-                            }
-                            Rating::Medium => {
-                                if weight < 8.9271 {
-                                    tmu += 19.7;
-                                } else {
-                                    tmu += 25.3;
-                                }
-                            }
-                            Rating::Low => {
-                                if weight < 8.9271 {
-                                    tmu += 21.0;
-                                } else {
-                                    tmu += 26.6;
-                                }
-                            }
-                        }
-                    } else {
-                        match target_info.symmetry() {
-                            Rating::High => {
-                                if weight < 8.9271 {
-                                    tmu += 43.0;
-                                } else {
-                                    tmu += 48.6;
-                                }
-                            }
-                            Rating::Medium => {
-                                if weight < 8.9271 {
-                                    tmu += 46.5;
-                                } else {
-                                    tmu += 52.1;
-                                }
-                            }
-                            Rating::Low => {
-                                if weight < 8.9271 {
-                                    tmu += 47.8;
-                                } else {
-                                    tmu += 53.4;
-                                }
-                            }
-                        }
-                    }
-
-                    // release TMU
-                    tmu += 2.0;
-
-                    // convert tmu to seconds
-                    let time = tmu * TMU_PER_SECOND;
-
-                    return time;
+        (2, Some(Primitive::Position { .. })) | (2, Some(Primitive::Force { .. })) => {
+            // Positioning under force is an engagement, classed by how much strength it takes
+            let pair = (assigned_primitives[0], assigned_primitives[1]);
+            let (target, magnitude) = match pair {
+                (Primitive::Position { target, .. }, Primitive::Force { magnitude, .. })
+                | (Primitive::Force { magnitude, .. }, Primitive::Position { target, .. }) => {
+                    (target, magnitude)
                 }
-                _ => 0.0,
+                _ => return mtm,
             };
-        }
-        (
-            2,
-            Some(Primitive::Force {
-                id,
-                target,
-                magnitude,
-                ..
-            }),
-        ) => {
-            return match assigned_primitives.last() {
-                Some(Primitive::Position {
-                    id,
-                    target,
-                    degrees,
-                    displacement,
-                }) => {
-                    let target_info = job.targets.get(target).unwrap();
-                    let weight = target_info.weight();
-                    let mut tmu = 0.0;
-
-                    // Grasp TMU
-                    tmu += 2.0;
-
-                    let (mvc, _hand_to_floor_dist, _dist, _is_one_hand) =
-                        get_force_mvc(transition, magnitude, agent, job, weight);
-
-                    if mvc < 0.2 {
-                        match target_info.symmetry() {
-                            Rating::High => {
-                                if weight < 8.9271 {
-                                    tmu += 5.6;
-                                } else {
-                                    tmu += 11.2;
-                                }
-                            }
-                            Rating::Medium => {
-                                if weight < 8.9271 {
-                                    tmu += 9.1;
-                                } else {
-                                    tmu += 14.7;
-                                }
-                            }
-                            Rating::Low => {
-                                if weight < 8.9271 {
-                                    tmu += 10.4;
-                                } else {
-                                    tmu += 16.0;
-                                }
-                            }
-                        }
-                    } else if mvc < 0.5 {
-                        match target_info.symmetry() {
-                            Rating::High => {
-                                if weight < 8.9271 {
-                                    tmu += 16.2;
-                                } else {
-                                    tmu += 21.8;
-                                }
-                            }
-                            Rating::Medium => {
-                                if weight < 8.9271 {
-                                    tmu += 19.7;
-                                } else {
-                                    tmu += 25.3;
-                                }
-                            }
-                            Rating::Low => {
-                                if weight < 8.9271 {
-                                    tmu += 21.0;
-                                } else {
-                                    tmu += 26.6;
-                                }
-                            }
-                        }
-                    } else {
-                        match target_info.symmetry() {
-                            Rating::High => {
-                                if weight < 8.9271 {
-                                    tmu += 43.0;
-                                } else {
-                                    tmu += 48.6;
-                                }
-                            }
-                            Rating::Medium => {
-                                if weight < 8.9271 {
-                                    tmu += 46.5;
-                                } else {
-                                    tmu += 52.1;
-                                }
-                            }
-                            Rating::Low => {
-                                if weight < 8.9271 {
-                                    tmu += 47.8;
-                                } else {
-                                    tmu += 53.4;
-                                }
-                            }
-                        }
-                    }
-
-                    // release TMU
-                    tmu += 2.0;
-
-                    // convert tmu to seconds
-                    let time = tmu * TMU_PER_SECOND;
+            let target_info = job.targets.get(target).unwrap();
+            let weight = target_info.weight();
+            let (mvc, _hand_to_floor_dist, _dist, _is_one_hand) =
+                get_force_mvc(transition, magnitude, agent, job, weight);
 
-                    return time;
-                }
-                _ => 0.0,
-            };
+            mtm.push(MtmElement::Grasp(GraspCase::G1A));
+            mtm.push(MtmElement::Position {
+                fit: Fit::from_mvc(mvc),
+                symmetry: target_info.symmetry(),
+                easy: weight < SMALL_WEIGHT,
+            });
+            mtm.push(MtmElement::Release(ReleaseCase::RL1));
         }
-        (_, _) => {
-            // There is some non-zero number of assigned primitives. Compute them independently and run the max on them
-            0.0
+        _ => {
+            // No-ops, holds and uses take no time on their own
         }
-    };
+    }
+
+    mtm
+}
+
+// Whether the robot can carry the target with the tool mounted for the transition
+fn robot_handles(agent: &RobotInfo, transition: &Transition, job: &Job, target: &Target) -> bool {
+    match job.transition_tool(transition, agent.id) {
+        Some(tool) => tool.can_handle(agent, target),
+        None => job.robot_can_handle(agent, target),
+    }
 }
 
-fn get_robot_grasp_time(agent: &RobotInfo) -> f64 {
-    if agent.sensing == Rating::Low {
-        return 4.0;
+// Locating the part (based on sensing), then closing the tool on it
+fn get_robot_grasp_time(agent: &RobotInfo, tool: Option<&Tool>) -> f64 {
+    let locate_time = if agent.sensing == Rating::Low {
+        3.5
     } else if agent.sensing == Rating::Medium {
-        return 2.0;
+        1.5
     } else {
-        return 1.5;
+        1.0
+    };
+    locate_time + tool.map_or(DEFAULT_ACTUATION_TIME, |tool| tool.actuation_time)
+}
+
+// Travel between standing POIs. Transitions with no way through are dropped from the cost net
+// before they are timed or costed.
+fn standing_travel_distance(job: &Job, from_standing: &Uuid, to_standing: &Uuid, robot: bool) -> f64 {
+    job.travel_distance(from_standing, to_standing, robot).unwrap_or_default()
+}
+
+// Arm travel along its motion profile, plus the approach to the target tolerance when the robot
+// has Fitts coefficients
+fn get_robot_motion_time(agent: &RobotInfo, distance: f64, width: f64) -> Time {
+    let travel_time = agent.arm_motion.time(distance, agent.speed);
+    match &agent.fitts {
+        Some(coefficients) => {
+            travel_time + coefficients.time(distance, effective_width(width, agent.precision))
+        }
+        None => travel_time,
     }
 }
 
@@ -1962,26 +1508,35 @@ fn get_robot_time_for_primitive(
             }),
         ) => {
             // Calculate mobile base travel distance
-            let from_standing_info = job.points_of_interest.get(from_standing).unwrap();
-            let to_standing_info = job.points_of_interest.get(to_standing).unwrap();
-            let standing_vector = from_standing_info.position() - to_standing_info.position();
-            let standing_distance = standing_vector.norm();
-            let standing_travel_time = standing_distance / agent.mobile_speed;
+            let standing_distance = standing_travel_distance(job, from_standing, to_standing, true);
+            let standing_travel_time = agent.base_motion.time(standing_distance, agent.mobile_speed);
 
             // Calculate manipulator travel distance
             let from_hand_info = job.points_of_interest.get(from_hand).unwrap();
             let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
             let hand_vector = from_hand_info.position() - to_hand_info.position();
             let hand_distance = hand_vector.norm();
-            let hand_travel_time = hand_distance / agent.speed;
+            let target_info = job.targets.get(target).unwrap();
+            let hand_travel_time =
+                get_robot_motion_time(agent, hand_distance, target_width(to_hand_info, Some(target_info)));
             
             // grasp time
             let mut time_delta = 0.0;
-            time_delta += get_robot_grasp_time(agent);
-
-            // travel time
-            // TODO: should this be max or additive?
-            time_delta += standing_travel_time + hand_travel_time;
+            time_delta += get_robot_grasp_time(agent, job.transition_tool(transition, agent.id));
+
+            // travel time, depending on whether the arm moves while the base travels
+            time_delta += combine_segments(
+                &agent.coordination,
+                &agent.blending,
+                (
+                    standing_travel_time,
+                    agent.base_motion.ramp_time(standing_distance, agent.mobile_speed),
+                ),
+                (
+                    hand_travel_time,
+                    agent.arm_motion.ramp_time(hand_distance, agent.speed),
+                ),
+            );
 
             // release time
             time_delta += 1.0;
@@ -2003,11 +1558,13 @@ fn get_robot_time_for_primitive(
             let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
             let hand_vector = from_hand_info.position() - to_hand_info.position();
             let hand_distance = hand_vector.norm();
-            let hand_travel_time = hand_distance / agent.speed;
+            let target_info = job.targets.get(target).unwrap();
+            let hand_travel_time =
+                get_robot_motion_time(agent, hand_distance, target_width(to_hand_info, Some(target_info)));
 
             // grasp time
             let mut time_delta = 0.0;
-            time_delta += get_robot_grasp_time(agent);
+            time_delta += get_robot_grasp_time(agent, job.transition_tool(transition, agent.id));
 
             // travel time
             time_delta += hand_travel_time;
@@ -2028,11 +1585,8 @@ fn get_robot_time_for_primitive(
             }),
         ) => {
             // Calculate mobile base travel distance
-            let from_standing_info = job.points_of_interest.get(from_standing).unwrap();
-            let to_standing_info = job.points_of_interest.get(to_standing).unwrap();
-            let standing_vector = from_standing_info.position() - to_standing_info.position();
-            let standing_distance = standing_vector.norm();
-            let standing_travel_time = standing_distance / agent.mobile_speed;
+            let standing_distance = standing_travel_distance(job, from_standing, to_standing, true);
+            let standing_travel_time = agent.base_motion.time(standing_distance, agent.mobile_speed);
             return standing_travel_time;
         }
         (
@@ -2046,7 +1600,8 @@ fn get_robot_time_for_primitive(
             let to_hand_info = job.points_of_interest.get(to_hand).unwrap();
             let hand_vector = from_hand_info.position() - to_hand_info.position();
             let hand_distance = hand_vector.norm();
-            let hand_travel_time = hand_distance / agent.speed;
+            let hand_travel_time =
+                get_robot_motion_time(agent, hand_distance, target_width(to_hand_info, None));
             return hand_travel_time;
         }
         (
@@ -2061,7 +1616,7 @@ fn get_robot_time_for_primitive(
 
             // grasp time (based on precision and sensing)
             let mut time_delta = 0.0;
-            time_delta += get_robot_grasp_time(agent);
+            time_delta += get_robot_grasp_time(agent, job.transition_tool(transition, agent.id));
             
             // duration of force
             time_delta += 1.0;
@@ -2083,7 +1638,7 @@ fn get_robot_time_for_primitive(
         ) => {
             // TODO: fix this
             let mut time_delta = 0.0;
-            time_delta += get_robot_grasp_time(agent);
+            time_delta += get_robot_grasp_time(agent, job.transition_tool(transition, agent.id));
 
             let deg: f64 = *degrees;
             let dis: f64 = *displacement;
@@ -2094,7 +1649,9 @@ fn get_robot_time_for_primitive(
             }
             
             if dis > 0.0 {
-                time_delta += dis / agent.speed;
+                let target_info = job.targets.get(target).unwrap();
+                let width = position_width(transition, job, agent.id, target_info);
+                time_delta += get_robot_motion_time(agent, dis, width);
             }
 
             // release
@@ -2189,7 +1746,7 @@ fn get_robot_time_for_primitive(
                     let mut time_delta = 0.0;
 
                     // grasp time (based on precision)
-                    time_delta += get_robot_grasp_time(agent);
+                    time_delta += get_robot_grasp_time(agent, job.transition_tool(transition, agent.id));
 
                     // position time (by max speed)
                     time_delta += 0.5 + degrees / agent.speed;
@@ -2224,7 +1781,7 @@ fn get_robot_time_for_primitive(
                     let mut time_delta = 0.0;
 
                     // grasp time (based on precision)
-                    time_delta += get_robot_grasp_time(agent);
+                    time_delta += get_robot_grasp_time(agent, job.transition_tool(transition, agent.id));
 
                     // position time (by max speed)
                     time_delta += 0.5 + degrees / agent.speed;
@@ -2266,3 +1823,31 @@ fn test_grade() {
     let point7 = Vector3::new(0.0, 0.0, 1.1);
     assert_eq!(get_grade(point1, point7), 1.0);
 }
+
+#[test]
+fn human_placement_test() {
+    let mut job = Job::new("Placement".into(), 0.1);
+    let human = job.create_human_agent("Worker".into(), 30.0, Gender::Male, Rating::High, 20.0, 500.0);
+    let bench = job.create_hand_point_of_interest("Bench".into(), 0.3, 0.0, 0.9, None, None);
+    let pin = job.create_precursor_target("Pin".into(), 0.002, 0.1, Rating::High, vec![bench], 1.0);
+    let block = job.create_precursor_target("Block".into(), 0.1, 0.1, Rating::High, vec![bench], 1.0);
+    let transition = Transition::new(
+        "Place".into(),
+        HashMap::new(),
+        HashMap::new(),
+        vec![Data::Hand(bench, human)],
+        0.0,
+        vec![],
+    );
+    let agent = match &job.agents[&human] {
+        Agent::Human(agent) => agent.clone(),
+        _ => unreachable!(),
+    };
+
+    // Without fitted coefficients, a pin is still slower to place than a block is to drop
+    let place = |target: Uuid| {
+        let primitive = Primitive::new_position(target, 0.0, 0.1);
+        get_human_mtm_for_primitive(vec![&primitive], &transition, &job, &agent).seconds()
+    };
+    assert!(place(pin) > place(block));
+}
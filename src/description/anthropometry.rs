@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal};
+use std::fs;
+
+use super::gender::Gender;
+
+pub const DEFAULT_REGION: &str = "default";
+
+pub fn default_region() -> String {
+    DEFAULT_REGION.to_string()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Measure {
+    Height,         // meters
+    Reach,          // meters
+    AcromialHeight, // meters
+    Weight,         // kg
+}
+
+impl Measure {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().replace(['_', '-', ' '], "").as_str() {
+            "height" | "stature" => Some(Measure::Height),
+            "reach" | "armreach" => Some(Measure::Reach),
+            "acromialheight" | "shoulderheight" => Some(Measure::AcromialHeight),
+            "weight" | "mass" => Some(Measure::Weight),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PopulationEntry {
+    pub region: String,
+    pub gender: Gender,
+    pub measure: Measure,
+    pub mean: f64,
+    pub sd: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PopulationTable {
+    pub entries: Vec<PopulationEntry>,
+}
+
+impl Default for PopulationTable {
+    fn default() -> Self {
+        let mut table = PopulationTable { entries: vec![] };
+        table.insert(DEFAULT_REGION, Gender::Female, Measure::Height, 1.6285, 0.0642);
+        table.insert(DEFAULT_REGION, Gender::Female, Measure::Reach, 0.6930, 0.0428);
+        table.insert(DEFAULT_REGION, Gender::Female, Measure::Weight, 62.08, 8.33);
+        table.insert(DEFAULT_REGION, Gender::Male, Measure::Height, 1.7562, 0.0686);
+        table.insert(DEFAULT_REGION, Gender::Male, Measure::Reach, 0.7569, 0.0437);
+        table.insert(DEFAULT_REGION, Gender::Male, Measure::Weight, 78.75, 11.0);
+        table
+    }
+}
+
+impl PopulationTable {
+    pub fn insert(&mut self, region: &str, gender: Gender, measure: Measure, mean: f64, sd: f64) {
+        self.entries
+            .retain(|e| !(e.region == region && e.gender == gender && e.measure == measure));
+        self.entries.push(PopulationEntry {
+            region: region.to_string(),
+            gender,
+            measure,
+            mean,
+            sd,
+        });
+    }
+
+    // Parses rows of `region,gender,measure,mean,sd`. A header row and lines starting with '#' are skipped.
+    pub fn from_csv_str(text: &str) -> Result<Self, String> {
+        let mut table = PopulationTable { entries: vec![] };
+        for (line_idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let columns: Vec<&str> = line.split(',').map(|c| c.trim()).collect();
+            if columns.len() != 5 {
+                return Err(format!(
+                    "Line {}: expected 5 columns (region,gender,measure,mean,sd), found {}",
+                    line_idx + 1,
+                    columns.len()
+                ));
+            }
+            let gender = match columns[1].to_lowercase().as_str() {
+                "female" | "f" => Gender::Female,
+                "male" | "m" => Gender::Male,
+                _ if line_idx == 0 => continue,
+                other => return Err(format!("Line {}: unknown gender '{}'", line_idx + 1, other)),
+            };
+            let measure = match Measure::parse(columns[2]) {
+                Some(measure) => measure,
+                None => {
+                    return Err(format!(
+                        "Line {}: unknown measure '{}'",
+                        line_idx + 1,
+                        columns[2]
+                    ))
+                }
+            };
+            let mean: f64 = columns[3]
+                .parse()
+                .map_err(|_| format!("Line {}: invalid mean '{}'", line_idx + 1, columns[3]))?;
+            let sd: f64 = columns[4]
+                .parse()
+                .map_err(|_| format!("Line {}: invalid sd '{}'", line_idx + 1, columns[4]))?;
+            if sd <= 0.0 {
+                return Err(format!("Line {}: sd must be positive", line_idx + 1));
+            }
+            table.insert(columns[0], gender, measure, mean, sd);
+        }
+        Ok(table)
+    }
+
+    // Loads a table from a local `.json` file (serialized PopulationTable) or a `.csv` file
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        if path.to_lowercase().ends_with(".json") {
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))
+        } else {
+            Self::from_csv_str(&text)
+        }
+    }
+
+    // Falls back to the default region if the requested region has no entry for this measure
+    pub fn distribution(&self, region: &str, gender: &Gender, measure: &Measure) -> Option<Normal> {
+        self.entries
+            .iter()
+            .find(|e| e.region == region && e.gender == *gender && e.measure == *measure)
+            .or_else(|| {
+                self.entries.iter().find(|e| {
+                    e.region == DEFAULT_REGION && e.gender == *gender && e.measure == *measure
+                })
+            })
+            .and_then(|e| Normal::new(e.mean, e.sd).ok())
+    }
+
+    pub fn percentile(&self, region: &str, gender: &Gender, measure: &Measure, p: f64) -> Option<f64> {
+        self.distribution(region, gender, measure)
+            .map(|n| n.inverse_cdf(p))
+    }
+}
+
+// Fraction of stature between the acromion and the top of the head
+pub fn acromial_height_ratio(gender: &Gender) -> f64 {
+    match gender {
+        Gender::Female => 0.164,
+        Gender::Male => 0.182,
+    }
+}
+
+// Measured values for a single worker. Anything left as None is derived from the population table.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Anthropometry {
+    pub height: Option<f64>,          // meters
+    pub reach: Option<f64>,           // meters
+    pub acromial_height: Option<f64>, // meters
+    pub weight: Option<f64>,          // kg
+}
+
+impl Anthropometry {
+    // Returns (height, reach, acromial_height, weight), filling gaps from the population at percentile `p`.
+    // Measures missing from the table are taken from the built-in one.
+    pub fn resolve(
+        &self,
+        table: &PopulationTable,
+        region: &str,
+        gender: &Gender,
+        p: f64,
+    ) -> (f64, f64, f64, f64) {
+        let built_in = PopulationTable::default();
+        let lookup = |measure: Measure| {
+            table
+                .percentile(region, gender, &measure, p)
+                .or_else(|| built_in.percentile(region, gender, &measure, p))
+                .unwrap_or_default()
+        };
+        let height = self.height.unwrap_or_else(|| lookup(Measure::Height));
+        let reach = self.reach.unwrap_or_else(|| lookup(Measure::Reach));
+        let acromial_height = self.acromial_height.unwrap_or_else(|| {
+            table
+                .percentile(region, gender, &Measure::AcromialHeight, p)
+                .unwrap_or(height - acromial_height_ratio(gender) * height)
+        });
+        let weight = self.weight.unwrap_or_else(|| lookup(Measure::Weight));
+        (height, reach, acromial_height, weight)
+    }
+}
+
+#[test]
+fn population_csv_test() {
+    let table = PopulationTable::from_csv_str(
+        "region,gender,measure,mean,sd\n\
+         # stature in meters\n\
+         nordic,female,height,1.68,0.06\n\
+         nordic,male,stature,1.81,0.07\n",
+    )
+    .unwrap();
+    assert_eq!(table.entries.len(), 2);
+    let median = table
+        .percentile("nordic", &Gender::Male, &Measure::Height, 0.5)
+        .unwrap();
+    assert!((median - 1.81).abs() < 1e-9);
+    assert!(table
+        .percentile("nordic", &Gender::Male, &Measure::Reach, 0.5)
+        .is_none());
+    assert!(PopulationTable::from_csv_str("nordic,female,height,abc,0.06").is_err());
+
+    // Unknown regions fall back to the default table, and overrides always win
+    let defaults = PopulationTable::default();
+    let measured = Anthropometry {
+        reach: Some(0.8),
+        ..Default::default()
+    };
+    let (height, reach, acromial_height, _weight) =
+        measured.resolve(&defaults, "unknown", &Gender::Female, 0.5);
+    assert!((height - 1.6285).abs() < 1e-9);
+    assert_eq!(reach, 0.8);
+    assert!((acromial_height - (1.6285 - 0.164 * 1.6285)).abs() < 1e-9);
+
+    // A loaded table without reach or weight falls back to the built-in measures
+    let (height, reach, _acromial_height, weight) =
+        Anthropometry::default().resolve(&table, "nordic", &Gender::Male, 0.5);
+    assert!((height - 1.81).abs() < 1e-9);
+    assert!((reach - 0.7569).abs() < 1e-9);
+    assert!((weight - 78.75).abs() < 1e-9);
+}
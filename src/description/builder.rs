@@ -0,0 +1,889 @@
+use nalgebra::Vector3;
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::anthropometry::Anthropometry;
+use super::gender::Gender;
+use super::job::Job;
+use super::poi::PointOfInterest;
+use super::primitive::Primitive;
+use super::quality::DefectHandling;
+use super::rating::Rating;
+use super::safety::SafetyMode;
+use super::target::Target;
+use super::tool::Tool;
+use super::units::{Time, Watts, USD};
+use super::urdf::{ReachabilityMap, UrdfChain};
+
+// Handles to the parts of a job, so that one kind of id can't be passed where another is expected.
+// They are only handed out by the builders. Settings the builders don't cover are made on the built
+// job, with `uuid()`.
+macro_rules! typed_id {
+    ($name:ident) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        pub struct $name(Uuid);
+
+        impl $name {
+            pub fn uuid(&self) -> Uuid {
+                self.0
+            }
+        }
+
+        impl From<$name> for Uuid {
+            fn from(id: $name) -> Uuid {
+                id.0
+            }
+        }
+    };
+}
+
+typed_id!(TaskId);
+typed_id!(TargetId);
+typed_id!(PoiId);
+typed_id!(AgentId);
+typed_id!(PrimitiveId);
+typed_id!(ToolId);
+
+const DEFAULT_KWH_COST: USD = 0.1;
+// Sampling of the joint space when a robot's workspace is read from its URDF
+const URDF_SAMPLES: usize = 20000;
+const URDF_VOXEL_SIZE: f64 = 0.05; // meters
+
+fn check(valid: bool, message: String) -> Result<(), String> {
+    if valid {
+        Ok(())
+    } else {
+        Err(message)
+    }
+}
+
+fn check_positive(name: &str, field: &str, value: f64) -> Result<(), String> {
+    check(value.is_finite() && value > 0.0, format!("{}: {} must be positive, not {}", name, field, value))
+}
+
+fn check_non_negative(name: &str, field: &str, value: f64) -> Result<(), String> {
+    check(value.is_finite() && value >= 0.0, format!("{}: {} must not be negative, not {}", name, field, value))
+}
+
+// Builds a job from named settings with defaults, checking each part as it is added
+pub struct JobBuilder {
+    job: Job,
+}
+
+impl JobBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            job: Job::new(name.to_string(), DEFAULT_KWH_COST),
+        }
+    }
+
+    pub fn kwh_cost(&mut self, kwh_cost: USD) -> &mut Self {
+        self.job.kwh_cost = kwh_cost;
+        self
+    }
+
+    pub fn standing_poi(&mut self, name: &str) -> PoiBuilder<'_> {
+        PoiBuilder::new(&mut self.job, name, true)
+    }
+
+    pub fn hand_poi(&mut self, name: &str) -> PoiBuilder<'_> {
+        PoiBuilder::new(&mut self.job, name, false)
+    }
+
+    pub fn robot(&mut self, name: &str) -> RobotBuilder<'_> {
+        RobotBuilder::new(&mut self.job, name)
+    }
+
+    pub fn human(&mut self, name: &str) -> HumanBuilder<'_> {
+        HumanBuilder::new(&mut self.job, name)
+    }
+
+    pub fn precursor(&mut self, name: &str) -> TargetBuilder<'_> {
+        TargetBuilder::new(&mut self.job, name, TargetKind::Precursor)
+    }
+
+    pub fn intermediate(&mut self, name: &str) -> TargetBuilder<'_> {
+        TargetBuilder::new(&mut self.job, name, TargetKind::Intermediate)
+    }
+
+    pub fn product(&mut self, name: &str) -> TargetBuilder<'_> {
+        TargetBuilder::new(&mut self.job, name, TargetKind::Product)
+    }
+
+    pub fn reusable(&mut self, name: &str) -> TargetBuilder<'_> {
+        TargetBuilder::new(&mut self.job, name, TargetKind::Reusable)
+    }
+
+    pub fn task(&mut self, name: &str) -> TaskBuilder<'_> {
+        TaskBuilder::new(&mut self.job, name)
+    }
+
+    pub fn tool(&mut self, name: &str) -> ToolBuilder<'_> {
+        ToolBuilder::new(&mut self.job, name)
+    }
+
+    // Units of the product required every period (seconds), and its share of a total volume
+    pub fn demand(&mut self, product: TargetId, units: f64, mix: f64, period: Time) -> Result<&mut Self, String> {
+        match self.job.targets.get(&product.0) {
+            Some(Target::Product { .. }) => {}
+            Some(target) => return Err(format!("{}: only products are in demand", target.name())),
+            None => return Err(format!("Unknown target {}", product.0)),
+        }
+        check_non_negative("Demand", "units", units)?;
+        check_non_negative("Demand", "mix", mix)?;
+        check_positive("Demand", "period", period)?;
+        self.job.add_product_demand(product.0, units, mix, period)?;
+        Ok(self)
+    }
+
+    pub fn defect_handling(&mut self, task: TaskId, handling: DefectHandling) -> Result<&mut Self, String> {
+        check(self.job.tasks.contains_key(&task.0), format!("Unknown task {}", task.0))?;
+        if let DefectHandling::Rework { cost } = handling {
+            check_non_negative("Rework", "cost", cost)?;
+        }
+        self.job.set_defect_handling(task.0, handling);
+        Ok(self)
+    }
+
+    // Probability that the inspection catches a defect, in place of the one by its skill
+    pub fn inspection_detection(&mut self, inspect: PrimitiveId, detection: f64) -> Result<&mut Self, String> {
+        check(
+            matches!(self.job.primitives.get(&inspect.0), Some(Primitive::Inspect { .. })),
+            format!("{} is not an inspection", inspect.0),
+        )?;
+        check(
+            (0.0..=1.0).contains(&detection),
+            format!("Detection must be a probability, not {}", detection),
+        )?;
+        self.job.set_inspection_detection(inspect.0, detection);
+        Ok(self)
+    }
+
+    pub fn selection(&mut self, task: TaskId, target: TargetId, skill: Rating) -> Result<PrimitiveId, String> {
+        self.primitive(task, Primitive::new_selection(target.0, skill))
+    }
+
+    pub fn inspect(&mut self, task: TaskId, target: TargetId, skill: Rating) -> Result<PrimitiveId, String> {
+        self.primitive(task, Primitive::new_inspect(target.0, skill))
+    }
+
+    pub fn hold(&mut self, task: TaskId, target: TargetId) -> Result<PrimitiveId, String> {
+        self.primitive(task, Primitive::new_hold(target.0))
+    }
+
+    pub fn position(&mut self, task: TaskId, target: TargetId, degrees: f64, displacement: f64) -> Result<PrimitiveId, String> {
+        self.primitive(task, Primitive::new_position(target.0, degrees, displacement))
+    }
+
+    pub fn use_target(&mut self, task: TaskId, target: TargetId) -> Result<PrimitiveId, String> {
+        self.primitive(task, Primitive::new_use(target.0))
+    }
+
+    // Magnitude in Newtons
+    pub fn force(&mut self, task: TaskId, target: TargetId, magnitude: f64) -> Result<PrimitiveId, String> {
+        check_non_negative("Force", "magnitude", magnitude)?;
+        self.primitive(task, Primitive::new_force(target.0, magnitude))
+    }
+
+    fn primitive(&mut self, task: TaskId, primitive: Primitive) -> Result<PrimitiveId, String> {
+        let task_obj = self.job.tasks.get(&task.0).ok_or(format!("Unknown task {}", task.0))?;
+        if let Some(target) = primitive.target() {
+            check(
+                task_obj.dependencies.iter().chain(task_obj.output.iter()).any(|(t, _)| *t == target),
+                format!("{}: the primitive's target is not an input or output of the task", task_obj.name),
+            )?;
+        }
+        let id = primitive.id();
+        self.job.add_task_primitive(task.0, primitive);
+        Ok(PrimitiveId(id))
+    }
+
+    pub fn job(&self) -> &Job {
+        &self.job
+    }
+
+    pub fn build(self) -> Job {
+        self.job
+    }
+}
+
+pub struct PoiBuilder<'a> {
+    job: &'a mut Job,
+    name: String,
+    standing: bool,
+    position: (f64, f64, f64),
+    variability: Option<Rating>,
+    structure: Option<Rating>,
+    movable: Option<(Vector3<f64>, Vector3<f64>)>,
+}
+
+impl<'a> PoiBuilder<'a> {
+    fn new(job: &'a mut Job, name: &str, standing: bool) -> Self {
+        Self {
+            job,
+            name: name.to_string(),
+            standing,
+            position: (0.0, 0.0, 0.0),
+            variability: None,
+            structure: None,
+            movable: None,
+        }
+    }
+
+    // Meters
+    pub fn at(mut self, x: f64, y: f64, z: f64) -> Self {
+        self.position = (x, y, z);
+        self
+    }
+
+    pub fn variability(mut self, variability: Rating) -> Self {
+        self.variability = Some(variability);
+        self
+    }
+
+    pub fn structure(mut self, structure: Rating) -> Self {
+        self.structure = Some(structure);
+        self
+    }
+
+    // Corners (meters) of the box a layout optimizer may move it within
+    pub fn movable(mut self, a: Vector3<f64>, b: Vector3<f64>) -> Self {
+        self.movable = Some((a, b));
+        self
+    }
+
+    pub fn add(self) -> Result<PoiId, String> {
+        let (x, y, z) = self.position;
+        check(
+            x.is_finite() && y.is_finite() && z.is_finite(),
+            format!("{}: position must be finite", self.name),
+        )?;
+        if let Some((a, b)) = &self.movable {
+            check(
+                a.iter().chain(b.iter()).all(|c| c.is_finite()),
+                format!("{}: movable range must be finite", self.name),
+            )?;
+        }
+        let id = if self.standing {
+            self.job
+                .create_standing_point_of_interest(self.name, x, y, z, self.variability, self.structure)
+        } else {
+            self.job
+                .create_hand_point_of_interest(self.name, x, y, z, self.variability, self.structure)
+        };
+        if let Some((a, b)) = self.movable {
+            self.job.set_point_of_interest_movable(id, a, b);
+        }
+        Ok(PoiId(id))
+    }
+}
+
+pub struct RobotBuilder<'a> {
+    job: &'a mut Job,
+    name: String,
+    reach: f64,
+    vertical_offset: f64,
+    payload: f64,
+    agility: Rating,
+    speed: f64,
+    precision: f64,
+    sensing: Rating,
+    mobile_speed: f64,
+    purchase_price: USD,
+    energy_consumption: Watts,
+    idle_power: Option<Watts>,
+    annual_maintenance_cost: USD,
+    tools: Vec<ToolId>,
+    safety: Option<SafetyMode>,
+    // Path, tip link and mount yaw (radians)
+    urdf: Option<(String, Option<String>, f64)>,
+}
+
+impl<'a> RobotBuilder<'a> {
+    // A stationary collaborative arm
+    fn new(job: &'a mut Job, name: &str) -> Self {
+        Self {
+            job,
+            name: name.to_string(),
+            reach: 0.855,
+            vertical_offset: 1.0,
+            payload: 3.0,
+            agility: Rating::Medium,
+            speed: 2.0,
+            precision: 0.0001,
+            sensing: Rating::Medium,
+            mobile_speed: 0.0,
+            purchase_price: 20000.0,
+            energy_consumption: 300.0,
+            idle_power: None,
+            annual_maintenance_cost: 5000.0,
+            tools: vec![],
+            safety: None,
+            urdf: None,
+        }
+    }
+
+    // Meters
+    pub fn reach(mut self, reach: f64) -> Self {
+        self.reach = reach;
+        self
+    }
+
+    // Meters
+    pub fn vertical_offset(mut self, vertical_offset: f64) -> Self {
+        self.vertical_offset = vertical_offset;
+        self
+    }
+
+    // Kilograms
+    pub fn payload(mut self, payload: f64) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    pub fn agility(mut self, agility: Rating) -> Self {
+        self.agility = agility;
+        self
+    }
+
+    // Meters per second
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    // Meters of repeatability
+    pub fn precision(mut self, precision: f64) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn sensing(mut self, sensing: Rating) -> Self {
+        self.sensing = sensing;
+        self
+    }
+
+    // Meters per second, on a mobile base
+    pub fn mobile_speed(mut self, mobile_speed: f64) -> Self {
+        self.mobile_speed = mobile_speed;
+        self
+    }
+
+    pub fn purchase_price(mut self, purchase_price: USD) -> Self {
+        self.purchase_price = purchase_price;
+        self
+    }
+
+    pub fn energy_consumption(mut self, energy_consumption: Watts) -> Self {
+        self.energy_consumption = energy_consumption;
+        self
+    }
+
+    pub fn annual_maintenance_cost(mut self, annual_maintenance_cost: USD) -> Self {
+        self.annual_maintenance_cost = annual_maintenance_cost;
+        self
+    }
+
+    // Draw while waiting on other agents
+    pub fn idle_power(mut self, idle_power: Watts) -> Self {
+        self.idle_power = Some(idle_power);
+        self
+    }
+
+    // A tool it can mount
+    pub fn tool(mut self, tool: ToolId) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    // Defaults to a fenced cell
+    pub fn safety(mut self, safety: SafetyMode) -> Self {
+        self.safety = Some(safety);
+        self
+    }
+
+    // Read its workspace from the URDF, in place of a sphere of the given reach
+    pub fn urdf(mut self, path: &str, tip_link: Option<&str>, mount_yaw: f64) -> Self {
+        self.urdf = Some((path.to_string(), tip_link.map(|link| link.to_string()), mount_yaw));
+        self
+    }
+
+    pub fn add(self) -> Result<AgentId, String> {
+        check_positive(&self.name, "reach", self.reach)?;
+        check(self.vertical_offset.is_finite(), format!("{}: vertical offset must be finite", self.name))?;
+        check_non_negative(&self.name, "payload", self.payload)?;
+        check_positive(&self.name, "speed", self.speed)?;
+        check_non_negative(&self.name, "precision", self.precision)?;
+        check_non_negative(&self.name, "mobile speed", self.mobile_speed)?;
+        check_non_negative(&self.name, "purchase price", self.purchase_price)?;
+        check_non_negative(&self.name, "energy consumption", self.energy_consumption)?;
+        check_non_negative(&self.name, "annual maintenance cost", self.annual_maintenance_cost)?;
+        if let Some(idle_power) = self.idle_power {
+            check_non_negative(&self.name, "idle power", idle_power)?;
+        }
+        for tool in self.tools.iter() {
+            check(self.job.tools.contains_key(&tool.0), format!("{}: unknown tool {}", self.name, tool.0))?;
+        }
+        // Read before the robot is added, so that a bad file leaves the job as it was
+        let map = match &self.urdf {
+            Some((path, tip_link, mount_yaw)) => {
+                let chain = UrdfChain::from_file(path, tip_link.as_deref())?;
+                let mut map = ReachabilityMap::build(&chain, URDF_SAMPLES, URDF_VOXEL_SIZE, &mut thread_rng());
+                map.mount_yaw = *mount_yaw;
+                Some(map)
+            }
+            None => None,
+        };
+
+        let id = self.job.create_robot_agent(
+            self.name,
+            self.reach,
+            self.vertical_offset,
+            self.payload,
+            self.agility,
+            self.speed,
+            self.precision,
+            self.sensing,
+            self.mobile_speed,
+            self.purchase_price,
+            self.energy_consumption,
+            self.annual_maintenance_cost,
+        );
+        if let Some(idle_power) = self.idle_power {
+            self.job.set_robot_idle_power(id, idle_power);
+        }
+        for tool in self.tools {
+            self.job.add_robot_tool(id, tool.0);
+        }
+        if let Some(safety) = self.safety {
+            self.job.set_robot_safety(id, safety);
+        }
+        if let Some(map) = map {
+            self.job.set_robot_reachability_map(id, map)?;
+        }
+        Ok(AgentId(id))
+    }
+}
+
+pub struct ToolBuilder<'a> {
+    job: &'a mut Job,
+    name: String,
+    mass: f64,
+    price: USD,
+    min_size: f64,
+    max_size: f64,
+    symmetries: Vec<Rating>,
+    actuation_time: Time,
+    change_time: Time,
+}
+
+impl<'a> ToolBuilder<'a> {
+    // A two-finger gripper
+    fn new(job: &'a mut Job, name: &str) -> Self {
+        Self {
+            job,
+            name: name.to_string(),
+            mass: 0.5,
+            price: 1000.0,
+            min_size: 0.01,
+            max_size: 0.08,
+            symmetries: vec![],
+            actuation_time: 0.3,
+            change_time: 5.0,
+        }
+    }
+
+    // Kilograms, counted against the robot's payload
+    pub fn mass(mut self, mass: f64) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn price(mut self, price: USD) -> Self {
+        self.price = price;
+        self
+    }
+
+    // Meters, the range of part sizes it can grasp
+    pub fn sizes(mut self, min_size: f64, max_size: f64) -> Self {
+        self.min_size = min_size;
+        self.max_size = max_size;
+        self
+    }
+
+    // A part symmetry it can handle. Without any, it handles all of them.
+    pub fn symmetry(mut self, symmetry: Rating) -> Self {
+        self.symmetries.push(symmetry);
+        self
+    }
+
+    // Seconds to close on (or release) a part
+    pub fn actuation_time(mut self, actuation_time: Time) -> Self {
+        self.actuation_time = actuation_time;
+        self
+    }
+
+    // Seconds to mount it in place of another tool
+    pub fn change_time(mut self, change_time: Time) -> Self {
+        self.change_time = change_time;
+        self
+    }
+
+    pub fn add(self) -> Result<ToolId, String> {
+        check_non_negative(&self.name, "mass", self.mass)?;
+        check_non_negative(&self.name, "price", self.price)?;
+        check_non_negative(&self.name, "minimum size", self.min_size)?;
+        check(
+            self.max_size.is_finite() && self.max_size >= self.min_size,
+            format!("{}: maximum size must be at least the minimum", self.name),
+        )?;
+        check_non_negative(&self.name, "actuation time", self.actuation_time)?;
+        check_non_negative(&self.name, "change time", self.change_time)?;
+        let mut tool = Tool {
+            mass: self.mass,
+            price: self.price,
+            min_size: self.min_size,
+            max_size: self.max_size,
+            actuation_time: self.actuation_time,
+            change_time: self.change_time,
+            ..Tool::new(self.name)
+        };
+        if !self.symmetries.is_empty() {
+            tool.symmetries = self.symmetries;
+        }
+        Ok(ToolId(self.job.add_tool(tool)))
+    }
+}
+
+pub struct HumanBuilder<'a> {
+    job: &'a mut Job,
+    name: String,
+    age: f64,
+    gender: Gender,
+    skill: Rating,
+    hourly_wage: USD,
+    labor_cost: USD,
+    measured: Anthropometry,
+}
+
+impl<'a> HumanBuilder<'a> {
+    fn new(job: &'a mut Job, name: &str) -> Self {
+        Self {
+            job,
+            name: name.to_string(),
+            age: 30.0,
+            gender: Gender::Female,
+            skill: Rating::Medium,
+            hourly_wage: 20.0,
+            labor_cost: 500.0,
+            measured: Anthropometry::default(),
+        }
+    }
+
+    pub fn age(mut self, age: f64) -> Self {
+        self.age = age;
+        self
+    }
+
+    pub fn gender(mut self, gender: Gender) -> Self {
+        self.gender = gender;
+        self
+    }
+
+    pub fn skill(mut self, skill: Rating) -> Self {
+        self.skill = skill;
+        self
+    }
+
+    pub fn hourly_wage(mut self, hourly_wage: USD) -> Self {
+        self.hourly_wage = hourly_wage;
+        self
+    }
+
+    // Cost of hiring them
+    pub fn labor_cost(mut self, labor_cost: USD) -> Self {
+        self.labor_cost = labor_cost;
+        self
+    }
+
+    // Measured body dimensions, in place of those from the population table
+    pub fn anthropometry(mut self, measured: Anthropometry) -> Self {
+        self.measured = measured;
+        self
+    }
+
+    pub fn add(self) -> Result<AgentId, String> {
+        check_positive(&self.name, "age", self.age)?;
+        check_non_negative(&self.name, "hourly wage", self.hourly_wage)?;
+        check_non_negative(&self.name, "labor cost", self.labor_cost)?;
+        Ok(AgentId(self.job.create_human_agent_with_anthropometry(
+            self.name,
+            self.age,
+            self.gender,
+            self.skill,
+            self.hourly_wage,
+            self.labor_cost,
+            self.measured,
+        )))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TargetKind {
+    Precursor,
+    Intermediate,
+    Product,
+    Reusable,
+}
+
+pub struct TargetBuilder<'a> {
+    job: &'a mut Job,
+    name: String,
+    kind: TargetKind,
+    size: f64,
+    weight: f64,
+    symmetry: Rating,
+    pois: Vec<PoiId>,
+    value: USD,
+}
+
+impl<'a> TargetBuilder<'a> {
+    fn new(job: &'a mut Job, name: &str, kind: TargetKind) -> Self {
+        Self {
+            job,
+            name: name.to_string(),
+            kind,
+            size: 0.05,
+            weight: 0.1,
+            symmetry: Rating::Medium,
+            pois: vec![],
+            value: 0.0,
+        }
+    }
+
+    // Meters
+    pub fn size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    // Kilograms, not Newtons
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn symmetry(mut self, symmetry: Rating) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    // A hand POI where the target can be found
+    pub fn poi(mut self, poi: PoiId) -> Self {
+        self.pois.push(poi);
+        self
+    }
+
+    // Value of a precursor or product. Intermediates and reusables have none.
+    pub fn value(mut self, value: USD) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn add(self) -> Result<TargetId, String> {
+        check_positive(&self.name, "size", self.size)?;
+        check_non_negative(&self.name, "weight", self.weight)?;
+        check_non_negative(&self.name, "value", self.value)?;
+        check(
+            self.value == 0.0 || matches!(self.kind, TargetKind::Precursor | TargetKind::Product),
+            format!("{}: only precursors and products have a value", self.name),
+        )?;
+        for poi in self.pois.iter() {
+            match self.job.points_of_interest.get(&poi.0) {
+                Some(PointOfInterest::Hand(_)) => {}
+                Some(PointOfInterest::Standing(_)) => {
+                    return Err(format!("{}: targets are found at hand POIs, not standing ones", self.name))
+                }
+                None => return Err(format!("{}: unknown POI {}", self.name, poi.0)),
+            }
+        }
+        let pois: Vec<Uuid> = self.pois.iter().map(|poi| poi.0).collect();
+        let id = match self.kind {
+            TargetKind::Precursor => self
+                .job
+                .create_precursor_target(self.name, self.size, self.weight, self.symmetry, pois, self.value),
+            TargetKind::Intermediate => self
+                .job
+                .create_intermediate_target(self.name, self.size, self.weight, self.symmetry, pois),
+            TargetKind::Product => self
+                .job
+                .create_product_target(self.name, self.size, self.weight, self.symmetry, pois, self.value),
+            TargetKind::Reusable => self
+                .job
+                .create_reusable_target(self.name, self.size, self.weight, self.symmetry, pois),
+        };
+        Ok(TargetId(id))
+    }
+}
+
+pub struct TaskBuilder<'a> {
+    job: &'a mut Job,
+    name: String,
+    order: i32,
+    inputs: Vec<(TargetId, usize)>,
+    outputs: Vec<(TargetId, usize)>,
+    reusables: Vec<(TargetId, usize)>,
+    pois: Vec<PoiId>,
+}
+
+impl<'a> TaskBuilder<'a> {
+    fn new(job: &'a mut Job, name: &str) -> Self {
+        let order = job.tasks.len() as i32 + 1;
+        Self {
+            job,
+            name: name.to_string(),
+            order,
+            inputs: vec![],
+            outputs: vec![],
+            reusables: vec![],
+            pois: vec![],
+        }
+    }
+
+    // Defaults to the order the tasks are added in
+    pub fn order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn input(mut self, target: TargetId, count: usize) -> Self {
+        self.inputs.push((target, count));
+        self
+    }
+
+    pub fn output(mut self, target: TargetId, count: usize) -> Self {
+        self.outputs.push((target, count));
+        self
+    }
+
+    // A target the task uses and gives back, such as a fixture
+    pub fn reusable(mut self, target: TargetId, count: usize) -> Self {
+        self.reusables.push((target, count));
+        self
+    }
+
+    pub fn poi(mut self, poi: PoiId) -> Self {
+        self.pois.push(poi);
+        self
+    }
+
+    fn target(&self, target: &TargetId) -> Result<&Target, String> {
+        self.job
+            .targets
+            .get(&target.0)
+            .ok_or(format!("{}: unknown target {}", self.name, target.0))
+    }
+
+    pub fn add(self) -> Result<TaskId, String> {
+        for (target, count) in self.inputs.iter().chain(self.outputs.iter()).chain(self.reusables.iter()) {
+            let target = self.target(target)?;
+            check(*count > 0, format!("{}: needs at least one {}", self.name, target.name()))?;
+        }
+        for (target, _) in self.inputs.iter() {
+            if let Target::Reusable { name, .. } = self.target(target)? {
+                return Err(format!("{}: {} is reusable, so it is given back rather than used up", self.name, name));
+            }
+        }
+        for (target, _) in self.outputs.iter() {
+            match self.target(target)? {
+                Target::Precursor { name, .. } | Target::Reusable { name, .. } => {
+                    return Err(format!("{}: {} can't be an output, only an intermediate or product", self.name, name))
+                }
+                _ => {}
+            }
+        }
+        for (target, _) in self.reusables.iter() {
+            if !matches!(self.target(target)?, Target::Reusable { .. }) {
+                return Err(format!("{}: only reusable targets are given back", self.name));
+            }
+        }
+        for poi in self.pois.iter() {
+            check(
+                self.job.points_of_interest.contains_key(&poi.0),
+                format!("{}: unknown POI {}", self.name, poi.0),
+            )?;
+        }
+
+        let task = self.job.create_task(self.name, self.order);
+        for (target, count) in self.inputs {
+            self.job.add_task_dependency(task, target.0, count);
+        }
+        for (target, count) in self.outputs {
+            self.job.add_task_output(task, target.0, count);
+        }
+        for (target, count) in self.reusables {
+            self.job.add_task_reusable(task, target.0, count);
+        }
+        for poi in self.pois {
+            self.job.add_task_point_of_interest(task, poi.0);
+        }
+        Ok(TaskId(task))
+    }
+}
+
+#[test]
+fn job_builder_test() {
+    use super::agent::Agent;
+
+    let mut builder = JobBuilder::new("Cell");
+    let bench = builder.hand_poi("Bench").at(0.5, 0.0, 1.0).add().unwrap();
+    let floor = builder.standing_poi("Floor").add().unwrap();
+    let gripper = builder.tool("Gripper").sizes(0.02, 0.1).add().unwrap();
+    let vacuum = builder.tool("Vacuum").symmetry(Rating::High).add().unwrap();
+    let arm = builder
+        .robot("Arm")
+        .payload(5.0)
+        .purchase_price(30000.0)
+        .tool(gripper)
+        .safety(SafetyMode::SpeedSeparation)
+        .add()
+        .unwrap();
+    builder.human("Worker").hourly_wage(22.0).add().unwrap();
+    let part = builder.precursor("Part").weight(1.5).poi(bench).value(4.0).add().unwrap();
+    let product = builder.product("Product").weight(1.5).poi(bench).value(10.0).add().unwrap();
+    let assemble = builder.task("Assemble").input(part, 1).output(product, 1).add().unwrap();
+    builder.inspect(assemble, product, Rating::Low).unwrap();
+
+    // Mistakes are caught when the part is added
+    assert!(builder.robot("Arm").reach(-1.0).add().is_err());
+    assert!(builder.precursor("Part").poi(floor).add().is_err());
+    assert!(builder.intermediate("Sub").value(3.0).add().is_err());
+    assert!(builder.task("Remake").output(part, 1).add().is_err());
+    assert!(builder.tool("Gripper").sizes(0.1, 0.02).add().is_err());
+    assert!(builder.robot("Arm").urdf("missing.urdf", None, 0.0).add().is_err());
+    assert!(builder.demand(part, 10.0, 1.0, 3600.0).is_err());
+    builder.demand(product, 10.0, 1.0, 3600.0).unwrap();
+
+    // Handles only come from the builder that made them
+    let mut other = JobBuilder::new("Other");
+    let stray = other.precursor("Stray").add().unwrap();
+    assert!(builder.hold(assemble, stray).is_err());
+
+    let job = builder.build();
+    assert_eq!(job.agents.len(), 2);
+    match &job.agents[&arm.uuid()] {
+        Agent::Robot(robot) => {
+            assert_eq!(robot.tools, vec![gripper.uuid()]);
+            assert_eq!(robot.safety, SafetyMode::SpeedSeparation);
+            // The gripper takes parts of any symmetry, the vacuum only the one it was given
+            let part = &job.targets[&part.uuid()];
+            assert!(job.robot_can_handle(robot, part));
+            assert!(job.tools[&gripper.uuid()].can_handle(robot, part));
+            assert!(!job.tools[&vacuum.uuid()].can_handle(robot, part));
+        }
+        _ => panic!("The arm is not a robot"),
+    }
+    assert_eq!(job.tasks[&assemble.uuid()].primitives.len(), 1);
+    assert_eq!(job.targets[&part.uuid()].weight(), 1.5 * crate::constants::GRAVITY);
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::units::Time;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductDemand {
+    pub units: f64, // required units per period
+    pub mix: f64,   // relative share of the total volume
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Demand {
+    pub period: Time,             // seconds
+    pub total_units: Option<f64>, // units per period, split across products by mix
+    pub products: HashMap<Uuid, ProductDemand>,
+}
+
+impl Demand {
+    pub fn new(period: Time) -> Self {
+        Self {
+            period,
+            total_units: None,
+            products: HashMap::new(),
+        }
+    }
+
+    pub fn set_total_units(&mut self, total_units: f64) {
+        self.total_units = Some(total_units);
+    }
+
+    pub fn add_product(&mut self, target: Uuid, units: f64, mix: f64) {
+        self.products.insert(target, ProductDemand { units, mix });
+    }
+
+    pub fn mix_share(&self, target: &Uuid) -> f64 {
+        let total_mix: f64 = self.products.values().map(|p| p.mix).sum();
+        match self.products.get(target) {
+            Some(product) if total_mix > 0.0 => product.mix / total_mix,
+            Some(_) => 1.0 / self.products.len() as f64,
+            None => 0.0,
+        }
+    }
+
+    // The explicit unit requirement or the mix share of the total volume, whichever is larger
+    pub fn required_units(&self, target: &Uuid) -> f64 {
+        match self.products.get(target) {
+            Some(product) => {
+                let mixed = self.total_units.unwrap_or(0.0) * self.mix_share(target);
+                product.units.max(mixed)
+            }
+            None => 0.0,
+        }
+    }
+
+    pub fn required(&self) -> HashMap<Uuid, f64> {
+        self.products
+            .keys()
+            .map(|target| (*target, self.required_units(target)))
+            .collect()
+    }
+}
+
+#[test]
+fn demand_mix_test() {
+    let a = Uuid::new_v4();
+    let b = Uuid::new_v4();
+    let mut demand = Demand::new(3600.0);
+    demand.add_product(a, 10.0, 3.0);
+    demand.add_product(b, 0.0, 1.0);
+    assert_eq!(demand.required_units(&a), 10.0);
+    assert_eq!(demand.required_units(&b), 0.0);
+
+    demand.set_total_units(100.0);
+    assert!((demand.mix_share(&a) - 0.75).abs() < f64::EPSILON);
+    assert!((demand.required_units(&a) - 75.0).abs() < f64::EPSILON);
+    assert!((demand.required_units(&b) - 25.0).abs() < f64::EPSILON);
+    assert_eq!(demand.required_units(&Uuid::new_v4()), 0.0);
+}
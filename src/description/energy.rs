@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::constants::SEC_PER_HOUR;
+
+use super::agent::{Agent, RobotInfo};
+use super::job::Job;
+use super::units::{Time, Watts, USD};
+
+// Standby draw of a robot with no idle power given, as a share of its active draw
+pub const DEFAULT_IDLE_POWER_FRACTION: f64 = 0.2;
+
+const SEC_PER_DAY: Time = 24.0 * SEC_PER_HOUR;
+
+// Energy in kWh of drawing the power for the duration
+pub fn kwh(power: Watts, duration: Time) -> f64 {
+    power * duration / SEC_PER_HOUR / 1000.0
+}
+
+// A price that applies between two times of day (seconds after midnight). It wraps past
+// midnight when the end comes before the start.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TariffPeriod {
+    pub start: Time,
+    pub end: Time,
+    pub kwh_cost: USD,
+}
+
+impl TariffPeriod {
+    pub fn contains(&self, time_of_day: Time) -> bool {
+        if self.start <= self.end {
+            time_of_day >= self.start && time_of_day < self.end
+        } else {
+            time_of_day >= self.start || time_of_day < self.end
+        }
+    }
+}
+
+// Time-of-use prices. Times outside every period pay the job's flat kwh_cost.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Tariff {
+    pub periods: Vec<TariffPeriod>,
+}
+
+impl Tariff {
+    pub fn add_period(&mut self, start: Time, end: Time, kwh_cost: USD) {
+        self.periods.push(TariffPeriod {
+            start: start.rem_euclid(SEC_PER_DAY),
+            end: end.rem_euclid(SEC_PER_DAY),
+            kwh_cost,
+        });
+    }
+
+    // Times of day at which the price may change
+    fn boundaries(&self) -> Vec<Time> {
+        let mut boundaries: Vec<Time> = self.periods.iter().flat_map(|p| [p.start, p.end]).collect();
+        boundaries.push(0.0);
+        boundaries.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        boundaries.dedup();
+        boundaries
+    }
+}
+
+// The working hours, from a time of day (seconds after midnight)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Shift {
+    pub start: Time,
+    pub length: Time,
+}
+
+impl Default for Shift {
+    fn default() -> Self {
+        Self {
+            start: 8.0 * SEC_PER_HOUR,
+            length: 8.0 * SEC_PER_HOUR,
+        }
+    }
+}
+
+impl RobotInfo {
+    pub fn standby_power(&self) -> Watts {
+        self.idle_power
+            .unwrap_or(self.energy_consumption * DEFAULT_IDLE_POWER_FRACTION)
+    }
+}
+
+impl Job {
+    pub fn set_energy_tariff(&mut self, tariff: Tariff) {
+        self.tariff = Some(tariff);
+    }
+
+    pub fn set_shift(&mut self, start: Time, length: Time) {
+        self.shift = Shift { start, length };
+    }
+
+    pub fn set_robot_idle_power(&mut self, agent: Uuid, idle_power: Watts) {
+        if let Some(Agent::Robot(robot_info)) = self.agents.get_mut(&agent) {
+            robot_info.idle_power = Some(idle_power);
+        }
+    }
+
+    // Price of a kWh at a time (seconds after midnight, or after the midnight before the shift)
+    pub fn kwh_cost_at(&self, time: Time) -> USD {
+        let time_of_day = time.rem_euclid(SEC_PER_DAY);
+        self.tariff
+            .as_ref()
+            .and_then(|tariff| tariff.periods.iter().find(|p| p.contains(time_of_day)))
+            .map_or(self.kwh_cost, |p| p.kwh_cost)
+    }
+
+    // Cost of drawing the power from `start` for `duration`, following the tariff as it changes
+    pub fn energy_cost(&self, power: Watts, start: Time, duration: Time) -> USD {
+        let tariff = match &self.tariff {
+            Some(tariff) if !tariff.periods.is_empty() => tariff,
+            _ => return kwh(power, duration) * self.kwh_cost,
+        };
+        let boundaries = tariff.boundaries();
+        let end = start + duration;
+        let mut cost = 0.0;
+        let mut time = start;
+        while time < end {
+            let day = (time / SEC_PER_DAY).floor() * SEC_PER_DAY;
+            let next = boundaries
+                .iter()
+                .map(|b| day + b)
+                .find(|b| *b > time)
+                .unwrap_or(day + SEC_PER_DAY)
+                .min(end);
+            cost += kwh(power, next - time) * self.kwh_cost_at(time);
+            time = next;
+        }
+        cost
+    }
+
+    // Price of a kWh averaged over the shift, for work that may happen at any time in it
+    pub fn shift_kwh_cost(&self) -> USD {
+        if self.shift.length <= 0.0 {
+            return self.kwh_cost_at(self.shift.start);
+        }
+        self.energy_cost(1000.0 * SEC_PER_HOUR / self.shift.length, self.shift.start, self.shift.length)
+    }
+}
+
+#[test]
+fn tariff_test() {
+    // Watts over seconds, not watt-hours
+    assert_eq!(kwh(1000.0, SEC_PER_HOUR), 1.0);
+    assert_eq!(kwh(250.0, 2.0 * SEC_PER_HOUR), 0.5);
+
+    let mut job = Job::new("Tariff".into(), 0.1);
+    // A 1kW draw for an hour costs one kWh at the flat price
+    assert!((job.energy_cost(1000.0, 0.0, SEC_PER_HOUR) - 0.1).abs() < 1e-12);
+
+    let mut tariff = Tariff::default();
+    // Peak prices from noon until 6pm, cheap nights from 10pm until 6am
+    tariff.add_period(12.0 * SEC_PER_HOUR, 18.0 * SEC_PER_HOUR, 0.3);
+    tariff.add_period(22.0 * SEC_PER_HOUR, 6.0 * SEC_PER_HOUR, 0.05);
+    job.set_energy_tariff(tariff);
+    assert_eq!(job.kwh_cost_at(13.0 * SEC_PER_HOUR), 0.3);
+    assert_eq!(job.kwh_cost_at(2.0 * SEC_PER_HOUR), 0.05);
+    assert_eq!(job.kwh_cost_at(26.0 * SEC_PER_HOUR), 0.05);
+    assert_eq!(job.kwh_cost_at(8.0 * SEC_PER_HOUR), 0.1);
+
+    // An hour either side of noon
+    let cost = job.energy_cost(1000.0, 11.0 * SEC_PER_HOUR, 2.0 * SEC_PER_HOUR);
+    assert!((cost - 0.4).abs() < 1e-9);
+    // The default 8am-4pm shift is half off-peak, half peak
+    assert!((job.shift_kwh_cost() - 0.2).abs() < 1e-9);
+}
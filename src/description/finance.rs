@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use super::job::Job;
+use super::units::USD;
+
+// How the capitalized cost of the robots is written down over their lifetime
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Depreciation {
+    StraightLine,
+    // `rate` times the straight-line share of the remaining book value each year (2.0 is double declining),
+    // switching to straight line once that is larger
+    DecliningBalance { rate: f64 },
+}
+
+impl Depreciation {
+    // Depreciation charged in each year of the lifetime, never taking the book value below the salvage value
+    pub fn schedule(&self, basis: USD, salvage: USD, years: usize) -> Vec<USD> {
+        let depreciable = (basis - salvage).max(0.0);
+        let mut remaining = depreciable;
+        let mut schedule = vec![];
+        for year in 0..years {
+            let straight = remaining / (years - year) as f64;
+            let charge = match self {
+                Depreciation::StraightLine => straight,
+                Depreciation::DecliningBalance { rate } => (rate * (salvage + remaining) / years as f64).max(straight),
+            }
+            .min(remaining);
+            remaining -= charge;
+            schedule.push(charge);
+        }
+        schedule
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinanceConfig {
+    pub lifetime: usize, // years
+    pub discount_rate: f64,
+    pub operating_hours: f64, // per year
+    pub depreciation: Depreciation,
+    // Share of the robots' capitalized cost recovered when they are sold at the end of the lifetime
+    pub salvage_fraction: f64,
+    // Depreciation only changes the cash flows through the tax it saves
+    pub tax_rate: f64,
+}
+
+impl Default for FinanceConfig {
+    fn default() -> Self {
+        Self {
+            lifetime: 7,
+            discount_rate: 0.08,
+            // Two shifts, five days a week
+            operating_hours: 4000.0,
+            depreciation: Depreciation::DecliningBalance { rate: 2.0 },
+            salvage_fraction: 0.1,
+            tax_rate: 0.25,
+        }
+    }
+}
+
+impl Job {
+    pub fn set_finance_config(&mut self, finance: FinanceConfig) {
+        self.finance = finance;
+    }
+}
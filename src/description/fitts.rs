@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+use crate::util::{fitz_law, index_of_difficulty};
+
+use super::poi::PointOfInterest;
+use super::rating::Rating;
+use super::target::Target;
+use super::units::Time;
+
+// Used when neither the location nor the target say how accurately the motion has to end
+pub const DEFAULT_WIDTH: f64 = 0.05; // meters
+// Narrowest effective width, so that tolerances at or below a robot's repeatability stay finite
+pub const MIN_WIDTH: f64 = 0.0005; // meters
+
+// Movement time = a + b * log2(D / W + 1)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FittsCoefficients {
+    pub a: Time, // seconds
+    pub b: Time, // seconds per bit
+}
+
+impl FittsCoefficients {
+    pub fn new(a: Time, b: Time) -> Self {
+        Self { a, b }
+    }
+
+    // Calibrated against the MTM-1 reach and move tables for a 5cm target
+    pub fn human() -> Self {
+        Self { a: 0.05, b: 0.12 }
+    }
+
+    // The approach and settling cost on top of the robot's travel at full speed
+    pub fn robot(agility: &Rating) -> Self {
+        match agility {
+            Rating::Low => Self { a: 0.0, b: 0.1 },
+            Rating::Medium => Self { a: 0.0, b: 0.05 },
+            Rating::High => Self { a: 0.0, b: 0.02 },
+        }
+    }
+
+    pub fn time(&self, distance: f64, width: f64) -> Time {
+        if distance <= 0.0 {
+            return 0.0;
+        }
+        fitz_law(self.a, self.b, distance, width.max(MIN_WIDTH))
+    }
+
+    // Least-squares fit to observed (distance, width, time) samples
+    pub fn fit(samples: &[(f64, f64, Time)]) -> Result<Self, String> {
+        let points: Vec<(f64, f64)> = samples
+            .iter()
+            .filter(|(d, _, _)| *d > 0.0)
+            .map(|(d, w, t)| (index_of_difficulty(*d, w.max(MIN_WIDTH)), *t))
+            .collect();
+        if points.len() < 2 {
+            return Err("At least two samples with a positive distance are needed".into());
+        }
+        let n = points.len() as f64;
+        let mean_id = points.iter().map(|(id, _)| id).sum::<f64>() / n;
+        let mean_time = points.iter().map(|(_, t)| t).sum::<f64>() / n;
+        let covariance: f64 = points
+            .iter()
+            .map(|(id, t)| (id - mean_id) * (t - mean_time))
+            .sum();
+        let variance: f64 = points.iter().map(|(id, _)| (id - mean_id).powi(2)).sum();
+        if variance <= f64::EPSILON {
+            return Err("Samples must span more than one index of difficulty".into());
+        }
+        let b = covariance / variance;
+        Ok(Self {
+            a: mean_time - b * mean_id,
+            b,
+        })
+    }
+}
+
+// How accurately a motion ending at this location must land: the location's tolerance if it has one,
+// otherwise the size of the target being handled
+pub fn target_width(poi: &PointOfInterest, target: Option<&Target>) -> f64 {
+    poi.tolerance()
+        .or(target.map(|t| t.size()).filter(|size| *size > 0.0))
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+// A robot must land within the tolerance despite its repeatability
+pub fn effective_width(width: f64, precision: f64) -> f64 {
+    (width - precision).max(MIN_WIDTH)
+}
+
+#[test]
+fn fitts_fit_test() {
+    let truth = FittsCoefficients::new(0.1, 0.15);
+    let samples: Vec<(f64, f64, Time)> = vec![(0.1, 0.05, 0.0), (0.3, 0.02, 0.0), (0.5, 0.1, 0.0)]
+        .into_iter()
+        .map(|(d, w, _)| (d, w, truth.time(d, w)))
+        .collect();
+    let fitted = FittsCoefficients::fit(&samples).unwrap();
+    assert!((fitted.a - 0.1).abs() < 1e-9);
+    assert!((fitted.b - 0.15).abs() < 1e-9);
+    assert!(FittsCoefficients::fit(&[(0.1, 0.05, 0.3)]).is_err());
+
+    // Fine placement costs more than a coarse drop over the same distance
+    assert!(truth.time(0.3, 0.005) > truth.time(0.3, 0.1));
+    assert_eq!(effective_width(0.001, 0.002), MIN_WIDTH);
+
+    // A move shorter than half the target width still takes time, rather than saving it
+    let robot = FittsCoefficients::robot(&Rating::Low);
+    assert!(robot.time(0.01, 0.05) > 0.0);
+    assert!(truth.time(0.01, 0.05) > truth.a);
+}
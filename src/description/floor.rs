@@ -0,0 +1,287 @@
+use nalgebra::{Vector2, Vector3};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ObstacleKind {
+    Wall,
+    Table,
+    // A zone closed to some kinds of agents, e.g. a human-only walkway
+    KeepOut { humans: bool, robots: bool },
+}
+
+// An axis-aligned rectangle on the floor
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Obstacle {
+    pub name: String,
+    pub kind: ObstacleKind,
+    pub min: Vector2<f64>,
+    pub max: Vector2<f64>,
+}
+
+impl Obstacle {
+    pub fn blocks(&self, robot: bool) -> bool {
+        match &self.kind {
+            ObstacleKind::Wall | ObstacleKind::Table => true,
+            ObstacleKind::KeepOut { humans, robots } => {
+                if robot {
+                    *robots
+                } else {
+                    *humans
+                }
+            }
+        }
+    }
+
+    // Whether the obstacle covers any part of the square of side `size` centered at `center`
+    fn overlaps(&self, center: &Vector2<f64>, size: f64) -> bool {
+        let half = size / 2.0;
+        self.min.x < center.x + half
+            && center.x - half < self.max.x
+            && self.min.y < center.y + half
+            && center.y - half < self.max.y
+    }
+}
+
+// 2D occupancy grid over the job's floor
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FloorPlan {
+    pub origin: Vector2<f64>, // meters, corner of cell (0, 0)
+    pub resolution: f64,      // meters per cell
+    pub columns: usize,
+    pub rows: usize,
+    pub obstacles: Vec<Obstacle>,
+}
+
+#[derive(PartialEq)]
+struct Frontier {
+    estimate: f64,
+    cell: (usize, usize),
+}
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    // Reversed so the binary heap pops the lowest estimate first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.cell.cmp(&other.cell))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FloorPlan {
+    pub fn new(min: Vector2<f64>, max: Vector2<f64>, resolution: f64) -> Self {
+        Self {
+            origin: min,
+            resolution,
+            columns: ((max.x - min.x) / resolution).ceil().max(1.0) as usize,
+            rows: ((max.y - min.y) / resolution).ceil().max(1.0) as usize,
+            obstacles: vec![],
+        }
+    }
+
+    pub fn add_obstacle(&mut self, name: String, kind: ObstacleKind, min: Vector2<f64>, max: Vector2<f64>) {
+        self.obstacles.push(Obstacle {
+            name,
+            kind,
+            min: Vector2::new(min.x.min(max.x), min.y.min(max.y)),
+            max: Vector2::new(min.x.max(max.x), min.y.max(max.y)),
+        });
+    }
+
+    pub fn cell_of(&self, point: &Vector3<f64>) -> Option<(usize, usize)> {
+        let column = ((point.x - self.origin.x) / self.resolution).floor();
+        let row = ((point.y - self.origin.y) / self.resolution).floor();
+        if column < 0.0 || row < 0.0 || column >= self.columns as f64 || row >= self.rows as f64 {
+            return None;
+        }
+        Some((column as usize, row as usize))
+    }
+
+    fn center(&self, cell: (usize, usize)) -> Vector2<f64> {
+        self.origin + Vector2::new(cell.0 as f64 + 0.5, cell.1 as f64 + 0.5) * self.resolution
+    }
+
+    pub fn is_free(&self, cell: (usize, usize), robot: bool) -> bool {
+        let center = self.center(cell);
+        !self
+            .obstacles
+            .iter()
+            .any(|o| o.blocks(robot) && o.overlaps(&center, self.resolution))
+    }
+
+    fn neighbors(&self, cell: (usize, usize), robot: bool) -> Vec<((usize, usize), f64)> {
+        let mut neighbors = vec![];
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)] {
+            let x = cell.0 as i64 + dx;
+            let y = cell.1 as i64 + dy;
+            if x < 0 || y < 0 || x >= self.columns as i64 || y >= self.rows as i64 {
+                continue;
+            }
+            let next = (x as usize, y as usize);
+            if !self.is_free(next, robot) {
+                continue;
+            }
+            // Diagonal steps may not cut the corner of an obstacle
+            if dx != 0 && dy != 0
+                && !(self.is_free((x as usize, cell.1), robot) && self.is_free((cell.0, y as usize), robot))
+            {
+                continue;
+            }
+            let step = if dx != 0 && dy != 0 { 2.0_f64.sqrt() } else { 1.0 };
+            neighbors.push((next, step * self.resolution));
+        }
+        neighbors
+    }
+
+    // A* over the grid. Returns the cells from start to goal, inclusive.
+    pub fn path(&self, from: &Vector3<f64>, to: &Vector3<f64>, robot: bool) -> Option<Vec<(usize, usize)>> {
+        let start = self.cell_of(from)?;
+        let goal = self.cell_of(to)?;
+        if !self.is_free(start, robot) || !self.is_free(goal, robot) {
+            return None;
+        }
+        let goal_center = self.center(goal);
+        let heuristic = |cell: (usize, usize)| (self.center(cell) - goal_center).norm();
+
+        let mut frontier = BinaryHeap::new();
+        let mut cost_so_far: HashMap<(usize, usize), f64> = HashMap::new();
+        let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        cost_so_far.insert(start, 0.0);
+        frontier.push(Frontier {
+            estimate: heuristic(start),
+            cell: start,
+        });
+
+        while let Some(Frontier { cell, .. }) = frontier.pop() {
+            if cell == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(previous) = came_from.get(&current) {
+                    path.push(*previous);
+                    current = *previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let cost = cost_so_far[&cell];
+            for (next, step) in self.neighbors(cell, robot) {
+                let next_cost = cost + step;
+                if next_cost < *cost_so_far.get(&next).unwrap_or(&f64::INFINITY) {
+                    cost_so_far.insert(next, next_cost);
+                    came_from.insert(next, cell);
+                    frontier.push(Frontier {
+                        estimate: next_cost + heuristic(next),
+                        cell: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    // Whether the straight segment between two floor positions stays in free cells
+    fn line_of_sight(&self, from: &Vector2<f64>, to: &Vector2<f64>, robot: bool) -> bool {
+        let steps = ((to - from).norm() / (self.resolution / 4.0)).ceil().max(1.0) as usize;
+        (0..=steps).all(|step| {
+            let point = from + (to - from) * (step as f64 / steps as f64);
+            match self.cell_of(&Vector3::new(point.x, point.y, 0.0)) {
+                Some(cell) => self.is_free(cell, robot),
+                None => false,
+            }
+        })
+    }
+
+    // Walking distance between two floor positions, or None if there is no way through.
+    // The grid path is shortened by cutting straight across any corner that is in the clear.
+    pub fn distance(&self, from: &Vector3<f64>, to: &Vector3<f64>, robot: bool) -> Option<f64> {
+        let path = self.path(from, to, robot)?;
+        let mut points: Vec<Vector2<f64>> = vec![Vector2::new(from.x, from.y)];
+        if path.len() > 2 {
+            points.extend(path[1..path.len() - 1].iter().map(|cell| self.center(*cell)));
+        }
+        points.push(Vector2::new(to.x, to.y));
+
+        let mut distance = 0.0;
+        let mut current = 0;
+        while current < points.len() - 1 {
+            let next = (current + 1..points.len())
+                .rev()
+                .find(|idx| self.line_of_sight(&points[current], &points[*idx], robot))
+                .unwrap_or(current + 1);
+            distance += (points[next] - points[current]).norm();
+            current = next;
+        }
+        Some(distance)
+    }
+}
+
+// Travel distances between every pair of standing POIs, found once per net build rather than
+// searching the floor plan again for each transition. None where there is no way through.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DistanceMatrix {
+    distances: HashMap<(Uuid, Uuid, bool), Option<f64>>,
+}
+
+impl DistanceMatrix {
+    pub fn new(floor_plan: &FloorPlan, standing: &[(Uuid, Vector3<f64>)]) -> Self {
+        let mut distances = HashMap::new();
+        for (from_id, from) in standing.iter() {
+            for (to_id, to) in standing.iter() {
+                for robot in [false, true] {
+                    distances.insert((*from_id, *to_id, robot), floor_plan.distance(from, to, robot));
+                }
+            }
+        }
+        Self { distances }
+    }
+
+    // The outer None is a pair that was not in the matrix
+    pub fn get(&self, from: &Uuid, to: &Uuid, robot: bool) -> Option<Option<f64>> {
+        self.distances.get(&(*from, *to, robot)).cloned()
+    }
+}
+
+#[test]
+fn floor_path_test() {
+    let mut plan = FloorPlan::new(Vector2::new(0.0, 0.0), Vector2::new(10.0, 10.0), 0.5);
+    let from = Vector3::new(1.0, 5.0, 0.0);
+    let to = Vector3::new(9.0, 5.0, 0.0);
+    assert!((plan.distance(&from, &to, false).unwrap() - 8.0).abs() < 1e-9);
+
+    // A wall with a gap near the top forces a detour
+    plan.add_obstacle("wall".into(), ObstacleKind::Wall, Vector2::new(4.9, 0.0), Vector2::new(5.1, 8.0));
+    let detour = plan.distance(&from, &to, false).unwrap();
+    assert!(detour > 8.0 + 2.0);
+
+    // Closing the gap to robots only
+    plan.add_obstacle(
+        "walkway".into(),
+        ObstacleKind::KeepOut { humans: false, robots: true },
+        Vector2::new(4.9, 8.0),
+        Vector2::new(5.1, 10.0),
+    );
+    assert_eq!(plan.distance(&from, &to, false), Some(detour));
+    assert!(plan.distance(&from, &to, true).is_none());
+    assert!(plan.distance(&from, &Vector3::new(11.0, 5.0, 0.0), false).is_none());
+
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let matrix = DistanceMatrix::new(&plan, &[(a, from), (b, to)]);
+    assert_eq!(matrix.get(&a, &b, false), Some(Some(detour)));
+    assert_eq!(matrix.get(&b, &a, true), Some(None));
+    assert_eq!(matrix.get(&a, &Uuid::new_v4(), false), None);
+}
@@ -176,6 +176,8 @@ impl Job {
             net.transitions.insert(transition.id, transition);
         }
 
+        self.add_defect_branches(&mut net);
+
         // Add all the primitives to map to the name of the primitive's type
         for (primitive_id, primitive) in self.primitives.iter() {
             net.name_lookup
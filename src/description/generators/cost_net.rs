@@ -1,8 +1,7 @@
 use crate::description::job::Job;
 use crate::description::agent::{Agent, CostProfiler};
-use crate::description::rating::Rating;
 use crate::description::units::Time;
-use crate::petri::cost::{add_cost_sets, Cost, CostSet, CostCategory, CostFrequency};
+use crate::petri::cost::{add_cost_sets, CostSet};
 use crate::petri::data::{Data, DataTag, Query};
 use crate::petri::net::PetriNet;
 use crate::petri::place::Place;
@@ -17,6 +16,8 @@ impl Job {
     pub fn compute_cost_from_poi(&self) -> PetriNet {
         let poi_net = self.poi_net.as_ref().unwrap();
         let mut net = poi_net.clone();
+        self.add_robot_tools(&mut net);
+        let zones = self.shared_zones();
         for (id, agent) in self.agents.iter() {
             match agent {
                 Agent::Human(human) => {
@@ -97,6 +98,12 @@ impl Job {
                         //     }
                         // }
 
+                        // Travel with no way through the floor plan is infeasible
+                        if !self.travel_possible(&transition, id, false) {
+                            remove_transitions.push(transition.id);
+                            continue;
+                        }
+
                         let (cost_set, new_ergo_meta_data): (CostSet, Vec<Data>) = human.cost_set(&transition, &self);
 
                         for ergo_meta_data in new_ergo_meta_data.iter() {
@@ -174,8 +181,13 @@ impl Job {
                             None => transition.clone(),
                         };
 
-                        let (cost_set, new_ergo_meta_data): (CostSet, Vec<Data>) = robot.cost_set(&transition, &self);
-                        let execution_time: Time = robot.execution_time(&transition, &self);
+                        if !self.travel_possible(&transition, id, true) {
+                            remove_transitions.push(transition.id);
+                            continue;
+                        }
+
+                        let (cost_set, new_ergo_meta_data): (CostSet, Vec<Data>) = robot.cost_set_in(&transition, &self, &zones);
+                        let execution_time: Time = robot.execution_time_in(&transition, &self, &zones);
 
                         
                         for ergo_meta_data in new_ergo_meta_data.iter() {
@@ -200,7 +212,6 @@ impl Job {
                         updated_transitions.insert(transition.id, transition_copy);
                     }
 
-
                     for transition in net.query_transitions(&vec![
                         Query::Data(Data::AgentAdd(*id)),
                     ]) {
@@ -210,28 +221,12 @@ impl Job {
                             None => transition.clone(),
                         };
 
-                        let mut cost_set: Vec<Cost> = CostSet::new();
-                        cost_set.push(Cost {
-                            frequency: CostFrequency::Once,
-                            value: robot.purchase_price,
-                            category: CostCategory::Monetary,
-                        });
-                        
-                        // Cost for integration (4-6x)
-                        // https://www.engineering.com/time-and-money-how-much-do-industrial-robots-cost/
-                        // TODO: update the value based on needed systems (i.e. integration breakdown - vision, pick+place, etc))
-                        let multiplicative_factor = if robot.sensing == Rating::High { 6.0 } else if robot.sensing == Rating::Medium { 5.0 } else { 4.0 }; 
-                        cost_set.push(Cost {
-                            frequency: CostFrequency::Once,
-                            value: robot.purchase_price * multiplicative_factor,
-                            category: CostCategory::Monetary,
-                        });
+                        let (cost_set, _new_ergo_meta_data): (CostSet, Vec<Data>) = robot.cost_set_in(&transition, &self, &zones);
 
                         transition_copy.cost = add_cost_sets(&transition.cost, &cost_set);
                         updated_transitions.insert(transition.id, transition_copy);
                     }
 
-
                     // Update the transitions with the new versions
                     for (id, transition) in updated_transitions {
                         net.transitions.insert(id, transition);
@@ -245,6 +240,9 @@ impl Job {
             // Add a place for each ergo bin
         }
 
+        // Keep guarded robots and people out of each other's way
+        self.add_shared_zone_locks(&mut net, &zones);
+
         // Check allocations and make sure they have outgoing arcs, if not we can prune the relevant decide action
         let mut remove_transitions: Vec<Uuid> = Vec::new();
         for transition in net.query_transitions(&vec![Query::Data(Data::Decide)]) {
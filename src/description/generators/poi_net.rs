@@ -1,3 +1,4 @@
+use crate::description::agent::Agent;
 use crate::description::job::Job;
 use crate::description::poi::PointOfInterest;
 use crate::description::primitive::Primitive;
@@ -12,6 +13,7 @@ use uuid::Uuid;
 
 impl Job {
     pub fn compute_poi_from_agent(&mut self) -> PetriNet {
+        self.compute_travel_distances();
         let agent_net = self.agent_net.as_ref().unwrap();
         let mut net = agent_net.clone();
         let mut standing_pois: Vec<&PointOfInterest> = vec![];
@@ -28,6 +30,7 @@ impl Job {
 
         // For each agent spawn location, create a standing place
         for (agent_id, agent) in self.agents.iter() {
+            let agent = &self.age_adjusted(agent);
             // Determine the pairs of valid standing/hand poi pairs
             let valid_pairs: Vec<(&PointOfInterest, &PointOfInterest)> = standing_pois
                 .iter()
@@ -173,7 +176,14 @@ impl Job {
                             );
                             new_transitions.push(transition2);
                         }
-                    } else if standing_poi1.travelability(standing_poi2, agent) {
+                    } else if standing_poi1.travelability(standing_poi2, agent)
+                        && self.travel_distances.as_ref().map_or(true, |distances| {
+                            distances
+                                .get(&standing_poi_id1, &standing_poi_id2, matches!(agent, Agent::Robot(_)))
+                                .flatten()
+                                .is_some()
+                        })
+                    {
                         // This is is a travel (plus reach if the hand poi is different)
 
                         // One Way 1->2
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+use super::job::Job;
+use super::primitive::Primitive;
+use super::rating::Rating;
+
+// HEART assessed effect of an error producing condition: (max_effect - 1) * proportion + 1
+fn assessed_effect(max_effect: f64, proportion: f64) -> f64 {
+    (max_effect - 1.0) * proportion.clamp(0.0, 1.0) + 1.0
+}
+
+// A HEART error producing condition, with the proportion assessed per rating (Low, Medium, High)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorProducingCondition {
+    pub max_effect: f64,
+    pub proportions: [f64; 3],
+}
+
+impl ErrorProducingCondition {
+    pub fn multiplier(&self, rating: &Rating) -> f64 {
+        assessed_effect(self.max_effect, self.proportions[rating.index()])
+    }
+}
+
+// HEART-style probability of a person getting a primitive wrong, counterpart to the robot error model
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HumanErrorModel {
+    // Nominal probability for carrying, moving and travelling to a location: a familiar, routine task
+    pub handling: f64,
+    // Nominal probability for inspection and selection by the skill they demand (Low, Medium, High)
+    pub judgement: [f64; 3],
+    // Inexperience, by the human's skill
+    pub inexperience: ErrorProducingCondition,
+    // Poor feedback from an unstructured location, by its structure
+    pub structure: ErrorProducingCondition,
+    // Ambiguity in what is expected at the location, by its variability
+    pub variability: ErrorProducingCondition,
+    // Maximum effect of physical exertion, assessed as the %MVC of the transition
+    pub fatigue: f64,
+}
+
+impl Default for HumanErrorModel {
+    fn default() -> Self {
+        Self {
+            handling: 0.0004,
+            judgement: [0.003, 0.02, 0.09],
+            inexperience: ErrorProducingCondition {
+                max_effect: 3.0,
+                proportions: [1.0, 0.4, 0.0],
+            },
+            structure: ErrorProducingCondition {
+                max_effect: 4.0,
+                proportions: [0.6, 0.2, 0.0],
+            },
+            variability: ErrorProducingCondition {
+                max_effect: 5.0,
+                proportions: [0.0, 0.2, 0.6],
+            },
+            fatigue: 2.0,
+        }
+    }
+}
+
+impl HumanErrorModel {
+    // Probability that the human gets the primitive wrong, with the given %MVC of the transition
+    pub fn probability(&self, primitive: &Primitive, skill: &Rating, job: &Job, mvc: f64) -> f64 {
+        let (nominal, location) = match primitive {
+            Primitive::Carry { to_hand, .. } | Primitive::Move { to_hand, .. } | Primitive::Travel { to_hand, .. } => {
+                (self.handling, job.points_of_interest.get(to_hand))
+            }
+            Primitive::Inspect { skill: demand, .. } | Primitive::Selection { skill: demand, .. } => {
+                (self.judgement[demand.index()], None)
+            }
+            _ => return 0.0,
+        };
+        let mut probability = nominal * self.inexperience.multiplier(skill) * assessed_effect(self.fatigue, mvc);
+        if let Some(poi) = location {
+            probability *= self.structure.multiplier(&poi.structure()) * self.variability.multiplier(&poi.variability());
+        }
+        probability.clamp(0.0, 1.0)
+    }
+}
+
+impl Job {
+    pub fn set_human_error_model(&mut self, model: HumanErrorModel) {
+        self.human_error_model = model;
+    }
+}
+
+#[test]
+fn human_error_model_test() {
+    use uuid::Uuid;
+
+    let job = Job::new("Human error".into(), 0.1);
+    let model = HumanErrorModel::default();
+    let inspect = Primitive::new_inspect(Uuid::new_v4(), Rating::High);
+    let expert = model.probability(&inspect, &Rating::High, &job, 0.0);
+    assert!((expert - 0.09).abs() < 1e-12);
+    // HEART: a novice at full inexperience triples it
+    assert!((model.probability(&inspect, &Rating::Low, &job, 0.0) - 0.27).abs() < 1e-12);
+    // Tired at half of the maximum exertion
+    assert!((model.probability(&inspect, &Rating::High, &job, 0.5) - 0.135).abs() < 1e-12);
+    assert!(model.probability(&inspect, &Rating::Low, &job, 10.0) <= 1.0);
+}
@@ -0,0 +1,407 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::job::Job;
+use super::units::{Dimension, Quantity, USD};
+
+// The physical fields of each part of a job, by the name they are serialized under
+const TARGET_FIELDS: [(&str, Dimension); 3] = [
+    ("size", Dimension::Length),
+    ("weight", Dimension::Mass),
+    ("value", Dimension::Currency),
+];
+const ROBOT_FIELDS: [(&str, Dimension); 10] = [
+    ("reach", Dimension::Length),
+    ("vertical_offset", Dimension::Length),
+    ("precision", Dimension::Length),
+    ("payload", Dimension::Mass),
+    ("speed", Dimension::Speed),
+    ("mobile_speed", Dimension::Speed),
+    ("purchase_price", Dimension::Currency),
+    ("annual_maintenance_cost", Dimension::Currency),
+    ("energy_consumption", Dimension::Power),
+    ("idle_power", Dimension::Power),
+];
+const HUMAN_FIELDS: [(&str, Dimension); 6] = [
+    ("acromial_height", Dimension::Length),
+    ("height", Dimension::Length),
+    ("reach", Dimension::Length),
+    ("weight", Dimension::Mass),
+    ("hourly_wage", Dimension::Currency),
+    ("labor_cost", Dimension::Currency),
+];
+const TOOL_FIELDS: [(&str, Dimension); 6] = [
+    ("mass", Dimension::Mass),
+    ("price", Dimension::Currency),
+    ("minSize", Dimension::Length),
+    ("maxSize", Dimension::Length),
+    ("actuationTime", Dimension::Time),
+    ("changeTime", Dimension::Time),
+];
+const FORCE_FIELDS: [(&str, Dimension); 1] = [("magnitude", Dimension::Force)];
+const POSITION_FIELDS: [(&str, Dimension); 1] = [("displacement", Dimension::Length)];
+// Parts of an agent, under the agent's snake_case names
+const PFL_FIELDS: [(&str, Dimension); 1] = [("moving_mass", Dimension::Mass)];
+const FITTS_FIELDS: [(&str, Dimension); 2] = [("a", Dimension::Time), ("b", Dimension::Time)];
+const AGENT_PARTS: [(&str, &[(&str, Dimension)]); 3] = [
+    ("safety", &PFL_FIELDS),
+    ("fitts", &FITTS_FIELDS),
+    ("labor_burden", &LABOR_BURDEN_FIELDS),
+];
+const SAFETY_FIELDS: [(&str, Dimension); 6] = [
+    ("separationDistance", Dimension::Length),
+    ("humanSpeed", Dimension::Speed),
+    ("reactionTime", Dimension::Time),
+    ("stoppingTime", Dimension::Time),
+    ("intrusionDistance", Dimension::Length),
+    ("positionUncertainty", Dimension::Length),
+];
+// Hours, and shares of an hour's pay, are left as they are
+const LABOR_BURDEN_FIELDS: [(&str, Dimension); 4] = [
+    ("dayStart", Dimension::Time),
+    ("dayEnd", Dimension::Time),
+    ("trainingCost", Dimension::Currency),
+    ("onboardingCost", Dimension::Currency),
+];
+const INTEGRATION_FIELDS: [(&str, Dimension); 7] = [
+    ("visionSystem", Dimension::Currency),
+    ("mobileBase", Dimension::Currency),
+    ("fencing", Dimension::Currency),
+    ("safetyScanners", Dimension::Currency),
+    ("collaborativeValidation", Dimension::Currency),
+    ("fixturePerLocation", Dimension::Currency),
+    ("programmingRate", Dimension::Currency),
+];
+const TARIFF_PERIOD_FIELDS: [(&str, Dimension); 3] = [
+    ("start", Dimension::Time),
+    ("end", Dimension::Time),
+    ("kwhCost", Dimension::Currency),
+];
+const REWORK_FIELDS: [(&str, Dimension); 1] = [("cost", Dimension::Currency)];
+const SHIFT_FIELDS: [(&str, Dimension); 2] = [("start", Dimension::Time), ("length", Dimension::Time)];
+const DEMAND_FIELDS: [(&str, Dimension); 1] = [("period", Dimension::Time)];
+// Positions and extents are vectors, each of whose components may carry a unit
+const POI_FIELDS: [(&str, Dimension); 2] = [("position", Dimension::Length), ("displacement", Dimension::Length)];
+const RANGE_FIELDS: [(&str, Dimension); 2] = [("min", Dimension::Length), ("max", Dimension::Length)];
+const FLOOR_FIELDS: [(&str, Dimension); 2] = [("origin", Dimension::Length), ("resolution", Dimension::Length)];
+const LENGTH_STATISTICS: [(&str, Dimension); 2] = [("mean", Dimension::Length), ("sd", Dimension::Length)];
+const MASS_STATISTICS: [(&str, Dimension); 2] = [("mean", Dimension::Mass), ("sd", Dimension::Mass)];
+
+// A field in its stored unit. Bare numbers are taken to be in it already; quantities are given as a
+// string ("250 mm") or as an object ({"value": 250, "unit": "mm"}).
+fn normalize_value(value: &Value, dimension: Dimension, exchange_rates: &HashMap<String, USD>) -> Result<Option<f64>, String> {
+    let quantity: Quantity = match value {
+        Value::Null | Value::Number(_) => return Ok(None),
+        Value::String(s) => s.parse()?,
+        Value::Object(_) => serde_json::from_value(value.clone()).map_err(|e| e.to_string())?,
+        _ => return Err(format!("Expected a {}, found {}", dimension, value)),
+    };
+    quantity.normalize(dimension, exchange_rates).map(Some)
+}
+
+fn normalize_fields(
+    object: &mut Value,
+    fields: &[(&str, Dimension)],
+    path: &str,
+    exchange_rates: &HashMap<String, USD>,
+) -> Result<(), String> {
+    let object = match object.as_object_mut() {
+        Some(object) => object,
+        None => return Ok(()),
+    };
+    for (field, dimension) in fields.iter() {
+        if let Some(value) = object.get_mut(*field) {
+            normalize_field(value, *dimension, exchange_rates).map_err(|error| format!("{}.{}: {}", path, field, error))?;
+        }
+    }
+    Ok(())
+}
+
+// A single value, or each component of a vector
+fn normalize_field(value: &mut Value, dimension: Dimension, exchange_rates: &HashMap<String, USD>) -> Result<(), String> {
+    if let Value::Array(components) = value {
+        for component in components.iter_mut() {
+            normalize_field(component, dimension, exchange_rates)?;
+        }
+        return Ok(());
+    }
+    if let Some(normalized) = normalize_value(value, dimension, exchange_rates)? {
+        *value = normalized.into();
+    }
+    Ok(())
+}
+
+// Normalize each element of a list in the job, choosing its fields by the element
+fn normalize_list<F>(list: Option<&mut Value>, path: &str, exchange_rates: &HashMap<String, USD>, fields: F) -> Result<(), String>
+where
+    F: Fn(&Value) -> &'static [(&'static str, Dimension)],
+{
+    if let Some(items) = list.and_then(|list| list.as_array_mut()) {
+        for (index, item) in items.iter_mut().enumerate() {
+            let item_fields = fields(item);
+            normalize_fields(item, item_fields, &format!("{}[{}]", path, index), exchange_rates)?;
+        }
+    }
+    Ok(())
+}
+
+// Normalize each entry of a map of the job (targets, agents, ...), choosing its fields by its type
+fn normalize_entries<F>(job: &mut Value, key: &str, exchange_rates: &HashMap<String, USD>, fields: F) -> Result<(), String>
+where
+    F: Fn(&str) -> &'static [(&'static str, Dimension)],
+{
+    if let Some(entries) = job.get_mut(key).and_then(|entries| entries.as_object_mut()) {
+        for (id, entry) in entries.iter_mut() {
+            let kind = entry.get("type").and_then(|kind| kind.as_str()).unwrap_or("").to_string();
+            normalize_fields(entry, fields(&kind), &format!("{}.{}", key, id), exchange_rates)?;
+        }
+    }
+    Ok(())
+}
+
+impl Job {
+    pub fn set_exchange_rate(&mut self, currency: String, usd: USD) {
+        self.exchange_rates.insert(currency, usd);
+    }
+
+    pub fn normalize(&self, quantity: &Quantity, dimension: Dimension) -> Result<f64, String> {
+        quantity.normalize(dimension, &self.exchange_rates)
+    }
+
+    // Load a job whose physical fields may carry units, converting them to the units the job is
+    // stored in. A quantity of the wrong kind for its field, such as a weight in newtons, is an error.
+    // Bare numbers are in meters, kilograms, seconds, newtons, watts and dollars, as the job stores them.
+    pub fn from_json(json: &str) -> Result<Job, String> {
+        let mut job: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let exchange_rates: HashMap<String, USD> = match job.get("exchangeRates") {
+            Some(rates) => serde_json::from_value(rates.clone()).map_err(|e| format!("exchangeRates: {}", e))?,
+            None => HashMap::new(),
+        };
+
+        normalize_fields(&mut job, &[("kwhCost", Dimension::Currency)], "job", &exchange_rates)?;
+        normalize_entries(&mut job, "targets", &exchange_rates, |_| &TARGET_FIELDS)?;
+        normalize_entries(&mut job, "agents", &exchange_rates, |kind| match kind {
+            "robot" => &ROBOT_FIELDS,
+            "human" => &HUMAN_FIELDS,
+            _ => &[],
+        })?;
+        normalize_entries(&mut job, "tools", &exchange_rates, |_| &TOOL_FIELDS)?;
+        normalize_entries(&mut job, "primitives", &exchange_rates, |kind| match kind {
+            "force" => &FORCE_FIELDS,
+            "position" => &POSITION_FIELDS,
+            _ => &[],
+        })?;
+        if let Some(agents) = job.get_mut("agents").and_then(|agents| agents.as_object_mut()) {
+            for (id, agent) in agents.iter_mut() {
+                for (key, fields) in AGENT_PARTS.iter() {
+                    if let Some(part) = agent.get_mut(*key) {
+                        normalize_fields(part, fields, &format!("agents.{}.{}", id, key), &exchange_rates)?;
+                    }
+                }
+            }
+        }
+        for (key, fields) in [
+            ("safety", &SAFETY_FIELDS[..]),
+            ("laborBurden", &LABOR_BURDEN_FIELDS[..]),
+            ("integrationRates", &INTEGRATION_FIELDS[..]),
+        ] {
+            if let Some(part) = job.get_mut(key) {
+                normalize_fields(part, fields, key, &exchange_rates)?;
+            }
+        }
+        if let Some(tariff) = job.get_mut("tariff") {
+            normalize_list(tariff.get_mut("periods"), "tariff.periods", &exchange_rates, |_| &TARIFF_PERIOD_FIELDS)?;
+        }
+        if let Some(quality) = job.get_mut("quality") {
+            normalize_entries(quality, "handling", &exchange_rates, |kind| match kind {
+                "rework" => &REWORK_FIELDS,
+                _ => &[],
+            })?;
+        }
+        if let Some(shift) = job.get_mut("shift") {
+            normalize_fields(shift, &SHIFT_FIELDS, "shift", &exchange_rates)?;
+        }
+        if let Some(demand) = job.get_mut("demand") {
+            normalize_fields(demand, &DEMAND_FIELDS, "demand", &exchange_rates)?;
+        }
+        normalize_entries(&mut job, "pointsOfInterest", &exchange_rates, |_| &POI_FIELDS)?;
+        if let Some(pois) = job.get_mut("pointsOfInterest").and_then(|pois| pois.as_object_mut()) {
+            for (id, poi) in pois.iter_mut() {
+                if let Some(movable) = poi.get_mut("movable") {
+                    normalize_fields(movable, &RANGE_FIELDS, &format!("pointsOfInterest.{}.movable", id), &exchange_rates)?;
+                }
+            }
+        }
+        if let Some(floor_plan) = job.get_mut("floorPlan") {
+            normalize_fields(floor_plan, &FLOOR_FIELDS, "floorPlan", &exchange_rates)?;
+            normalize_list(floor_plan.get_mut("obstacles"), "floorPlan.obstacles", &exchange_rates, |_| &RANGE_FIELDS)?;
+        }
+        // Population statistics are in the unit of their measure
+        if let Some(population) = job.get_mut("population") {
+            normalize_list(population.get_mut("entries"), "population.entries", &exchange_rates, |entry| {
+                match entry.get("measure").and_then(|measure| measure.as_str()) {
+                    Some("weight") => &MASS_STATISTICS,
+                    _ => &LENGTH_STATISTICS,
+                }
+            })?;
+        }
+
+        serde_json::from_value(job).map_err(|e| e.to_string())
+    }
+}
+
+#[test]
+fn from_json_test() {
+    use super::rating::Rating;
+
+    let mut job = Job::new("Units".into(), 0.1);
+    let part = job.create_precursor_target("Part".into(), 0.1, 1.0, Rating::High, vec![], 5.0);
+    let mut input = serde_json::to_value(&job).unwrap();
+    input["exchangeRates"] = serde_json::json!({ "EUR": 1.1 });
+    input["targets"][part.to_string()]["size"] = "250 mm".into();
+    input["targets"][part.to_string()]["weight"] = serde_json::json!({ "value": 2.0, "unit": "lb" });
+    input["targets"][part.to_string()]["value"] = "10 EUR".into();
+
+    let loaded = Job::from_json(&input.to_string()).unwrap();
+    let target = &loaded.targets[&part];
+    assert!((target.size() - 0.25).abs() < 1e-12);
+    assert!((target.weight() - 0.90718474 * crate::constants::GRAVITY).abs() < 1e-9);
+    assert!((target.value() - 11.0).abs() < 1e-9);
+
+    // Weight entered in newtons
+    input["targets"][part.to_string()]["weight"] = "20 N".into();
+    let error = Job::from_json(&input.to_string()).unwrap_err();
+    assert!(error.contains("weight"));
+}
+
+#[test]
+fn from_json_baseline_test() {
+    use super::agent::Agent;
+    use super::floor::FloorPlan;
+    use super::gender::Gender;
+    use super::rating::Rating;
+    use nalgebra::Vector2;
+
+    let mut job = Job::new("Baseline".into(), 0.1);
+    let human = job.create_human_agent("Worker".into(), 30.0, Gender::Female, Rating::High, 24.0, 500.0);
+    let robot = job.create_robot_agent(
+        "Arm".into(), 0.8, 0.0, 3.0, Rating::Medium, 1.0, 0.001, Rating::High, 0.0, 20000.0, 300.0, 1000.0,
+    );
+    let bench = job.create_hand_point_of_interest("Bench".into(), 0.5, 0.0, 0.9, None, None);
+    job.create_precursor_target("Part".into(), 0.1, 1.0, Rating::High, vec![bench], 5.0);
+
+    // Only the fields a job had before the newer models were added
+    let mut input = serde_json::to_value(&job).unwrap();
+    let keep = |value: &mut Value, keys: &[&str]| value.as_object_mut().unwrap().retain(|k, _| keys.contains(&k.as_str()));
+    keep(
+        &mut input,
+        &[
+            "id", "name", "tasks", "primitives", "pointsOfInterest", "agents", "targets", "basicNet", "agentNet",
+            "poiNet", "costNet", "weights", "kwhCost", "targetPop",
+        ],
+    );
+    keep(
+        &mut input["agents"][robot.to_string()],
+        &[
+            "type", "id", "name", "reach", "vertical_offset", "payload", "agility", "speed", "precision", "sensing",
+            "mobile_speed", "purchase_price", "energy_consumption", "annual_maintenance_cost",
+        ],
+    );
+    keep(
+        &mut input["agents"][human.to_string()],
+        &[
+            "type", "id", "name", "age", "gender", "acromial_height", "height", "reach", "weight", "skill",
+            "hourly_wage", "labor_cost",
+        ],
+    );
+    keep(
+        &mut input["pointsOfInterest"][bench.to_string()],
+        &["type", "id", "name", "position", "shape", "displacement", "variability", "structure"],
+    );
+    let loaded = Job::from_json(&input.to_string()).unwrap();
+    assert_eq!(loaded.agents.len(), 2);
+    assert_eq!(loaded.region, job.region);
+    match &loaded.agents[&robot] {
+        Agent::Robot(robot) => assert!(robot.tools.is_empty() && robot.idle_power.is_none()),
+        Agent::Human(_) => unreachable!(),
+    }
+
+    // Lengths on the floor and in the layout carry units too
+    let mut input = serde_json::to_value(&job).unwrap();
+    input["pointsOfInterest"][bench.to_string()]["position"] = serde_json::json!(["500 mm", 0.0, "3 ft"]);
+    input["floorPlan"] = serde_json::to_value(FloorPlan::new(Vector2::new(0.0, 0.0), Vector2::new(1.0, 1.0), 0.1)).unwrap();
+    input["floorPlan"]["resolution"] = "10 cm".into();
+    input["population"]["entries"][0]["mean"] = "1000 mm".into();
+    let loaded = Job::from_json(&input.to_string()).unwrap();
+    assert_eq!(loaded.population.entries[0].mean, 1.0);
+    let position = loaded.points_of_interest[&bench].position();
+    assert!((position.x - 0.5).abs() < 1e-12 && (position.z - 0.9144).abs() < 1e-12);
+    assert!((loaded.floor_plan.unwrap().resolution - 0.1).abs() < 1e-12);
+    input["floorPlan"]["resolution"] = "10 kg".into();
+    assert!(Job::from_json(&input.to_string()).unwrap_err().contains("resolution"));
+}
+
+#[test]
+fn from_json_models_test() {
+    use super::agent::Agent;
+    use super::energy::Tariff;
+    use super::gender::Gender;
+    use super::primitive::Primitive;
+    use super::rating::Rating;
+    use super::safety::{BodyRegion, SafetyMode};
+
+    let mut job = Job::new("Models".into(), 0.1);
+    let human = job.create_human_agent("Worker".into(), 30.0, Gender::Female, Rating::High, 24.0, 500.0);
+    let robot = job.create_robot_agent(
+        "Arm".into(), 0.8, 0.0, 3.0, Rating::Medium, 1.0, 0.001, Rating::High, 0.0, 20000.0, 300.0, 1000.0,
+    );
+    job.set_robot_safety(robot, SafetyMode::PowerForceLimited { body_region: BodyRegion::Hand, moving_mass: 20.0 });
+    let part = job.create_precursor_target("Part".into(), 0.1, 1.0, Rating::High, vec![], 5.0);
+    let position = Primitive::new_position(part, 90.0, 0.01);
+    let position_id = position.id();
+    job.add_primitive(position);
+    let mut tariff = Tariff::default();
+    tariff.add_period(0.0, 3600.0, 0.3);
+    job.set_energy_tariff(tariff);
+
+    let mut input = serde_json::to_value(&job).unwrap();
+    input["exchangeRates"] = serde_json::json!({ "EUR": 1.1 });
+    input["primitives"][position_id.to_string()]["displacement"] = "5 mm".into();
+    input["agents"][robot.to_string()]["safety"]["moving_mass"] = "44 lb".into();
+    input["agents"][human.to_string()]["reach"] = "30 in".into();
+    input["safety"]["separationDistance"] = "150 cm".into();
+    input["safety"]["reactionTime"] = "200 ms".into();
+    input["tariff"]["periods"][0]["kwhCost"] = "0.2 EUR".into();
+    input["tariff"]["periods"][0]["end"] = "2 h".into();
+    input["integrationRates"]["visionSystem"] = serde_json::json!(["1000 EUR", 8000.0, 15000.0]);
+    input["laborBurden"]["trainingCost"] = "1000 EUR".into();
+    input["laborBurden"]["dayStart"] = "6 h".into();
+
+    let loaded = Job::from_json(&input.to_string()).unwrap();
+    match &loaded.primitives[&position_id] {
+        Primitive::Position { displacement, .. } => assert!((displacement - 0.005).abs() < 1e-12),
+        _ => unreachable!(),
+    }
+    match &loaded.agents[&robot] {
+        Agent::Robot(robot) => match robot.safety {
+            SafetyMode::PowerForceLimited { moving_mass, .. } => assert!((moving_mass - 19.9580643).abs() < 1e-6),
+            _ => unreachable!(),
+        },
+        Agent::Human(_) => unreachable!(),
+    }
+    match &loaded.agents[&human] {
+        Agent::Human(human) => assert!((human.reach - 0.762).abs() < 1e-12),
+        Agent::Robot(_) => unreachable!(),
+    }
+    assert!((loaded.safety.separation_distance - 1.5).abs() < 1e-12);
+    assert!((loaded.safety.reaction_time - 0.2).abs() < 1e-12);
+    assert!((loaded.tariff.as_ref().unwrap().periods[0].kwh_cost - 0.22).abs() < 1e-12);
+    assert_eq!(loaded.tariff.as_ref().unwrap().periods[0].end, 7200.0);
+    assert!((loaded.integration_rates.vision_system[0] - 1100.0).abs() < 1e-9);
+    assert!((loaded.labor_burden.training_cost - 1100.0).abs() < 1e-9);
+    assert_eq!(loaded.labor_burden.day_start, 21600.0);
+
+    // A safety distance entered as a time
+    input["safety"]["separationDistance"] = "2 s".into();
+    assert!(Job::from_json(&input.to_string()).unwrap_err().contains("safety.separationDistance"));
+}
@@ -0,0 +1,265 @@
+use enum_tag::EnumTag;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::petri::cost::{Cost, CostCategory, CostFrequency, CostSet, Uncertainty};
+use crate::petri::data::{Data, DataTag};
+use crate::petri::transition::Transition;
+
+use super::agent::RobotInfo;
+use super::job::Job;
+use super::primitive::Primitive;
+use super::safety::SafetyMode;
+use super::units::USD;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrationItem {
+    Vision,
+    MobileBase,
+    Tooling,
+    SafetyHardware,
+    Fixtures,
+    Programming,
+    Commissioning,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationLine {
+    pub item: IntegrationItem,
+    pub description: String,
+    pub cost: USD,
+}
+
+// What it takes to put a robot to work, beyond buying it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationBom {
+    pub lines: Vec<IntegrationLine>,
+}
+
+impl IntegrationBom {
+    pub fn total(&self) -> USD {
+        self.lines.iter().map(|line| line.cost).sum()
+    }
+
+    fn push(&mut self, item: IntegrationItem, description: String, cost: USD) {
+        if cost > 0.0 {
+            self.lines.push(IntegrationLine {
+                item,
+                description,
+                cost,
+            });
+        }
+    }
+}
+
+// Prices the integration bill of materials is built from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationRates {
+    // Camera, lighting and software, by the robot's sensing rating (Low, Medium, High)
+    pub vision_system: [USD; 3],
+    pub mobile_base: USD,
+    pub fencing: USD,
+    pub safety_scanners: USD,
+    // Risk assessment and validation of a power and force limited cell
+    pub collaborative_validation: USD,
+    pub fixture_per_location: USD,
+    pub programming_base_hours: f64,
+    pub programming_hours_per_primitive: f64,
+    pub programming_rate: USD, // per hour
+    // Share of the hardware and programming spent installing and commissioning the cell
+    pub commissioning_fraction: f64,
+}
+
+impl Default for IntegrationRates {
+    fn default() -> Self {
+        Self {
+            vision_system: [3000.0, 8000.0, 15000.0],
+            mobile_base: 25000.0,
+            fencing: 15000.0,
+            safety_scanners: 10000.0,
+            collaborative_validation: 3000.0,
+            fixture_per_location: 1500.0,
+            programming_base_hours: 40.0,
+            programming_hours_per_primitive: 8.0,
+            programming_rate: 120.0,
+            commissioning_fraction: 0.15,
+        }
+    }
+}
+
+impl Job {
+    // Integration bill of materials for the robot performing the given transitions
+    pub fn integration_bom(&self, robot: &RobotInfo, transitions: &Vec<&Transition>) -> IntegrationBom {
+        let rates = &self.integration_rates;
+        let primitives: Vec<&Primitive> = transitions
+            .iter()
+            .flat_map(|t| t.meta_data.iter())
+            .filter(|d| d.tag() == DataTag::PrimitiveAssignment && d.id() == Some(robot.id))
+            .filter_map(|d| self.primitives.get(&d.secondary()?))
+            .unique_by(|p| p.id())
+            .collect();
+        let standing: Vec<Uuid> = transitions
+            .iter()
+            .flat_map(|t| t.meta_data.iter())
+            .filter_map(|d| match d {
+                Data::Standing(poi, agent) if *agent == robot.id => Some(*poi),
+                _ => None,
+            })
+            .unique()
+            .collect();
+        let hands: Vec<Uuid> = transitions
+            .iter()
+            .flat_map(|t| t.meta_data.iter())
+            .filter_map(|d| match d {
+                Data::Hand(poi, agent) | Data::ToHandPOI(poi, agent) if *agent == robot.id => Some(*poi),
+                _ => None,
+            })
+            .unique()
+            .collect();
+
+        let mut bom = IntegrationBom::default();
+        if primitives
+            .iter()
+            .any(|p| matches!(p, Primitive::Inspect { .. } | Primitive::Selection { .. }))
+        {
+            bom.push(
+                IntegrationItem::Vision,
+                format!("{:?} sensing vision system", robot.sensing),
+                rates.vision_system[robot.sensing.index()],
+            );
+        }
+        let travels = primitives.iter().any(|p| match p {
+            Primitive::Travel { .. } => true,
+            Primitive::Carry {
+                from_standing,
+                to_standing,
+                ..
+            } => from_standing != to_standing,
+            _ => false,
+        });
+        if travels || standing.len() > 1 {
+            bom.push(IntegrationItem::MobileBase, "Mobile base".into(), rates.mobile_base);
+        }
+        // Only the tools it needs for these transitions are bought
+        for tool in self.transitions_tools(robot, transitions) {
+            bom.push(IntegrationItem::Tooling, tool.name.clone(), tool.price);
+        }
+        match robot.safety {
+            SafetyMode::Fenced => bom.push(IntegrationItem::SafetyHardware, "Fencing and interlocks".into(), rates.fencing),
+            SafetyMode::SpeedSeparation => {
+                bom.push(IntegrationItem::SafetyHardware, "Safety laser scanners".into(), rates.safety_scanners)
+            }
+            SafetyMode::PowerForceLimited { .. } => bom.push(
+                IntegrationItem::SafetyHardware,
+                "Collaborative risk assessment".into(),
+                rates.collaborative_validation,
+            ),
+        }
+        bom.push(
+            IntegrationItem::Fixtures,
+            format!("Fixtures for {} locations", hands.len()),
+            rates.fixture_per_location * hands.len() as f64,
+        );
+        let hours = rates.programming_base_hours + rates.programming_hours_per_primitive * primitives.len() as f64;
+        bom.push(
+            IntegrationItem::Programming,
+            format!("{} hours of programming", hours),
+            hours * rates.programming_rate,
+        );
+        let commissioned = robot.purchase_price + bom.total();
+        bom.push(
+            IntegrationItem::Commissioning,
+            "Installation and commissioning".into(),
+            commissioned * rates.commissioning_fraction,
+        );
+        bom
+    }
+
+    // One-time costs of integrating the robot for the given transitions. Its purchase is on the
+    // transition adding it to the cost net.
+    pub fn robot_integration_costs(&self, robot: &RobotInfo, transitions: &Vec<&Transition>) -> CostSet {
+        self.integration_bom(robot, transitions)
+            .lines
+            .into_iter()
+            .map(|line| Cost {
+                frequency: CostFrequency::Once,
+                value: line.cost,
+                category: CostCategory::Monetary,
+                uncertainty: self.cost_uncertainty.of(Uncertainty::Integration),
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn integration_bom_test() {
+    use super::rating::Rating;
+    use super::agent::Agent;
+    use super::tool::Tool;
+
+    let mut job = Job::new("Integration".into(), 0.1);
+    let robot_id = job.create_robot_agent(
+        "Arm".into(), 0.8, 0.0, 3.0, Rating::Medium, 1.0, 0.001, Rating::High, 0.0, 20000.0, 300.0, 1000.0,
+    );
+    let part = job.create_precursor_target("Part".into(), 0.05, 1.0, Rating::High, vec![], 1.0);
+    let inspect = Primitive::new_inspect(part, Rating::Medium);
+    let inspect_id = inspect.id();
+    job.add_primitive(inspect);
+    let robot = match job.agents.get(&robot_id).unwrap() {
+        Agent::Robot(robot) => robot.clone(),
+        Agent::Human(_) => unreachable!(),
+    };
+
+    let idle = job.integration_bom(&robot, &vec![]);
+    assert!(idle.lines.iter().all(|l| l.item != IntegrationItem::Vision));
+
+    let transition = Transition::new(
+        "Inspect".into(),
+        Default::default(),
+        Default::default(),
+        vec![Data::PrimitiveAssignment(robot_id, inspect_id)],
+        0.0,
+        vec![],
+    );
+    let bom = job.integration_bom(&robot, &vec![&transition]);
+    let vision = bom.lines.iter().find(|l| l.item == IntegrationItem::Vision).unwrap();
+    assert_eq!(vision.cost, 15000.0);
+    assert!(bom.total() > idle.total());
+    let integration: f64 = job.robot_integration_costs(&robot, &vec![&transition]).iter().map(|c| c.value).sum();
+    assert!((integration - bom.total()).abs() < 1e-6);
+
+    // Only the purchase is on the transition adding the robot
+    let add = Transition::new(
+        "Add Arm".into(),
+        Default::default(),
+        Default::default(),
+        vec![Data::AgentAdd(robot_id)],
+        0.0,
+        vec![],
+    );
+    let (costs, _) = robot.cost_set_in(&add, &job, &[]);
+    let once: Vec<f64> = costs.iter().filter(|c| c.frequency == CostFrequency::Once).map(|c| c.value).collect();
+    assert_eq!(once, vec![robot.purchase_price]);
+
+    // A listed tool is only bought once a transition mounts it
+    let tool = job.add_tool(Tool {
+        price: 800.0,
+        symmetries: vec![Rating::High],
+        ..Tool::new("Gripper".into())
+    });
+    job.add_robot_tool(robot_id, tool);
+    let robot = match job.agents.get(&robot_id).unwrap() {
+        Agent::Robot(robot) => robot.clone(),
+        Agent::Human(_) => unreachable!(),
+    };
+    let tooling = |bom: &IntegrationBom| bom.lines.iter().filter(|l| l.item == IntegrationItem::Tooling).count();
+    assert_eq!(tooling(&job.integration_bom(&robot, &vec![&transition])), 0);
+    let mut tooled = transition.clone();
+    tooled.add_data(Data::Tool(tool, robot_id));
+    assert_eq!(tooling(&job.integration_bom(&robot, &vec![&tooled])), 1);
+}
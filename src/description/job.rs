@@ -1,20 +1,40 @@
-use crate::description::agent::Agent;
-use crate::description::poi::PointOfInterest;
+use crate::description::age::AgeFactors;
+use crate::description::agent::{Agent, RobotInfo};
+use crate::description::anthropometry::{default_region, Anthropometry, PopulationTable, DEFAULT_REGION};
+use crate::description::demand::Demand;
+use crate::description::energy::{Shift, Tariff};
+use crate::description::finance::FinanceConfig;
+use crate::description::fitts::FittsCoefficients;
+use crate::description::floor::{DistanceMatrix, FloorPlan};
+use crate::description::human_error::HumanErrorModel;
+use crate::description::integration::IntegrationRates;
+use crate::description::labor::LaborBurden;
+use crate::description::motion::{Blending, Coordination, MotionProfile};
+use crate::description::poi::{LayoutConflict, MovableRange, PointOfInterest, ReachCriterion, Shape};
 use crate::description::primitive::Primitive;
+use crate::description::quality::QualityConfig;
 use crate::description::rating::Rating;
+use crate::description::robot_error::{
+    judgement_observations_from_csv_str, observations_from_csv_str, JudgementRates, RobotErrorModel,
+};
+use crate::description::safety::{SafetyConfig, SafetyMode};
 use crate::description::target::Target;
 use crate::description::task::Task;
+use crate::description::uncertainty::CostUncertainty;
+use crate::description::tool::Tool;
+use crate::description::urdf::{ReachabilityMap, UrdfChain};
 use crate::description::weights::Weights;
+use crate::petri::data::DataTag;
 use crate::petri::net::PetriNet;
+use crate::petri::transition::Transition;
 use enum_tag::EnumTag;
-use nalgebra::Norm;
+use itertools::Itertools;
+use nalgebra::{Norm, Vector3};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
-use statrs::distribution::{Normal, ContinuousCDF};
-use statrs::statistics::Distribution;
 
-use super::{gender::Gender, units::{Watts, USD}};
+use super::{gender::Gender, units::{Time, Watts, USD}};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -26,13 +46,50 @@ pub struct Job {
     pub points_of_interest: HashMap<Uuid, PointOfInterest>,
     pub agents: HashMap<Uuid, Agent>,
     pub targets: HashMap<Uuid, Target>,
+    #[serde(default)]
+    pub tools: HashMap<Uuid, Tool>,
     pub basic_net: Option<PetriNet>,
     pub agent_net: Option<PetriNet>,
     pub poi_net: Option<PetriNet>,
     pub cost_net: Option<PetriNet>,
     pub weights: Weights,
-    pub kwh_cost: USD, // USD per hour
+    pub kwh_cost: USD, // USD per kWh, outside any tariff period
+    #[serde(default)]
+    pub exchange_rates: HashMap<String, USD>, // USD per unit of each other currency, by symbol
+    pub tariff: Option<Tariff>,
+    #[serde(default)]
+    pub shift: Shift,
     pub target_pop: f64,
+    pub demand: Option<Demand>,
+    #[serde(default)]
+    pub population: PopulationTable,
+    #[serde(default = "default_region")]
+    pub region: String,
+    #[serde(default)]
+    pub age_factors: AgeFactors,
+    pub floor_plan: Option<FloorPlan>,
+    // Standing POI travel distances on the floor plan, cleared when either changes
+    #[serde(skip)]
+    pub travel_distances: Option<DistanceMatrix>,
+    #[serde(default)]
+    pub safety: SafetyConfig,
+    #[serde(default)]
+    pub robot_error_model: RobotErrorModel,
+    // Error rates of robot inspections and selections
+    #[serde(default)]
+    pub robot_judgement: JudgementRates,
+    #[serde(default)]
+    pub human_error_model: HumanErrorModel,
+    #[serde(default)]
+    pub quality: QualityConfig,
+    #[serde(default)]
+    pub integration_rates: IntegrationRates,
+    #[serde(default)]
+    pub finance: FinanceConfig,
+    #[serde(default)]
+    pub labor_burden: LaborBurden,
+    #[serde(default)]
+    pub cost_uncertainty: CostUncertainty,
 }
 
 impl Job {
@@ -45,13 +102,32 @@ impl Job {
             points_of_interest: HashMap::new(),
             agents: HashMap::new(),
             targets: HashMap::new(),
+            tools: HashMap::new(),
             basic_net: None,
             agent_net: None,
             poi_net: None,
             cost_net: None,
             weights: Weights::default(),
             kwh_cost,
-            target_pop: 0.01
+            exchange_rates: HashMap::new(),
+            tariff: None,
+            shift: Shift::default(),
+            target_pop: 0.01,
+            demand: None,
+            population: PopulationTable::default(),
+            region: DEFAULT_REGION.to_string(),
+            age_factors: AgeFactors::default(),
+            floor_plan: None,
+            travel_distances: None,
+            safety: SafetyConfig::default(),
+            robot_error_model: RobotErrorModel::default(),
+            robot_judgement: JudgementRates::default(),
+            human_error_model: HumanErrorModel::default(),
+            quality: QualityConfig::default(),
+            integration_rates: IntegrationRates::default(),
+            finance: FinanceConfig::default(),
+            labor_burden: LaborBurden::default(),
+            cost_uncertainty: CostUncertainty::default(),
         }
     }
 
@@ -65,6 +141,7 @@ impl Job {
 
     pub fn add_point_of_interest(&mut self, poi: PointOfInterest) {
         self.points_of_interest.insert(poi.id(), poi);
+        self.travel_distances = None;
     }
 
     pub fn add_agent(&mut self, agent: Agent) {
@@ -112,6 +189,51 @@ impl Job {
         uuid
     }
 
+    // Give a POI a volume: `displacement` is its half extent along each axis
+    pub fn set_point_of_interest_extent(
+        &mut self,
+        poi: Uuid,
+        shape: Shape,
+        displacement: Vector3<f64>,
+        reach: ReachCriterion,
+    ) {
+        if let Some(poi) = self.points_of_interest.get_mut(&poi) {
+            let location = poi.location_mut();
+            location.shape = shape;
+            location.displacement = displacement;
+            location.reach = reach;
+        }
+    }
+
+    // Let the layout optimizer place the POI anywhere in the box between `a` and `b`
+    pub fn set_point_of_interest_movable(&mut self, poi: Uuid, a: Vector3<f64>, b: Vector3<f64>) {
+        if let Some(poi) = self.points_of_interest.get_mut(&poi) {
+            poi.location_mut().movable = Some(MovableRange::new(a, b));
+        }
+    }
+
+    pub fn set_point_of_interest_position(&mut self, poi: Uuid, position: Vector3<f64>) {
+        if let Some(poi) = self.points_of_interest.get_mut(&poi) {
+            poi.location_mut().position = position;
+        }
+        self.travel_distances = None;
+    }
+
+    // Pairs of POIs whose volumes overlap
+    pub fn layout_conflicts(&self) -> Vec<LayoutConflict> {
+        self.points_of_interest
+            .values()
+            .filter(|poi| poi.location().has_volume())
+            .sorted_by_key(|poi| (poi.name(), poi.id()))
+            .tuple_combinations()
+            .filter(|(poi1, poi2)| poi1.location().overlaps(poi2.location()))
+            .map(|(poi1, poi2)| LayoutConflict {
+                first: poi1.id(),
+                second: poi2.id(),
+            })
+            .collect()
+    }
+
     pub fn create_robot_agent(
         &mut self,
         name: String,
@@ -155,28 +277,29 @@ impl Job {
         hourly_wage: USD,
         labor_cost: USD,
     ) -> Uuid {
-        let mut height = 0.0;
-        let mut reach = 0.0;
-        let mut acromial_height = 0.0;
-        let mut weight = 0.0;
-
-        if gender == Gender::Female {
-            let height_n = Normal::new(1.6285, 0.0642).unwrap();
-            let reach_n = Normal::new(0.6930, 0.0428).unwrap();
-            let weight_n = Normal::new(62.08, 8.33).unwrap();
-            height += height_n.inverse_cdf(self.target_pop);
-            acromial_height += height - 0.164*height;
-            reach += reach_n.inverse_cdf(self.target_pop);
-            weight += weight_n.inverse_cdf(self.target_pop);
-        } else {
-            let height_n = Normal::new(1.7562, 0.0686).unwrap();
-            let reach_n = Normal::new(0.7569, 0.0437).unwrap();
-            let weight_n = Normal::new(78.75, 11.0).unwrap();
-            height += height_n.inverse_cdf(self.target_pop);
-            acromial_height += height - 0.182*height;
-            reach += reach_n.inverse_cdf(self.target_pop);
-            weight += weight_n.inverse_cdf(self.target_pop);
-        }
+        self.create_human_agent_with_anthropometry(
+            name,
+            age,
+            gender,
+            skill,
+            hourly_wage,
+            labor_cost,
+            Anthropometry::default(),
+        )
+    }
+
+    pub fn create_human_agent_with_anthropometry(
+        &mut self,
+        name: String,
+        age: f64,
+        gender: Gender,
+        skill: Rating,
+        hourly_wage: USD,
+        labor_cost: USD,
+        measured: Anthropometry,
+    ) -> Uuid {
+        let (height, reach, acromial_height, weight) =
+            measured.resolve(&self.population, &self.region, &gender, self.target_pop);
 
         let agent = Agent::new_human(
             name,
@@ -195,6 +318,231 @@ impl Job {
         uuid
     }
 
+    // Replace the stored body dimensions of a human with measured values, keeping the rest
+    pub fn set_human_anthropometry(&mut self, agent: Uuid, measured: Anthropometry) {
+        match self.agents.get_mut(&agent) {
+            Some(Agent::Human(human_info)) => {
+                human_info.height = measured.height.unwrap_or(human_info.height);
+                human_info.reach = measured.reach.unwrap_or(human_info.reach);
+                human_info.acromial_height =
+                    measured.acromial_height.unwrap_or(human_info.acromial_height);
+                human_info.weight = measured.weight.unwrap_or(human_info.weight);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_population_table(&mut self, population: PopulationTable) {
+        self.population = population;
+    }
+
+    pub fn load_population_table(&mut self, path: &str) -> Result<(), String> {
+        self.population = PopulationTable::from_file(path)?;
+        Ok(())
+    }
+
+    pub fn set_region(&mut self, region: String) {
+        self.region = region;
+    }
+
+    pub fn set_age_factors(&mut self, age_factors: AgeFactors) {
+        self.age_factors = age_factors;
+    }
+
+    pub fn set_floor_plan(&mut self, floor_plan: FloorPlan) {
+        self.floor_plan = Some(floor_plan);
+        self.travel_distances = None;
+    }
+
+    pub fn set_robot_error_model(&mut self, model: RobotErrorModel) {
+        self.robot_error_model = model;
+    }
+
+    // None falls back to the job's model
+    pub fn set_agent_error_model(&mut self, agent: Uuid, model: Option<RobotErrorModel>) {
+        if let Some(Agent::Robot(robot_info)) = self.agents.get_mut(&agent) {
+            robot_info.error_model = model;
+        }
+    }
+
+    pub fn error_model_for<'a>(&'a self, robot: &'a RobotInfo) -> &'a RobotErrorModel {
+        robot.error_model.as_ref().unwrap_or(&self.robot_error_model)
+    }
+
+    // Fit the error model (the robot's if given, otherwise the job's) to a CSV of observed picks
+    pub fn calibrate_robot_error_model(&mut self, agent: Option<Uuid>, path: &str) -> Result<(), String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        let observations = observations_from_csv_str(&text)?;
+        match agent {
+            Some(agent) => {
+                let robot = match self.agents.get(&agent) {
+                    Some(Agent::Robot(robot_info)) => robot_info,
+                    _ => return Err("Only robot agents have an error model".into()),
+                };
+                let model = self.error_model_for(robot).calibrate(&observations)?;
+                self.set_agent_error_model(agent, Some(model));
+            }
+            None => self.robot_error_model = self.robot_error_model.calibrate(&observations)?,
+        }
+        Ok(())
+    }
+
+    pub fn set_robot_judgement(&mut self, judgement: JudgementRates) {
+        self.robot_judgement = judgement;
+    }
+
+    // Fit the inspection and selection error rates to a CSV of observed judgements
+    pub fn calibrate_robot_judgement(&mut self, path: &str) -> Result<(), String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        self.robot_judgement = self.robot_judgement.calibrate(&judgement_observations_from_csv_str(&text)?)?;
+        Ok(())
+    }
+
+    pub fn set_safety_config(&mut self, safety: SafetyConfig) {
+        self.safety = safety;
+    }
+
+    pub fn set_integration_rates(&mut self, rates: IntegrationRates) {
+        self.integration_rates = rates;
+    }
+
+    pub fn set_robot_safety(&mut self, agent: Uuid, mode: SafetyMode) {
+        if let Some(Agent::Robot(robot_info)) = self.agents.get_mut(&agent) {
+            robot_info.safety = mode;
+        }
+    }
+
+    // Distance walked or driven between two standing POIs: the shortest path around obstacles when
+    // there is a floor plan, otherwise a straight line. None if there is no way through.
+    pub fn travel_distance(&self, from_standing: &Uuid, to_standing: &Uuid, robot: bool) -> Option<f64> {
+        let from = self.points_of_interest.get(from_standing)?.position();
+        let to = self.points_of_interest.get(to_standing)?.position();
+        match &self.floor_plan {
+            Some(floor_plan) => match self
+                .travel_distances
+                .as_ref()
+                .and_then(|distances| distances.get(from_standing, to_standing, robot))
+            {
+                Some(distance) => distance,
+                None => floor_plan.distance(&from, &to, robot),
+            },
+            None => Some((to - from).norm()),
+        }
+    }
+
+    // Whether the agent can get between the standing POIs the transition moves it between
+    pub fn travel_possible(&self, transition: &Transition, agent: &Uuid, robot: bool) -> bool {
+        let standing = |tag: DataTag| {
+            transition.meta_data.iter().find_map(|d| match d.secondary() {
+                Some(id) if d.tag() == tag && id == *agent => d.id(),
+                _ => None,
+            })
+        };
+        match (standing(DataTag::FromStandingPOI), standing(DataTag::ToStandingPOI)) {
+            (Some(from), Some(to)) => self.travel_distance(&from, &to, robot).is_some(),
+            _ => true,
+        }
+    }
+
+    // Fill in the travel distances between standing POIs, if there is a floor plan and they are not known
+    pub fn compute_travel_distances(&mut self) {
+        if self.travel_distances.is_some() {
+            return;
+        }
+        if let Some(floor_plan) = &self.floor_plan {
+            let standing: Vec<(Uuid, Vector3<f64>)> = self
+                .points_of_interest
+                .values()
+                .filter(|poi| poi.is_standing())
+                .map(|poi| (poi.id(), poi.position()))
+                .collect();
+            self.travel_distances = Some(DistanceMatrix::new(floor_plan, &standing));
+        }
+    }
+
+    // None falls back to MTM tables for humans and distance / speed for robots
+    pub fn set_fitts_coefficients(&mut self, agent: Uuid, coefficients: Option<FittsCoefficients>) {
+        match self.agents.get_mut(&agent) {
+            Some(Agent::Human(human_info)) => human_info.fitts = coefficients,
+            Some(Agent::Robot(robot_info)) => robot_info.fitts = coefficients,
+            None => {}
+        }
+    }
+
+    pub fn set_robot_motion(
+        &mut self,
+        agent: Uuid,
+        arm_motion: MotionProfile,
+        base_motion: MotionProfile,
+        coordination: Coordination,
+        blending: Blending,
+    ) {
+        match self.agents.get_mut(&agent) {
+            Some(Agent::Robot(robot_info)) => {
+                robot_info.arm_motion = arm_motion;
+                robot_info.base_motion = base_motion;
+                robot_info.coordination = coordination;
+                robot_info.blending = blending;
+            }
+            _ => {}
+        }
+    }
+
+    // Replace a robot's spherical reach with a workspace sampled from its URDF
+    pub fn load_robot_urdf(
+        &mut self,
+        agent: Uuid,
+        path: &str,
+        tip_link: Option<&str>,
+        samples: usize,
+        voxel_size: f64,
+        mount_yaw: f64,
+    ) -> Result<(), String> {
+        let chain = UrdfChain::from_file(path, tip_link)?;
+        let mut map = ReachabilityMap::build(&chain, samples, voxel_size, &mut rand::thread_rng());
+        map.mount_yaw = mount_yaw;
+        self.set_robot_reachability_map(agent, map)
+    }
+
+    pub fn set_robot_reachability_map(&mut self, agent: Uuid, map: ReachabilityMap) -> Result<(), String> {
+        match self.agents.get_mut(&agent) {
+            Some(Agent::Robot(robot_info)) => {
+                robot_info.reach = map.max_reach();
+                robot_info.reachability_map = Some(map);
+                Ok(())
+            }
+            _ => Err(format!("{} is not a robot", agent)),
+        }
+    }
+
+    // Fit an agent's coefficients to observed (distance, width, time) samples
+    pub fn fit_fitts_coefficients(
+        &mut self,
+        agent: Uuid,
+        samples: &[(f64, f64, Time)],
+    ) -> Result<FittsCoefficients, String> {
+        if !self.agents.contains_key(&agent) {
+            return Err(format!("Unknown agent {}", agent));
+        }
+        let coefficients = FittsCoefficients::fit(samples)?;
+        self.set_fitts_coefficients(agent, Some(coefficients.clone()));
+        Ok(coefficients)
+    }
+
+    pub fn add_tool(&mut self, tool: Tool) -> Uuid {
+        let uuid = tool.id;
+        self.tools.insert(uuid, tool);
+        uuid
+    }
+
+    pub fn add_robot_tool(&mut self, agent: Uuid, tool: Uuid) {
+        if let Some(Agent::Robot(robot_info)) = self.agents.get_mut(&agent) {
+            if !robot_info.tools.contains(&tool) {
+                robot_info.tools.push(tool);
+            }
+        }
+    }
+
     pub fn create_precursor_target(
         &mut self,
         name: String,
@@ -310,6 +658,26 @@ impl Job {
         self.target_pop = target_pop;
     }
 
+    pub fn set_demand(&mut self, demand: Demand) {
+        self.demand = Some(demand);
+    }
+
+    // Units are per the given period. Once the job has a demand, they are rescaled to its period.
+    pub fn add_product_demand(&mut self, target: Uuid, units: f64, mix: f64, period: Time) -> Result<(), String> {
+        if !(period.is_finite() && period > 0.0) {
+            return Err(format!("The demand period must be positive, not {}", period));
+        }
+        match self.demand.as_mut() {
+            Some(demand) => demand.add_product(target, units * demand.period / period, mix),
+            None => {
+                let mut demand = Demand::new(period);
+                demand.add_product(target, units, mix);
+                self.demand = Some(demand);
+            }
+        }
+        Ok(())
+    }
+
     pub fn create_petri_nets(&mut self) {
         self.basic_net = Some(self.create_basic_net());
         self.agent_net = Some(self.create_agent_net());
@@ -335,6 +703,7 @@ impl Job {
         if !self.poi_net.is_some() {
             self.poi_net = Some(self.create_poi_net());
         }
+        self.compute_travel_distances();
         self.compute_cost_from_poi()
     }
 }
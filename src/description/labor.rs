@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::constants::SEC_PER_HOUR;
+
+use super::agent::{Agent, HumanInfo};
+use super::job::Job;
+use super::units::{Time, USD};
+
+// What employing a person costs on top of their wage. Rates are fractions of the wage.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaborBurden {
+    pub benefits: f64,
+    pub payroll_tax: f64,
+    pub days_per_week: f64,
+    pub weeks_per_year: f64,
+    // Weekly hours beyond this are paid at the overtime multiplier
+    pub overtime_threshold: f64,
+    pub overtime_multiplier: f64,
+    // Extra pay for the hours of the shift outside the day window (seconds after midnight)
+    pub shift_differential: f64,
+    pub day_start: Time,
+    pub day_end: Time,
+    pub training_cost: USD,
+    pub onboarding_cost: USD,
+    // Share of the workforce that leaves each year and has to be hired (at the human's labor cost)
+    // and trained again
+    pub annual_turnover: f64,
+}
+
+impl Default for LaborBurden {
+    fn default() -> Self {
+        Self {
+            benefits: 0.3,
+            payroll_tax: 0.0765,
+            days_per_week: 5.0,
+            weeks_per_year: 50.0,
+            overtime_threshold: 40.0,
+            overtime_multiplier: 1.5,
+            shift_differential: 0.1,
+            day_start: 6.0 * SEC_PER_HOUR,
+            day_end: 18.0 * SEC_PER_HOUR,
+            training_cost: 2000.0,
+            onboarding_cost: 1000.0,
+            annual_turnover: 0.3,
+        }
+    }
+}
+
+// Hourly cost of a worker, broken down, and what it costs to take one on
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaborCost {
+    pub wage: USD,
+    pub overtime: USD,
+    pub shift_differential: USD,
+    pub benefits: USD,
+    pub payroll_tax: USD,
+    pub turnover: USD,
+    pub hourly: USD, // the total
+    pub annual_hours: f64,
+    // Hiring, training and onboarding
+    pub once: USD,
+}
+
+impl LaborBurden {
+    pub fn cost(&self, human: &HumanInfo, shift_start: Time, shift_length: Time) -> LaborCost {
+        let shift_hours = shift_length / SEC_PER_HOUR;
+        let weekly_hours = shift_hours * self.days_per_week;
+        let annual_hours = weekly_hours * self.weeks_per_year;
+
+        let overtime_hours = (weekly_hours - self.overtime_threshold).max(0.0);
+        let overtime_share = if weekly_hours > 0.0 { overtime_hours / weekly_hours } else { 0.0 };
+        let overtime = human.hourly_wage * overtime_share * (self.overtime_multiplier - 1.0);
+        let night_share = if shift_length > 0.0 {
+            self.off_day_time(shift_start, shift_length) / shift_length
+        } else {
+            0.0
+        };
+        let shift_differential = human.hourly_wage * night_share * self.shift_differential;
+        let gross = human.hourly_wage + overtime + shift_differential;
+        let benefits = gross * self.benefits;
+        let payroll_tax = gross * self.payroll_tax;
+
+        let once = human.labor_cost + self.training_cost + self.onboarding_cost;
+        // Replacements are hired the same way, spread over the hours worked in a year
+        let turnover = if annual_hours > 0.0 {
+            self.annual_turnover * once / annual_hours
+        } else {
+            0.0
+        };
+
+        LaborCost {
+            wage: human.hourly_wage,
+            overtime,
+            shift_differential,
+            benefits,
+            payroll_tax,
+            turnover,
+            hourly: gross + benefits + payroll_tax + turnover,
+            annual_hours,
+            once,
+        }
+    }
+
+    // Time of the shift that falls outside the day window
+    fn off_day_time(&self, start: Time, length: Time) -> Time {
+        let day = 24.0 * SEC_PER_HOUR;
+        let step = 60.0;
+        let steps = (length / step).ceil() as usize;
+        (0..steps)
+            .map(|i| {
+                let time = (start + i as f64 * step).rem_euclid(day);
+                let duration = step.min(length - i as f64 * step);
+                if time >= self.day_start && time < self.day_end { 0.0 } else { duration }
+            })
+            .sum()
+    }
+}
+
+impl Job {
+    pub fn set_labor_burden(&mut self, burden: LaborBurden) {
+        self.labor_burden = burden;
+    }
+
+    pub fn set_human_labor_burden(&mut self, agent: Uuid, burden: LaborBurden) {
+        if let Some(Agent::Human(human_info)) = self.agents.get_mut(&agent) {
+            human_info.labor_burden = Some(burden);
+        }
+    }
+
+    pub fn labor_burden_for<'a>(&'a self, human: &'a HumanInfo) -> &'a LaborBurden {
+        human.labor_burden.as_ref().unwrap_or(&self.labor_burden)
+    }
+
+    pub fn labor_cost(&self, human: &HumanInfo) -> LaborCost {
+        self.labor_burden_for(human)
+            .cost(human, self.shift.start, self.shift.length)
+    }
+}
+
+#[test]
+fn labor_burden_test() {
+    use super::gender::Gender;
+    use super::rating::Rating;
+
+    let human = match Agent::new_human(
+        "Worker".into(), 30.0, Gender::Female, 1.4, 1.7, 0.7, 70.0, Rating::Medium, 20.0, 500.0,
+    ) {
+        Agent::Human(human) => human,
+        Agent::Robot(_) => unreachable!(),
+    };
+    let burden = LaborBurden {
+        benefits: 0.3,
+        payroll_tax: 0.1,
+        annual_turnover: 0.0,
+        ..Default::default()
+    };
+
+    // A 40 hour week of day shifts costs the wage plus benefits and taxes
+    let day = burden.cost(&human, 8.0 * SEC_PER_HOUR, 8.0 * SEC_PER_HOUR);
+    assert!((day.hourly - 28.0).abs() < 1e-9);
+    assert_eq!(day.annual_hours, 2000.0);
+    assert_eq!(day.once, 500.0 + 2000.0 + 1000.0);
+
+    // Ten hour shifts go into overtime, and end after dark
+    let long = burden.cost(&human, 10.0 * SEC_PER_HOUR, 10.0 * SEC_PER_HOUR);
+    assert!((long.overtime - 20.0 * 0.2 * 0.5).abs() < 1e-9);
+    assert!((long.shift_differential - 20.0 * 0.2 * 0.1).abs() < 1e-9);
+
+    let churn = LaborBurden { annual_turnover: 0.5, ..burden }.cost(&human, 8.0 * SEC_PER_HOUR, 8.0 * SEC_PER_HOUR);
+    assert!((churn.turnover - 0.5 * 3500.0 / 2000.0).abs() < 1e-9);
+}
@@ -1,11 +1,31 @@
 pub mod generators;
 pub mod agent;
+pub mod builder;
+pub mod age;
+pub mod anthropometry;
+pub mod demand;
+pub mod energy;
+pub mod finance;
+pub mod fitts;
+pub mod floor;
+pub mod human_error;
+pub mod input;
+pub mod integration;
 pub mod job;
+pub mod labor;
+pub mod motion;
+pub mod mtm;
 pub mod poi;
 pub mod primitive;
+pub mod quality;
 pub mod rating;
+pub mod robot_error;
+pub mod safety;
 pub mod target;
 pub mod task;
+pub mod tool;
+pub mod uncertainty;
 pub mod units;
+pub mod urdf;
 pub mod weights;
 pub mod gender;
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use super::rating::Rating;
+use super::units::Time;
+
+// How base travel and arm motion are combined within one primitive
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Coordination {
+    #[default]
+    Sequential,   // The base stops before the arm moves
+    Simultaneous, // The arm moves while the base travels
+}
+
+// Whether consecutive sequential segments come to a full stop between them
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Blending {
+    #[default]
+    StopAndGo,
+    // The next segment accelerates while the previous one decelerates
+    Blended,
+}
+
+// Limits on a point-to-point motion. Without a jerk limit the profile is trapezoidal,
+// with one it is a (7-segment) S-curve.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotionProfile {
+    pub acceleration: f64, // m/s^2
+    pub jerk: Option<f64>, // m/s^3
+}
+
+impl MotionProfile {
+    pub fn trapezoidal(acceleration: f64) -> Self {
+        Self {
+            acceleration,
+            jerk: None,
+        }
+    }
+
+    pub fn s_curve(acceleration: f64, jerk: f64) -> Self {
+        Self {
+            acceleration,
+            jerk: Some(jerk),
+        }
+    }
+
+    pub fn arm(agility: &Rating) -> Self {
+        match agility {
+            Rating::Low => Self::s_curve(1.0, 5.0),
+            Rating::Medium => Self::s_curve(2.5, 15.0),
+            Rating::High => Self::s_curve(5.0, 40.0),
+        }
+    }
+
+    // Mobile bases carry the arm and accelerate more gently
+    pub fn base(agility: &Rating) -> Self {
+        match agility {
+            Rating::Low => Self::trapezoidal(0.3),
+            Rating::Medium => Self::trapezoidal(0.5),
+            Rating::High => Self::trapezoidal(1.0),
+        }
+    }
+
+    // For robots stored without a profile
+    pub fn default_arm() -> Self {
+        Self::arm(&Rating::Medium)
+    }
+
+    pub fn default_base() -> Self {
+        Self::base(&Rating::Medium)
+    }
+
+    // (time, distance) to accelerate from rest to `velocity`
+    fn ramp(&self, velocity: f64) -> (Time, f64) {
+        let time = match self.jerk {
+            Some(jerk) if velocity < self.acceleration.powi(2) / jerk => 2.0 * (velocity / jerk).sqrt(),
+            Some(jerk) => velocity / self.acceleration + self.acceleration / jerk,
+            None => velocity / self.acceleration,
+        };
+        // Both profiles are symmetric, so the average velocity over the ramp is half the peak
+        (time, velocity * time / 2.0)
+    }
+
+    // Peak velocity reached on a move from rest to rest, limited by `max_speed`
+    fn peak_velocity(&self, distance: f64, max_speed: f64) -> f64 {
+        if 2.0 * self.ramp(max_speed).1 <= distance {
+            return max_speed;
+        }
+        // Short move: find the peak at which accelerating and decelerating covers the distance
+        let (mut low, mut high) = (0.0, max_speed);
+        for _ in 0..60 {
+            let mid = (low + high) / 2.0;
+            if 2.0 * self.ramp(mid).1 > distance {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        low
+    }
+
+    // Rest-to-rest time over `distance` at a top speed of `max_speed`
+    pub fn time(&self, distance: f64, max_speed: f64) -> Time {
+        if distance <= 0.0 {
+            return 0.0;
+        }
+        if max_speed <= 0.0 || self.acceleration <= 0.0 {
+            return f64::INFINITY;
+        }
+        let peak = self.peak_velocity(distance, max_speed);
+        let (ramp_time, ramp_distance) = self.ramp(peak);
+        2.0 * ramp_time + (distance - 2.0 * ramp_distance).max(0.0) / peak
+    }
+
+    // Time spent accelerating (and, symmetrically, decelerating) on this move
+    pub fn ramp_time(&self, distance: f64, max_speed: f64) -> Time {
+        if distance <= 0.0 || max_speed <= 0.0 || self.acceleration <= 0.0 {
+            return 0.0;
+        }
+        self.ramp(self.peak_velocity(distance, max_speed)).0
+    }
+}
+
+// Combined time of a base segment followed by an arm segment
+pub fn combine_segments(
+    coordination: &Coordination,
+    blending: &Blending,
+    first: (Time, Time), // (duration, ramp time)
+    second: (Time, Time),
+) -> Time {
+    match (coordination, blending) {
+        (Coordination::Simultaneous, _) => first.0.max(second.0),
+        (Coordination::Sequential, Blending::StopAndGo) => first.0 + second.0,
+        (Coordination::Sequential, Blending::Blended) => {
+            first.0 + second.0 - first.1.min(second.1)
+        }
+    }
+}
+
+#[test]
+fn motion_profile_test() {
+    let trapezoid = MotionProfile::trapezoidal(1.0);
+    // Long move: 1s up to speed, 1s down, and the remaining 1m at 1 m/s
+    assert!((trapezoid.time(2.0, 1.0) - 3.0).abs() < 1e-9);
+    // Short move never reaches top speed: triangular profile
+    assert!((trapezoid.time(0.25, 1.0) - 1.0).abs() < 1e-6);
+
+    // Limiting jerk makes every move slower than the trapezoid
+    let s_curve = MotionProfile::s_curve(1.0, 2.0);
+    assert!(s_curve.time(2.0, 1.0) > trapezoid.time(2.0, 1.0));
+    assert!(s_curve.time(0.05, 1.0) > trapezoid.time(0.05, 1.0));
+    assert_eq!(s_curve.time(0.0, 1.0), 0.0);
+
+    let base = (3.0, 1.0);
+    let arm = (2.0, 0.5);
+    assert_eq!(combine_segments(&Coordination::Simultaneous, &Blending::StopAndGo, base, arm), 3.0);
+    assert_eq!(combine_segments(&Coordination::Sequential, &Blending::StopAndGo, base, arm), 5.0);
+    assert_eq!(combine_segments(&Coordination::Sequential, &Blending::Blended, base, arm), 4.5);
+}
@@ -0,0 +1,469 @@
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{DISTANCE_PER_PACE, TMU_PER_SECOND};
+
+use super::fitts::FittsCoefficients;
+use super::rating::Rating;
+use super::units::Time;
+
+// MTM-1 tables. Distances are tabulated in inches, weights are in Newtons (see Target::weight).
+
+const INCHES_PER_METER: f64 = 39.37;
+
+// Tabulated distances (in) for Reach and Move. Anything at or below 3/4" uses the first column.
+const DISTANCE_BANDS: [f64; 21] = [
+    0.75, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 12.0, 14.0, 16.0, 18.0, 20.0, 22.0,
+    24.0, 26.0, 28.0, 30.0,
+];
+
+const REACH_A: [f64; 21] = [
+    2.0, 2.5, 4.0, 5.3, 6.1, 6.5, 7.0, 7.4, 7.9, 8.3, 8.7, 9.6, 10.5, 11.4, 12.3, 13.1, 14.0, 14.9,
+    15.8, 16.7, 17.5,
+];
+const REACH_B: [f64; 21] = [
+    2.0, 2.5, 4.0, 5.3, 6.4, 7.8, 8.6, 9.3, 10.1, 10.8, 11.5, 12.9, 14.4, 15.8, 17.2, 18.6, 20.1,
+    21.5, 22.9, 24.4, 25.8,
+];
+const REACH_CD: [f64; 21] = [
+    2.0, 3.6, 5.9, 7.3, 8.4, 9.4, 10.1, 10.8, 11.5, 12.2, 12.9, 14.2, 15.6, 17.0, 18.4, 19.8, 21.2,
+    22.5, 23.9, 25.3, 26.7,
+];
+const REACH_E: [f64; 21] = [
+    2.0, 2.4, 3.8, 5.3, 6.8, 7.4, 8.0, 8.7, 9.3, 9.9, 10.5, 11.8, 13.0, 14.2, 15.5, 16.7, 18.0,
+    19.2, 20.4, 21.7, 22.9,
+];
+
+const MOVE_A: [f64; 21] = [
+    2.0, 2.5, 3.6, 4.9, 6.1, 7.3, 8.1, 8.9, 9.7, 10.5, 11.3, 12.9, 14.4, 16.0, 17.6, 19.2, 20.8,
+    22.4, 24.0, 25.5, 27.1,
+];
+const MOVE_B: [f64; 21] = [
+    2.0, 2.9, 4.6, 5.7, 6.9, 8.0, 8.9, 9.7, 10.6, 11.5, 12.2, 13.4, 14.6, 15.8, 17.0, 18.2, 19.4,
+    20.6, 21.8, 23.1, 24.3,
+];
+const MOVE_C: [f64; 21] = [
+    2.0, 3.4, 5.2, 6.7, 8.0, 9.2, 10.3, 11.1, 11.8, 12.7, 13.5, 15.2, 16.9, 18.7, 20.4, 22.1, 23.8,
+    25.5, 27.3, 29.0, 30.7,
+];
+
+// Tabulated turn angles (degrees)
+const TURN_BANDS: [f64; 11] = [
+    30.0, 45.0, 60.0, 75.0, 90.0, 105.0, 120.0, 135.0, 150.0, 165.0, 180.0,
+];
+const TURN_SMALL: [f64; 11] = [2.8, 3.5, 4.1, 4.8, 5.4, 6.1, 6.8, 7.4, 8.1, 8.7, 9.4];
+const TURN_MEDIUM: [f64; 11] = [4.4, 5.5, 6.5, 7.5, 8.5, 9.6, 10.6, 11.6, 12.7, 13.7, 14.8];
+const TURN_LARGE: [f64; 11] = [8.4, 10.5, 12.3, 14.4, 16.2, 18.3, 20.4, 22.2, 24.3, 26.1, 28.2];
+
+// Upper weight bounds (N) for small (2 lb) and medium (10 lb) objects
+pub const SMALL_WEIGHT: f64 = 8.9271;
+pub const MEDIUM_WEIGHT: f64 = 44.5374;
+
+// Weight allowance for Move: (LOWER BOUND, UPPER BOUND, DYNAMIC FACTOR, STATIC CONSTANT)
+pub const MOVE_WEIGHT_ALLOWANCE: [(f64, f64, f64, f64); 9] = [
+    (11.0853, 33.354, 1.06, 2.2),
+    (33.354, 55.6227, 1.11, 3.9),
+    (55.6227, 77.8914, 1.17, 5.6),
+    (77.8914, 100.1601, 1.22, 7.4),
+    (100.1601, 122.625, 1.28, 9.1),
+    (122.625, 144.5994, 1.33, 10.8),
+    (144.5994, 166.77, 1.39, 12.5),
+    (166.77, 188.9406, 1.44, 14.3),
+    (188.9406, f64::INFINITY, 1.5, 16.0),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReachCase {
+    A, // Object in fixed location, or in the other hand
+    B, // Single object in a location that varies slightly
+    C, // Object jumbled with others
+    D, // Very small object, or one needing an accurate grasp
+    E, // Indefinite location, to clear the hand or balance the body
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MoveCase {
+    A, // Object to the other hand or against a stop
+    B, // Object to an approximate or indefinite location
+    C, // Object to an exact location
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GraspCase {
+    G1A, // Pick up, small, medium or large object by itself
+    G1B, // Very small object, or one lying close against a flat surface
+    G1C1, // Interference with the grasp on the bottom and one side, diameter > 1/2"
+    G1C2, // Interference with the grasp, diameter 1/4" to 1/2"
+    G1C3, // Interference with the grasp, diameter < 1/4"
+    G2,  // Regrasp
+    G3,  // Transfer grasp
+    G4A, // Jumbled objects, larger than 1" x 1" x 1"
+    G4B, // Jumbled objects, 1/4" x 1/4" x 1/8" to 1" x 1" x 1"
+    G4C, // Jumbled objects, smaller than 1/4" x 1/4" x 1/8"
+    G5,  // Contact, sliding or hook grasp
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Fit {
+    Loose, // No pressure required
+    Close, // Light pressure required
+    Exact, // Heavy pressure required
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReleaseCase {
+    RL1, // Normal release by opening the fingers
+    RL2, // Contact release
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PressureCase {
+    A, // Apply pressure without regrasping
+    B, // Apply pressure including a regrasp
+}
+
+impl ReachCase {
+    // More variable destinations need more visual control
+    pub fn from_variability(variability: &Rating) -> Self {
+        match variability {
+            Rating::Low => ReachCase::A,
+            Rating::Medium => ReachCase::B,
+            Rating::High => ReachCase::C,
+        }
+    }
+}
+
+impl MoveCase {
+    pub fn from_variability(variability: &Rating) -> Self {
+        match variability {
+            Rating::Low => MoveCase::A,
+            Rating::Medium => MoveCase::B,
+            Rating::High => MoveCase::C,
+        }
+    }
+}
+
+impl GraspCase {
+    // Selecting an object from a group depends on its size (m)
+    pub fn jumbled(size: f64) -> Self {
+        if size < 0.00635 {
+            GraspCase::G4C
+        } else if size < 0.0254 {
+            GraspCase::G4B
+        } else {
+            GraspCase::G4A
+        }
+    }
+}
+
+impl Fit {
+    // The fit class follows from how much of the worker's strength the engagement takes
+    pub fn from_mvc(mvc: f64) -> Self {
+        if mvc < 0.2 {
+            Fit::Loose
+        } else if mvc < 0.5 {
+            Fit::Close
+        } else {
+            Fit::Exact
+        }
+    }
+
+    // Clearance left by the location's tolerance: wide ones drop in, tight ones must be pressed
+    pub fn from_tolerance(width: f64) -> Self {
+        if width >= 0.01 {
+            Fit::Loose
+        } else if width >= 0.002 {
+            Fit::Close
+        } else {
+            Fit::Exact
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MtmElement {
+    Reach { case: ReachCase, distance: f64 }, // meters
+    Move { case: MoveCase, distance: f64, weight: f64 }, // meters, Newtons
+    Turn { degrees: f64, weight: f64 },
+    Grasp(GraspCase),
+    Position { fit: Fit, symmetry: Rating, easy: bool },
+    Disengage { fit: Fit, easy: bool },
+    ApplyPressure(PressureCase),
+    Release(ReleaseCase),
+    EyeTravel { travel: f64, distance: f64 }, // meters between points, meters from the eyes
+    EyeFocus,
+    Walk { distance: f64, loaded: bool }, // meters
+    Bend, // Bend or stoop to a low location and arise
+    // Reach or move timed by Fitts' law instead of the distance tables
+    Pointing { distance: f64, width: f64, weight: f64, coefficients: FittsCoefficients },
+}
+
+fn distance_lookup(table: &[f64; 21], distance: f64, per_inch_beyond: f64) -> f64 {
+    if distance <= 0.0 {
+        return 0.0;
+    }
+    let inches = distance * INCHES_PER_METER;
+    match DISTANCE_BANDS.iter().position(|band| inches <= *band) {
+        Some(idx) => table[idx],
+        None => table[20] + per_inch_beyond * (inches - 30.0),
+    }
+}
+
+// Heavier objects move more slowly and take longer to get moving
+fn weight_allowance(tmu: f64, weight: f64) -> f64 {
+    match MOVE_WEIGHT_ALLOWANCE
+        .iter()
+        .find(|(lower, upper, _, _)| *lower < weight && weight <= *upper)
+    {
+        Some((_, _, factor, constant)) => tmu * factor + constant,
+        None => tmu,
+    }
+}
+
+fn turn_lookup(degrees: f64, weight: f64) -> f64 {
+    let table = if weight <= SMALL_WEIGHT {
+        &TURN_SMALL
+    } else if weight <= MEDIUM_WEIGHT {
+        &TURN_MEDIUM
+    } else {
+        &TURN_LARGE
+    };
+    // Turns past 180 degrees are performed as repeated half turns
+    let mut remaining = degrees.abs();
+    let mut tmu = 0.0;
+    while remaining > 180.0 {
+        tmu += table[10];
+        remaining -= 180.0;
+    }
+    if remaining > 0.0 {
+        let idx = TURN_BANDS
+            .iter()
+            .position(|band| remaining <= *band)
+            .unwrap_or(10);
+        tmu += table[idx];
+    }
+    tmu
+}
+
+impl MtmElement {
+    pub fn code(&self) -> String {
+        match self {
+            MtmElement::Reach { case, .. } => format!("R{:?}", case),
+            MtmElement::Move { case, .. } => format!("M{:?}", case),
+            MtmElement::Turn { .. } => "T".into(),
+            MtmElement::Grasp(case) => format!("{:?}", case),
+            MtmElement::Position { fit, symmetry, easy } => format!(
+                "P{}{}{}",
+                match fit {
+                    Fit::Loose => 1,
+                    Fit::Close => 2,
+                    Fit::Exact => 3,
+                },
+                match symmetry {
+                    Rating::High => "S",
+                    Rating::Medium => "SS",
+                    Rating::Low => "NS",
+                },
+                if *easy { "E" } else { "D" }
+            ),
+            MtmElement::Disengage { fit, easy } => format!(
+                "D{}{}",
+                match fit {
+                    Fit::Loose => 1,
+                    Fit::Close => 2,
+                    Fit::Exact => 3,
+                },
+                if *easy { "E" } else { "D" }
+            ),
+            MtmElement::ApplyPressure(case) => format!("AP{:?}", case),
+            MtmElement::Release(case) => format!("{:?}", case),
+            MtmElement::EyeTravel { .. } => "ET".into(),
+            MtmElement::EyeFocus => "EF".into(),
+            MtmElement::Walk { .. } => "W-P".into(),
+            MtmElement::Bend => "B".into(),
+            MtmElement::Pointing { .. } => "FL".into(),
+        }
+    }
+
+    pub fn tmu(&self) -> f64 {
+        match self {
+            MtmElement::Reach { case, distance } => match case {
+                ReachCase::A => distance_lookup(&REACH_A, *distance, 0.4),
+                ReachCase::B => distance_lookup(&REACH_B, *distance, 0.7),
+                ReachCase::C | ReachCase::D => distance_lookup(&REACH_CD, *distance, 0.7),
+                ReachCase::E => distance_lookup(&REACH_E, *distance, 0.6),
+            },
+            MtmElement::Move {
+                case,
+                distance,
+                weight,
+            } => {
+                let tmu = match case {
+                    MoveCase::A => distance_lookup(&MOVE_A, *distance, 0.8),
+                    MoveCase::B => distance_lookup(&MOVE_B, *distance, 0.6),
+                    MoveCase::C => distance_lookup(&MOVE_C, *distance, 0.85),
+                };
+                weight_allowance(tmu, *weight)
+            }
+            MtmElement::Turn { degrees, weight } => turn_lookup(*degrees, *weight),
+            MtmElement::Grasp(case) => match case {
+                GraspCase::G1A => 2.0,
+                GraspCase::G1B => 3.5,
+                GraspCase::G1C1 => 7.3,
+                GraspCase::G1C2 => 8.7,
+                GraspCase::G1C3 => 10.8,
+                GraspCase::G2 => 5.6,
+                GraspCase::G3 => 5.6,
+                GraspCase::G4A => 7.3,
+                GraspCase::G4B => 9.1,
+                GraspCase::G4C => 12.9,
+                GraspCase::G5 => 0.0,
+            },
+            MtmElement::Position {
+                fit,
+                symmetry,
+                easy,
+            } => {
+                let (easy_tmu, difficult_tmu) = match (fit, symmetry) {
+                    (Fit::Loose, Rating::High) => (5.6, 11.2),
+                    (Fit::Loose, Rating::Medium) => (9.1, 14.7),
+                    (Fit::Loose, Rating::Low) => (10.4, 16.0),
+                    (Fit::Close, Rating::High) => (16.2, 21.8),
+                    (Fit::Close, Rating::Medium) => (19.7, 25.3),
+                    (Fit::Close, Rating::Low) => (21.0, 26.6),
+                    (Fit::Exact, Rating::High) => (43.0, 48.6),
+                    (Fit::Exact, Rating::Medium) => (46.5, 52.1),
+                    (Fit::Exact, Rating::Low) => (47.8, 53.4),
+                };
+                if *easy {
+                    easy_tmu
+                } else {
+                    difficult_tmu
+                }
+            }
+            MtmElement::Disengage { fit, easy } => match (fit, easy) {
+                (Fit::Loose, true) => 4.0,
+                (Fit::Loose, false) => 5.7,
+                (Fit::Close, true) => 7.5,
+                (Fit::Close, false) => 11.8,
+                (Fit::Exact, true) => 22.9,
+                (Fit::Exact, false) => 34.7,
+            },
+            MtmElement::ApplyPressure(case) => match case {
+                PressureCase::A => 10.6,
+                PressureCase::B => 16.2,
+            },
+            MtmElement::Release(case) => match case {
+                ReleaseCase::RL1 => 2.0,
+                ReleaseCase::RL2 => 0.0,
+            },
+            MtmElement::EyeTravel { travel, distance } => {
+                if *distance <= 0.0 {
+                    return 0.0;
+                }
+                (15.2 * travel / distance).min(20.0)
+            }
+            MtmElement::EyeFocus => 7.3,
+            MtmElement::Walk { distance, loaded } => {
+                let per_pace = if *loaded { 17.0 } else { 15.0 };
+                per_pace * distance / DISTANCE_PER_PACE
+            }
+            MtmElement::Bend => 30.5,
+            MtmElement::Pointing {
+                distance,
+                width,
+                weight,
+                coefficients,
+            } => {
+                if *distance <= 0.0 {
+                    return 0.0;
+                }
+                weight_allowance(coefficients.time(*distance, *width) / TMU_PER_SECOND, *weight)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MtmStep {
+    pub code: String,
+    pub element: MtmElement,
+    pub tmu: f64,
+}
+
+// An itemized sequence of MTM elements
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MtmBreakdown {
+    pub steps: Vec<MtmStep>,
+}
+
+impl MtmBreakdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, element: MtmElement) {
+        self.push_scaled(element, 1.0);
+    }
+
+    // Adds an element whose tabulated time is scaled, e.g. for a slower walking pace
+    pub fn push_scaled(&mut self, element: MtmElement, scale: f64) {
+        let tmu = element.tmu() * scale;
+        if tmu > 0.0 {
+            self.steps.push(MtmStep {
+                code: element.code(),
+                element,
+                tmu,
+            });
+        }
+    }
+
+    pub fn total_tmu(&self) -> f64 {
+        self.steps.iter().map(|s| s.tmu).sum()
+    }
+
+    pub fn seconds(&self) -> Time {
+        self.total_tmu() * TMU_PER_SECOND
+    }
+}
+
+#[test]
+fn mtm_table_test() {
+    // 10" reach to a fixed location and a 10" move to an exact location
+    let reach = MtmElement::Reach {
+        case: ReachCase::A,
+        distance: 10.0 / INCHES_PER_METER,
+    };
+    assert_eq!(reach.tmu(), 8.7);
+    let heavy = MtmElement::Move {
+        case: MoveCase::C,
+        distance: 10.0 / INCHES_PER_METER,
+        weight: 40.0,
+    };
+    assert!((heavy.tmu() - (13.5 * 1.11 + 3.9)).abs() < 1e-9);
+
+    // Beyond the table, time grows per inch
+    let long = MtmElement::Reach {
+        case: ReachCase::B,
+        distance: 40.0 / INCHES_PER_METER,
+    };
+    assert!((long.tmu() - (25.8 + 0.7 * 10.0)).abs() < 1e-6);
+
+    assert_eq!(MtmElement::Turn { degrees: 90.0, weight: 1.0 }.tmu(), 5.4);
+    assert_eq!(MtmElement::Turn { degrees: 270.0, weight: 1.0 }.tmu(), 9.4 + 5.4);
+
+    let mut breakdown = MtmBreakdown::new();
+    breakdown.push(MtmElement::Grasp(GraspCase::G1A));
+    breakdown.push(MtmElement::Position {
+        fit: Fit::from_mvc(0.3),
+        symmetry: Rating::Medium,
+        easy: true,
+    });
+    breakdown.push(MtmElement::Release(ReleaseCase::RL2));
+    breakdown.push(MtmElement::Release(ReleaseCase::RL1));
+    assert_eq!(breakdown.steps.len(), 3);
+    assert_eq!(breakdown.steps[1].code, "P2SSE");
+    assert!((breakdown.total_tmu() - 23.7).abs() < 1e-9);
+}
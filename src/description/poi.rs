@@ -64,6 +64,29 @@ impl PointOfInterest {
         }
     }
 
+    pub fn location(&self) -> &Location {
+        match self {
+            PointOfInterest::Standing(location) => location,
+            PointOfInterest::Hand(location) => location
+        }
+    }
+
+    pub fn location_mut(&mut self) -> &mut Location {
+        match self {
+            PointOfInterest::Standing(location) => location,
+            PointOfInterest::Hand(location) => location
+        }
+    }
+
+    // Full width of the location's extent along its narrowest axis, if it has an extent
+    pub fn tolerance(&self) -> Option<f64> {
+        self.location().displacement
+            .iter()
+            .map(|half_width| 2.0 * half_width.abs())
+            .filter(|width| *width > 0.0)
+            .fold(None, |narrowest: Option<f64>, width| Some(narrowest.map_or(width, |n| n.min(width))))
+    }
+
     pub fn structure(&self) -> Rating {
         match self {
             PointOfInterest::Standing(location) => location.structure.clone(),
@@ -75,26 +98,44 @@ impl PointOfInterest {
         if (self.is_standing() && other.is_standing()) || (self.is_hand() && other.is_hand()) {
             return false;
         }
+        let location = other.location();
+        let mut origin = self.position().clone();
+        origin.z += match agent {
+            Agent::Robot(robot_info) => robot_info.vertical_offset,
+            Agent::Human(human_info) => human_info.acromial_height,
+        };
+        match location.reach {
+            ReachCriterion::Center => self.reaches(&location.position, agent),
+            ReachCriterion::Nearest => self.reaches(&location.nearest_point(&origin), agent),
+            ReachCriterion::Farthest => self.reaches(&location.farthest_point(&origin), agent),
+            ReachCriterion::Volume => location.sample_points().iter().all(|point| self.reaches(point, agent)),
+        }
+    }
+
+    // Whether the agent, standing here, can reach a single point
+    fn reaches(&self, point: &Vector3<f64>, agent: &Agent) -> bool {
         match agent {
             Agent::Robot(robot_info) => {
                 let mut offset_pos = self.position().clone();
                 offset_pos.z += robot_info.vertical_offset;
-                let distance: f64 = (offset_pos.clone() - &other.position()).norm();
+                if let Some(map) = &robot_info.reachability_map {
+                    return map.contains(&(point - offset_pos));
+                }
+                let distance: f64 = (offset_pos.clone() - point).norm();
                 return distance <= robot_info.reach && distance >= (robot_info.reach * 0.05);
             },
             Agent::Human(human_info) => {
-                let distance: f64 = (&self.position() - &other.position()).norm();
                 let mut offset_pos = self.position().clone();
                 offset_pos.z += human_info.acromial_height;
 
                 let self_pos = Vector2::new(self.position().x, self.position().y);
-                let other_pos = Vector2::new(other.position().x, other.position().y);
+                let other_pos = Vector2::new(point.x, point.y);
 
                 let horizontal_distance: f64 = (self_pos.clone() - other_pos.clone()).norm();
-                let total_distance: f64 = (offset_pos.clone() - other.position().clone()).norm();
+                let total_distance: f64 = (offset_pos.clone() - point.clone()).norm();
                 
                 // person can bend/reach down
-                if other.position().z <= offset_pos.z {
+                if point.z <= offset_pos.z {
                     return horizontal_distance <= human_info.reach;
                 }
                 // person is standing/can reach up
@@ -119,26 +160,154 @@ impl PointOfInterest {
 
 }
 
+// Which part of a location's volume an agent must be able to reach
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReachCriterion {
+    #[default]
+    Center,
+    Nearest,  // Any part of it, e.g. the lip of a bin
+    Farthest, // The far side of it
+    Volume,   // All of it, e.g. every slot in a tray
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Location {
     pub id: Uuid,
     pub name: String,
-    pub position: Vector3<f64>,
+    pub position: Vector3<f64>,     // meters
     pub shape: Shape,
-    pub displacement: Vector3<f64>,
+    pub displacement: Vector3<f64>, // meters, half extent along each axis
+    #[serde(default)]
+    pub reach: ReachCriterion,
+    // Where a layout optimizer may move this location, if anywhere
+    #[serde(default)]
+    pub movable: Option<MovableRange>,
+
     pub variability: Rating,
     pub structure: Rating,
 
 }
 
+// Slack for comparisons on the boundary of a volume
+const EPSILON: f64 = 1e-9;
+
 impl Location {
     pub fn new(name: String, x: f64, y: f64, z: f64, variability: Rating, structure: Rating) -> Self {
         Self { 
             id: Uuid::new_v4(), name, position: Vector3::new(x, y, z),
             shape: Shape::Ellipsoid, displacement: Vector3::new(0.0, 0.0, 0.0),
+            reach: ReachCriterion::Center,
+            movable: None,
             variability, structure
         }
     }
+
+    pub fn has_volume(&self) -> bool {
+        self.displacement.iter().all(|half_width| half_width.abs() > EPSILON)
+    }
+
+    pub fn bounds(&self) -> (Vector3<f64>, Vector3<f64>) {
+        let half = self.displacement.abs();
+        (self.position - half, self.position + half)
+    }
+
+    pub fn contains(&self, point: &Vector3<f64>) -> bool {
+        let offset = (point - self.position).abs();
+        let half = self.displacement.abs();
+        match self.shape {
+            Shape::Cuboid => (0..3).all(|i| offset[i] <= half[i] + EPSILON),
+            Shape::Ellipsoid => {
+                let mut sum = 0.0;
+                for i in 0..3 {
+                    if half[i] <= EPSILON {
+                        // Flat along this axis
+                        if offset[i] > EPSILON {
+                            return false;
+                        }
+                    } else {
+                        sum += (offset[i] / half[i]).powi(2);
+                    }
+                }
+                sum <= 1.0 + EPSILON
+            }
+        }
+    }
+
+    // The center plus points spread over the boundary: the corners, edge and face centers of a cuboid,
+    // or the ellipsoid's surface along the same 26 directions
+    pub fn sample_points(&self) -> Vec<Vector3<f64>> {
+        let mut points = vec![self.position];
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    if x == 0 && y == 0 && z == 0 {
+                        continue;
+                    }
+                    let mut direction = Vector3::new(x as f64, y as f64, z as f64);
+                    if self.shape == Shape::Ellipsoid {
+                        direction.normalize_mut();
+                    }
+                    points.push(self.position + direction.component_mul(&self.displacement.abs()));
+                }
+            }
+        }
+        points
+    }
+
+    // Closest point of the volume to `from`. Exact for cuboids, sampled for ellipsoids.
+    pub fn nearest_point(&self, from: &Vector3<f64>) -> Vector3<f64> {
+        if self.contains(from) {
+            return from.clone();
+        }
+        match self.shape {
+            Shape::Cuboid => {
+                let (min, max) = self.bounds();
+                Vector3::new(from.x.clamp(min.x, max.x), from.y.clamp(min.y, max.y), from.z.clamp(min.z, max.z))
+            }
+            Shape::Ellipsoid => self
+                .sample_points()
+                .into_iter()
+                .min_by(|a, b| (a - from).norm().partial_cmp(&(b - from).norm()).unwrap())
+                .unwrap(),
+        }
+    }
+
+    // Point of the volume furthest from `from`. Exact for cuboids (a corner), sampled for ellipsoids.
+    pub fn farthest_point(&self, from: &Vector3<f64>) -> Vector3<f64> {
+        self.sample_points()
+            .into_iter()
+            .max_by(|a, b| (a - from).norm().partial_cmp(&(b - from).norm()).unwrap())
+            .unwrap()
+    }
+
+    // Whether the two volumes share any space. Boxes are compared exactly; otherwise the
+    // overlap of their bounding boxes is searched on a grid.
+    pub fn overlaps(&self, other: &Location) -> bool {
+        let (min_a, max_a) = self.bounds();
+        let (min_b, max_b) = other.bounds();
+        let min = min_a.sup(&min_b);
+        let max = max_a.inf(&max_b);
+        if (0..3).any(|i| min[i] > max[i] + EPSILON) {
+            return false;
+        }
+        if self.shape == Shape::Cuboid && other.shape == Shape::Cuboid {
+            return true;
+        }
+        let steps = 8;
+        for x in 0..=steps {
+            for y in 0..=steps {
+                for z in 0..=steps {
+                    let fraction = Vector3::new(x as f64, y as f64, z as f64) / steps as f64;
+                    let point = min + (max - min).component_mul(&fraction);
+                    if self.contains(&point) && other.contains(&point) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
 }
 
 
@@ -146,4 +315,50 @@ impl Location {
 pub enum Shape {
     Ellipsoid,
     Cuboid
-}
\ No newline at end of file
+}
+
+// Box the center of a movable location has to stay within
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovableRange {
+    pub min: Vector3<f64>,
+    pub max: Vector3<f64>,
+}
+
+impl MovableRange {
+    pub fn new(a: Vector3<f64>, b: Vector3<f64>) -> Self {
+        Self { min: a.inf(&b), max: a.sup(&b) }
+    }
+
+    pub fn clamp(&self, position: &Vector3<f64>) -> Vector3<f64> {
+        position.sup(&self.min).inf(&self.max)
+    }
+}
+
+// Two locations whose volumes overlap
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutConflict {
+    pub first: Uuid,
+    pub second: Uuid,
+}
+
+#[test]
+fn location_volume_test() {
+    let mut bin = Location::new("bin".into(), 0.5, 0.0, 1.0, Rating::Medium, Rating::Medium);
+    bin.shape = Shape::Cuboid;
+    bin.displacement = Vector3::new(0.2, 0.1, 0.05);
+    let origin = Vector3::new(0.0, 0.0, 1.0);
+    assert_eq!(bin.nearest_point(&origin), Vector3::new(0.3, 0.0, 1.0));
+    assert!(((bin.farthest_point(&origin) - origin).norm() - (0.7_f64.powi(2) + 0.1_f64.powi(2) + 0.05_f64.powi(2)).sqrt()).abs() < 1e-9);
+
+    let mut ball = Location::new("ball".into(), 0.85, 0.0, 1.0, Rating::Medium, Rating::Medium);
+    ball.displacement = Vector3::new(0.1, 0.1, 0.1);
+    assert!(!ball.contains(&Vector3::new(0.92, 0.08, 1.0)));
+    assert!(!ball.overlaps(&bin));
+    ball.position.x = 0.75;
+    assert!(ball.overlaps(&bin));
+    // Bounding boxes touch at the corner, but the sphere does not reach it
+    ball.position = Vector3::new(0.8, 0.2, 1.15);
+    assert!(!ball.overlaps(&bin));
+}
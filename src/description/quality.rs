@@ -0,0 +1,348 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::petri::data::{Data, Query};
+use crate::petri::net::PetriNet;
+use crate::petri::place::Place;
+use crate::petri::token::TokenSet;
+use crate::petri::transition::{Signature, Timing, Transition};
+
+use super::job::Job;
+use super::primitive::Primitive;
+use super::target::Target;
+use super::task::Task;
+use super::units::USD;
+
+// What happens to a part found defective
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DefectHandling {
+    // Thrown away, losing the material that went into it
+    #[default]
+    Scrap,
+    // Repaired and sent back into the process
+    Rework { cost: USD },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityConfig {
+    // Probability that an inspection catches a defect, by the skill it demands (Low, Medium, High)
+    pub detection: [f64; 3],
+    // Inspect primitive UUID -> detection probability, in place of the one by skill
+    pub inspection_detection: HashMap<Uuid, f64>,
+    // Task UUID -> handling of the defects caught there
+    pub handling: HashMap<Uuid, DefectHandling>,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            detection: [0.95, 0.85, 0.7],
+            inspection_detection: HashMap::new(),
+            handling: HashMap::new(),
+        }
+    }
+}
+
+impl Job {
+    pub fn set_inspection_detection(&mut self, primitive: Uuid, detection: f64) {
+        self.quality.inspection_detection.insert(primitive, detection.clamp(0.0, 1.0));
+    }
+
+    pub fn set_defect_handling(&mut self, task: Uuid, handling: DefectHandling) {
+        self.quality.handling.insert(task, handling);
+    }
+
+    // Probability that a defective part going through the task is caught by its inspections
+    pub fn detection_probability(&self, task: &Task) -> f64 {
+        let missed: f64 = task
+            .primitives
+            .iter()
+            .filter_map(|id| match self.primitives.get(id) {
+                Some(Primitive::Inspect { id, skill, .. }) => Some(
+                    *self
+                        .quality
+                        .inspection_detection
+                        .get(id)
+                        .unwrap_or(&self.quality.detection[skill.index()]),
+                ),
+                _ => None,
+            })
+            .map(|detection| 1.0 - detection)
+            .product();
+        1.0 - missed
+    }
+
+    fn producer(&self, target: &Uuid) -> Option<&Task> {
+        self.tasks
+            .values()
+            .sorted_by_key(|task| task.order)
+            .find(|task| task.output_target_count(target) > 0)
+    }
+
+    fn consumer(&self, target: &Uuid) -> Option<&Task> {
+        self.tasks
+            .values()
+            .sorted_by_key(|task| task.order)
+            .find(|task| task.dependencies.iter().any(|(dependency, _)| dependency == target))
+    }
+
+    // Value of the material that went into one unit of the target
+    pub fn material_value(&self, target: &Uuid) -> f64 {
+        self.material_value_within(target, self.tasks.len())
+    }
+
+    fn material_value_within(&self, target: &Uuid, depth: usize) -> f64 {
+        match self.targets.get(target) {
+            Some(Target::Precursor { value, .. }) => *value,
+            Some(Target::Intermediate { .. }) | Some(Target::Product { .. }) if depth > 0 => {
+                match self.producer(target) {
+                    Some(task) => {
+                        task.dependencies
+                            .iter()
+                            .filter(|(dependency, _)| !matches!(self.targets.get(dependency), Some(Target::Reusable { .. })))
+                            .map(|(dependency, count)| *count as f64 * self.material_value_within(dependency, depth - 1))
+                            .sum::<f64>()
+                            / task.output_target_count(target) as f64
+                    }
+                    None => 0.0,
+                }
+            }
+            _ => 0.0,
+        }
+    }
+
+    // What it costs when a part is caught defective at the task
+    fn rejection_cost(&self, task: &Task) -> USD {
+        match self.quality.handling.get(&task.id).cloned().unwrap_or_default() {
+            DefectHandling::Scrap => task
+                .output
+                .iter()
+                .map(|(output, _)| self.material_value(output))
+                .sum(),
+            DefectHandling::Rework { cost } => cost,
+        }
+    }
+
+    // Expected value lost to a defect introduced in the task: followed downstream, it may be caught by an
+    // inspection and scrapped or reworked, or else reach the customer and cost the whole product.
+    // This prices the reject, rework and scrap branches of the net, weighted by their probabilities.
+    pub fn defect_loss(&self, task: &Uuid) -> USD {
+        let mut loss = 0.0;
+        let mut escaped = 1.0;
+        let mut visited: Vec<Uuid> = vec![];
+        let mut current = self.tasks.get(task);
+        while let Some(task) = current {
+            if visited.contains(&task.id) {
+                break;
+            }
+            visited.push(task.id);
+
+            let detection = self.detection_probability(task);
+            loss += escaped * detection * self.rejection_cost(task);
+            escaped *= 1.0 - detection;
+
+            let output = task
+                .output
+                .iter()
+                .map(|(output, _)| output)
+                .find(|output| matches!(self.targets.get(output), Some(Target::Intermediate { .. } | Target::Product { .. })));
+            current = match output.and_then(|output| self.targets.get(output)) {
+                Some(product @ Target::Product { .. }) => {
+                    loss += escaped * product.value();
+                    None
+                }
+                Some(intermediate) => self.consumer(&intermediate.id()),
+                None => None,
+            };
+        }
+        loss
+    }
+
+    // Add a failure branch for each output of every task that inspects its parts. The task also marks
+    // each part it makes as under inspection, which is settled at once: it passes and stays where it
+    // is, or it is rejected as defective and goes back into the process or into a scrap sink. Only
+    // parts under inspection can be rejected, so good parts already made are never drawn off. Branch
+    // weights are given that the part is defective, since how often it is depends on the agent.
+    pub fn add_defect_branches(&self, net: &mut PetriNet) {
+        for task in self.tasks.values().sorted_by_key(|task| task.order) {
+            let detection = self.detection_probability(task);
+            if detection <= 0.0 {
+                continue;
+            }
+            let task_transition_id = match net.query_transitions(&vec![Query::Data(Data::Task(task.id))]).first() {
+                Some(transition) => transition.id,
+                None => continue,
+            };
+            let handling = self.quality.handling.get(&task.id).cloned().unwrap_or_default();
+            for (output_id, count) in task.output.iter() {
+                let output = match self.targets.get(output_id) {
+                    Some(output @ (Target::Intermediate { .. } | Target::Product { .. })) => output,
+                    _ => continue,
+                };
+                let situated_id = match net
+                    .query_places(&vec![Query::Data(Data::TargetSituated(*output_id))])
+                    .first()
+                {
+                    Some(place) => place.id,
+                    None => continue,
+                };
+
+                let inspecting = Place::new(
+                    format!("Target: {} (inspecting)", output.name()),
+                    TokenSet::Finite,
+                    vec![Data::Reject(task.id, detection)],
+                );
+                let inspecting_id = inspecting.id;
+                net.places.insert(inspecting_id, inspecting);
+                net.initial_marking.insert(inspecting_id, 0);
+                if let Some(transition) = net.transitions.get_mut(&task_transition_id) {
+                    transition.output.insert(inspecting_id, Signature::Static(*count));
+                }
+
+                let defective = Place::new(
+                    format!("Target: {} (defective)", output.name()),
+                    TokenSet::Finite,
+                    vec![Data::Defect(*output_id)],
+                );
+                let defective_id = defective.id;
+                net.places.insert(defective_id, defective);
+                net.initial_marking.insert(defective_id, 0);
+
+                let mut pass = Transition::new(
+                    format!("Pass: {} ({})", output.name(), task.name),
+                    vec![(inspecting_id, Signature::Static(1))].into_iter().collect(),
+                    HashMap::new(),
+                    vec![Data::Simulation, Data::AgentAgnostic, Data::TargetSituated(*output_id)],
+                    0.0,
+                    vec![],
+                );
+                pass.set_timing(Timing::Immediate { weight: 1.0 - detection });
+                net.transitions.insert(pass.id, pass);
+
+                let mut reject = Transition::new(
+                    format!("Reject: {} ({})", output.name(), task.name),
+                    vec![
+                        (inspecting_id, Signature::Static(1)),
+                        (situated_id, Signature::Static(1)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                    vec![(defective_id, Signature::Static(1))].into_iter().collect(),
+                    vec![
+                        Data::Simulation,
+                        Data::AgentAgnostic,
+                        Data::TargetSituated(*output_id),
+                        Data::Reject(task.id, detection),
+                    ],
+                    0.0,
+                    vec![],
+                );
+                reject.set_timing(Timing::Immediate { weight: detection });
+                net.transitions.insert(reject.id, reject);
+
+                let resolution = match handling {
+                    DefectHandling::Rework { .. } => Transition::new(
+                        format!("Rework: {}", output.name()),
+                        vec![(defective_id, Signature::Static(1))].into_iter().collect(),
+                        vec![(situated_id, Signature::Static(1))].into_iter().collect(),
+                        vec![
+                            Data::Simulation,
+                            Data::AgentAgnostic,
+                            Data::TargetSituated(*output_id),
+                            Data::Rework(task.id),
+                        ],
+                        0.0,
+                        vec![],
+                    ),
+                    DefectHandling::Scrap => {
+                        let scrap = Place::new(
+                            format!("Target: {} (scrap)", output.name()),
+                            TokenSet::Sink,
+                            vec![Data::Defect(*output_id)],
+                        );
+                        let scrap_id = scrap.id;
+                        net.places.insert(scrap_id, scrap);
+                        net.initial_marking.insert(scrap_id, 0);
+                        Transition::new(
+                            format!("Scrap: {}", output.name()),
+                            vec![(defective_id, Signature::Static(1))].into_iter().collect(),
+                            vec![(scrap_id, Signature::Static(1))].into_iter().collect(),
+                            vec![
+                                Data::Simulation,
+                                Data::AgentAgnostic,
+                                Data::Scrap(*output_id, self.material_value(output_id)),
+                            ],
+                            0.0,
+                            vec![],
+                        )
+                    }
+                };
+                net.transitions.insert(resolution.id, resolution);
+            }
+        }
+    }
+}
+
+#[test]
+fn defect_loss_test() {
+    use super::rating::Rating;
+    use crate::petri::data::DataTag;
+
+    let weight = |transition: &Transition| match transition.timing() {
+        Timing::Immediate { weight } => weight,
+        _ => panic!("branches are immediate"),
+    };
+    let mut job = Job::new("Quality".into(), 0.1);
+    let part = job.create_precursor_target("Part".into(), 0.05, 1.0, Rating::High, vec![], 4.0);
+    let sub = job.create_intermediate_target("Sub".into(), 0.05, 1.0, Rating::High, vec![]);
+    let product = job.create_product_target("Product".into(), 0.05, 1.0, Rating::High, vec![], 100.0);
+    let assemble = job.create_task("Assemble".into(), 1);
+    job.add_task_dependency(assemble, part, 2);
+    job.add_task_output(assemble, sub, 1);
+    let finish = job.create_task("Finish".into(), 2);
+    job.add_task_dependency(finish, sub, 1);
+    job.add_task_output(finish, product, 1);
+    assert_eq!(job.material_value(&product), 8.0);
+
+    // With no inspection, every defect reaches the customer
+    assert_eq!(job.defect_loss(&assemble), 100.0);
+
+    // Catching 90% of them at the end scraps the material instead
+    let inspect = Primitive::new_inspect(product, Rating::Medium);
+    let inspect_id = inspect.id();
+    job.add_task_primitive(finish, inspect);
+    job.set_inspection_detection(inspect_id, 0.9);
+    assert!((job.defect_loss(&assemble) - (0.9 * 8.0 + 0.1 * 100.0)).abs() < 1e-9);
+
+    job.set_defect_handling(finish, DefectHandling::Rework { cost: 5.0 });
+    assert!((job.defect_loss(&assemble) - (0.9 * 5.0 + 0.1 * 100.0)).abs() < 1e-9);
+
+    // The net branches at the inspection: reject or pass, then rework
+    let net = job.create_basic_net();
+    let task = net.query_transitions(&vec![Query::Data(Data::Task(finish))])[0];
+    let reject = net.query_transitions(&vec![Query::Tag(DataTag::Reject)]);
+    assert_eq!(reject.len(), 1);
+    assert!((weight(reject[0]) - 0.9).abs() < 1e-9);
+    assert_eq!(net.query_transitions(&vec![Query::Data(Data::Rework(finish))]).len(), 1);
+    assert_eq!(net.query_transitions(&vec![Query::Tag(DataTag::Scrap)]).len(), 0);
+    // Only the parts the task marks as under inspection can be rejected, so finished ones stay put
+    let pass = net.transitions.values().find(|t| t.name.starts_with("Pass")).unwrap();
+    let inspecting = pass.input.keys().collect::<Vec<_>>();
+    assert_eq!(inspecting.len(), 1);
+    assert!(pass.output.is_empty());
+    assert!(reject[0].input.contains_key(inspecting[0]));
+    assert!(reject[0].input.keys().all(|place| task.output.contains_key(place)));
+    assert!((weight(pass) - 0.1).abs() < 1e-9);
+
+    // Scrapped parts go to a sink, carrying the material lost
+    job.set_defect_handling(finish, DefectHandling::Scrap);
+    let net = job.create_basic_net();
+    let scrap = net.query_transitions(&vec![Query::Tag(DataTag::Scrap)]);
+    assert_eq!(scrap.len(), 1);
+    assert_eq!(scrap[0].meta_data.last(), Some(&Data::Scrap(product, 8.0)));
+    assert_eq!(net.query_transitions(&vec![Query::Tag(DataTag::Rework)]).len(), 0);
+}
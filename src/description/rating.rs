@@ -9,6 +9,17 @@ pub enum Rating {
     High
 }
 
+impl Rating {
+    // Position in tables indexed Low, Medium, High
+    pub fn index(&self) -> usize {
+        match self {
+            Rating::Low => 0,
+            Rating::Medium => 1,
+            Rating::High => 2,
+        }
+    }
+}
+
 #[test]
 fn ratings_test() {
     assert!( Rating::Low < Rating::Medium );
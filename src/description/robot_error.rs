@@ -0,0 +1,393 @@
+use serde::{Deserialize, Serialize};
+
+use super::rating::Rating;
+
+// Rating value used by the exponential model: better ratings are smaller
+fn rating_value(rating: &Rating) -> f64 {
+    match rating {
+        Rating::Low => 3.0,
+        Rating::Medium => 2.0,
+        Rating::High => 1.0,
+    }
+}
+
+// How likely a robot is to fail a pick, from the structure and variability of the location
+// and the robot's sensing. Tables are indexed Low, Medium, High.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RobotErrorModel {
+    // A rate for each [sensing][structure][variability]
+    Independent { rates: [[[f64; 3]; 3]; 3] },
+    // A base rate for each [structure][variability], scaled by a factor per [sensing]
+    Multiplicative {
+        base: [[f64; 3]; 3],
+        sensing_factors: [f64; 3],
+    },
+    // e^(v + a) + e^(b - s) + e^(c - n), with the ratings valued Low 3, Medium 2, High 1
+    Exponential { offsets: [f64; 3] },
+}
+
+impl Default for RobotErrorModel {
+    fn default() -> Self {
+        Self::independent()
+    }
+}
+
+// How likely a robot is to get an inspection or selection wrong, from the skill it demands and the
+// robot's sensing
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JudgementRates {
+    // A rate for each [skill][sensing]
+    pub rates: [[f64; 3]; 3],
+}
+
+impl Default for JudgementRates {
+    fn default() -> Self {
+        Self {
+            rates: [
+                // Low skill
+                [0.005, 0.002, 0.0005],
+                // Medium skill
+                [0.015, 0.006, 0.00075],
+                // High skill: only a well-sensing robot judges reliably
+                [0.1, 0.02, 0.001],
+            ],
+        }
+    }
+}
+
+impl JudgementRates {
+    pub fn rate(&self, skill: &Rating, sensing: &Rating) -> f64 {
+        self.rates[skill.index()][sensing.index()].clamp(0.0, 1.0)
+    }
+
+    // Observed failure rates in place of the table's. Combinations that were never observed keep their values.
+    pub fn calibrate(&self, observations: &[JudgementObservation]) -> Result<Self, String> {
+        let mut counts = [[(0.0, 0.0); 3]; 3];
+        for o in observations.iter() {
+            let count = &mut counts[o.skill.index()][o.sensing.index()];
+            count.0 += o.failures;
+            count.1 += o.successes + o.failures;
+        }
+        if counts.iter().flatten().all(|(_, trials)| *trials <= 0.0) {
+            return Err("No observations with any trials".into());
+        }
+        let mut rates = self.rates;
+        for (k, n) in itertools::iproduct!(0..3, 0..3) {
+            let (failures, trials) = counts[k][n];
+            if trials > 0.0 {
+                rates[k][n] = failures / trials;
+            }
+        }
+        Ok(Self { rates })
+    }
+}
+
+// Observed inspections or selections at one skill/sensing combination
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JudgementObservation {
+    pub skill: Rating,
+    pub sensing: Rating,
+    pub successes: f64,
+    pub failures: f64,
+}
+
+// Observed picks at one structure/variability/sensing combination
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorObservation {
+    pub structure: Rating,
+    pub variability: Rating,
+    pub sensing: Rating,
+    pub successes: f64,
+    pub failures: f64,
+}
+
+impl ErrorObservation {
+    pub fn trials(&self) -> f64 {
+        self.successes + self.failures
+    }
+
+    pub fn rate(&self) -> f64 {
+        if self.trials() > 0.0 {
+            self.failures / self.trials()
+        } else {
+            0.0
+        }
+    }
+}
+
+fn parse_rating(text: &str) -> Option<Rating> {
+    match text.to_lowercase().as_str() {
+        "low" | "l" => Some(Rating::Low),
+        "medium" | "m" => Some(Rating::Medium),
+        "high" | "h" => Some(Rating::High),
+        _ => None,
+    }
+}
+
+// Ratings and success/failure counts from each row of a CSV with the given rating columns.
+// A header line is skipped.
+fn csv_rows(text: &str, columns_named: &str) -> Result<Vec<(Vec<Rating>, f64, f64)>, String> {
+    let ratings_count = columns_named.split(',').count() - 2;
+    let mut rows = vec![];
+    for (line_idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let columns: Vec<&str> = line.split(',').map(|c| c.trim()).collect();
+        if columns.len() != ratings_count + 2 {
+            return Err(format!(
+                "Line {}: expected {} columns ({}), found {}",
+                line_idx + 1,
+                ratings_count + 2,
+                columns_named,
+                columns.len()
+            ));
+        }
+        let ratings: Option<Vec<Rating>> = columns[..ratings_count].iter().map(|c| parse_rating(c)).collect();
+        let ratings = match ratings {
+            Some(ratings) => ratings,
+            None if line_idx == 0 => continue,
+            None => return Err(format!("Line {}: ratings must be low, medium or high", line_idx + 1)),
+        };
+        let successes: f64 = columns[ratings_count]
+            .parse()
+            .map_err(|_| format!("Line {}: invalid successes '{}'", line_idx + 1, columns[ratings_count]))?;
+        let failures: f64 = columns[ratings_count + 1]
+            .parse()
+            .map_err(|_| format!("Line {}: invalid failures '{}'", line_idx + 1, columns[ratings_count + 1]))?;
+        if successes < 0.0 || failures < 0.0 {
+            return Err(format!("Line {}: counts must not be negative", line_idx + 1));
+        }
+        rows.push((ratings, successes, failures));
+    }
+    Ok(rows)
+}
+
+// Rows of structure,variability,sensing,successes,failures. A header line is skipped.
+pub fn observations_from_csv_str(text: &str) -> Result<Vec<ErrorObservation>, String> {
+    Ok(csv_rows(text, "structure,variability,sensing,successes,failures")?
+        .into_iter()
+        .map(|(ratings, successes, failures)| ErrorObservation {
+            structure: ratings[0].clone(),
+            variability: ratings[1].clone(),
+            sensing: ratings[2].clone(),
+            successes,
+            failures,
+        })
+        .collect())
+}
+
+// Rows of skill,sensing,successes,failures. A header line is skipped.
+pub fn judgement_observations_from_csv_str(text: &str) -> Result<Vec<JudgementObservation>, String> {
+    Ok(csv_rows(text, "skill,sensing,successes,failures")?
+        .into_iter()
+        .map(|(ratings, successes, failures)| JudgementObservation {
+            skill: ratings[0].clone(),
+            sensing: ratings[1].clone(),
+            successes,
+            failures,
+        })
+        .collect())
+}
+
+impl RobotErrorModel {
+    pub fn independent() -> Self {
+        Self::Independent {
+            rates: [
+                // Low sensing: not very resilient to the environment (error prone)
+                [[0.3, 0.7, 0.95], [0.15, 0.4, 0.8], [0.01, 0.20, 0.5]],
+                // Medium sensing: moderately resilient (somewhat error prone)
+                [[0.015, 0.06, 0.1], [0.01, 0.02, 0.06], [0.004, 0.015, 0.04]],
+                // High sensing: most resilient (least prone to error)
+                [[0.004, 0.0075, 0.01], [0.0025, 0.005, 0.0075], [0.001, 0.003, 0.006]],
+            ],
+        }
+    }
+
+    pub fn multiplicative() -> Self {
+        Self::Multiplicative {
+            base: [[0.4, 0.75, 1.0], [0.25, 0.5, 0.75], [0.1, 0.3, 0.6]],
+            sensing_factors: [1.0, 0.1, 0.01],
+        }
+    }
+
+    pub fn exponential() -> Self {
+        Self::Exponential {
+            offsets: [-4.0, 0.0, 0.0],
+        }
+    }
+
+    pub fn rate(&self, structure: &Rating, variability: &Rating, sensing: &Rating) -> f64 {
+        let (s, v, n) = (structure.index(), variability.index(), sensing.index());
+        let rate = match self {
+            Self::Independent { rates } => rates[n][s][v],
+            Self::Multiplicative {
+                base,
+                sensing_factors,
+            } => base[s][v] * sensing_factors[n],
+            Self::Exponential { offsets } => {
+                (rating_value(variability) + offsets[0]).exp()
+                    + (offsets[1] - rating_value(structure)).exp()
+                    + (offsets[2] - rating_value(sensing)).exp()
+            }
+        };
+        rate.clamp(0.0, 1.0)
+    }
+
+    // Squared error against the observed failure rates, weighted by the number of trials
+    pub fn fit_error(&self, observations: &[ErrorObservation]) -> f64 {
+        observations
+            .iter()
+            .map(|o| o.trials() * (self.rate(&o.structure, &o.variability, &o.sensing) - o.rate()).powi(2))
+            .sum()
+    }
+
+    // Fit this kind of model to the observations. Combinations that were never observed keep their current values.
+    pub fn calibrate(&self, observations: &[ErrorObservation]) -> Result<Self, String> {
+        let observations: Vec<ErrorObservation> =
+            observations.iter().filter(|o| o.trials() > 0.0).cloned().collect();
+        if observations.is_empty() {
+            return Err("No observations with any trials".into());
+        }
+        let index = |o: &ErrorObservation| {
+            (o.structure.index(), o.variability.index(), o.sensing.index())
+        };
+
+        match self {
+            Self::Independent { rates } => {
+                let mut rates = *rates;
+                let mut counts = [[[(0.0, 0.0); 3]; 3]; 3];
+                for o in observations.iter() {
+                    let (s, v, n) = index(o);
+                    counts[n][s][v].0 += o.failures;
+                    counts[n][s][v].1 += o.trials();
+                }
+                for (n, s, v) in itertools::iproduct!(0..3, 0..3, 0..3) {
+                    let (failures, trials) = counts[n][s][v];
+                    if trials > 0.0 {
+                        rates[n][s][v] = failures / trials;
+                    }
+                }
+                Ok(Self::Independent { rates })
+            }
+            Self::Multiplicative {
+                base,
+                sensing_factors,
+            } => {
+                // Alternate between the closed-form fits of the factors and of the base rates
+                let (mut base, mut sensing_factors) = (*base, *sensing_factors);
+                for _ in 0..100 {
+                    for (n, factor) in sensing_factors.iter_mut().enumerate() {
+                        let (failures, expected) = observations
+                            .iter()
+                            .filter(|o| index(o).2 == n)
+                            .fold((0.0, 0.0), |(f, e), o| {
+                                let (s, v, _) = index(o);
+                                (f + o.failures, e + o.trials() * base[s][v])
+                            });
+                        if expected > 0.0 {
+                            *factor = failures / expected;
+                        }
+                    }
+                    for (s, v) in itertools::iproduct!(0..3, 0..3) {
+                        let (failures, expected) = observations
+                            .iter()
+                            .filter(|o| index(o).0 == s && index(o).1 == v)
+                            .fold((0.0, 0.0), |(f, e), o| {
+                                (f + o.failures, e + o.trials() * sensing_factors[index(o).2])
+                            });
+                        if expected > 0.0 {
+                            base[s][v] = failures / expected;
+                        }
+                    }
+                }
+                // Scale so that low sensing has a factor of one, as in the default table
+                let scale = sensing_factors[0];
+                if scale > 0.0 {
+                    for (s, v) in itertools::iproduct!(0..3, 0..3) {
+                        base[s][v] *= scale;
+                    }
+                    for factor in sensing_factors.iter_mut() {
+                        *factor /= scale;
+                    }
+                }
+                Ok(Self::Multiplicative {
+                    base,
+                    sensing_factors,
+                })
+            }
+            Self::Exponential { offsets } => {
+                // Coordinate descent on the offsets, halving the step once no move helps
+                let mut best = Self::Exponential { offsets: *offsets };
+                let mut best_error = best.fit_error(&observations);
+                let mut step = 1.0;
+                while step > 1e-6 {
+                    let mut improved = false;
+                    for (axis, direction) in itertools::iproduct!(0..3, [1.0, -1.0]) {
+                        if let Self::Exponential { offsets } = &best {
+                            let mut offsets = *offsets;
+                            offsets[axis] += direction * step;
+                            let candidate = Self::Exponential { offsets };
+                            let error = candidate.fit_error(&observations);
+                            if error < best_error {
+                                best = candidate;
+                                best_error = error;
+                                improved = true;
+                            }
+                        }
+                    }
+                    if !improved {
+                        step /= 2.0;
+                    }
+                }
+                Ok(best)
+            }
+        }
+    }
+}
+
+#[test]
+fn robot_error_model_test() {
+    // The table reproduces the original lookups
+    let table = RobotErrorModel::independent();
+    assert_eq!(table.rate(&Rating::High, &Rating::Medium, &Rating::Low), 0.20);
+    assert_eq!(table.rate(&Rating::Low, &Rating::Low, &Rating::Medium), 0.015);
+    assert!((RobotErrorModel::multiplicative().rate(&Rating::Medium, &Rating::High, &Rating::Medium) - 0.075).abs() < 1e-12);
+
+    let observations = observations_from_csv_str(
+        "structure,variability,sensing,successes,failures\n\
+         high,high,low,80,20\n\
+         high,high,high,99,1\n\
+         low,low,high,90,10\n",
+    )
+    .unwrap();
+    assert_eq!(observations.len(), 3);
+    assert!(observations_from_csv_str("high,high,low,80").is_err());
+
+    let fitted = table.calibrate(&observations).unwrap();
+    assert!((fitted.rate(&Rating::High, &Rating::High, &Rating::Low) - 0.2).abs() < 1e-9);
+    // Unobserved combinations are left alone
+    assert_eq!(fitted.rate(&Rating::Medium, &Rating::Medium, &Rating::Medium), 0.02);
+
+    for model in [RobotErrorModel::multiplicative(), RobotErrorModel::exponential()] {
+        let fitted = model.calibrate(&observations).unwrap();
+        assert!(fitted.fit_error(&observations) < model.fit_error(&observations));
+    }
+
+    // Inspection and selection have a table of their own
+    let judgement = JudgementRates::default();
+    assert_eq!(judgement.rate(&Rating::High, &Rating::Medium), 0.02);
+    assert_eq!(judgement.rate(&Rating::Low, &Rating::High), 0.0005);
+    let observations = judgement_observations_from_csv_str("skill,sensing,successes,failures
+high,medium,95,5
+").unwrap();
+    let fitted = judgement.calibrate(&observations).unwrap();
+    assert!((fitted.rate(&Rating::High, &Rating::Medium) - 0.05).abs() < 1e-9);
+    assert_eq!(fitted.rate(&Rating::Low, &Rating::High), 0.0005);
+    assert!(judgement.calibrate(&[]).is_err());
+}
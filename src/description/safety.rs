@@ -0,0 +1,416 @@
+use enum_tag::EnumTag;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::petri::data::{Data, DataTag, Query};
+use crate::petri::net::PetriNet;
+use crate::petri::place::Place;
+use crate::petri::token::TokenSet;
+use crate::petri::transition::{Signature, Timing, Transition};
+
+use super::agent::{Agent, RobotInfo};
+use super::job::Job;
+
+// Body region a collaborative robot may come into contact with, with the
+// ISO/TS 15066 transient contact limits
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BodyRegion {
+    Hand,
+    LowerArm,
+    UpperArm,
+    Chest,
+}
+
+impl BodyRegion {
+    // Twice the quasi-static force limit (N)
+    pub fn max_transient_force(&self) -> f64 {
+        match self {
+            BodyRegion::Hand => 280.0,
+            BodyRegion::LowerArm => 320.0,
+            BodyRegion::UpperArm => 300.0,
+            BodyRegion::Chest => 280.0,
+        }
+    }
+
+    // Effective spring constant (N/m)
+    pub fn spring_constant(&self) -> f64 {
+        match self {
+            BodyRegion::Hand => 75000.0,
+            BodyRegion::LowerArm => 40000.0,
+            BodyRegion::UpperArm => 30000.0,
+            BodyRegion::Chest => 25000.0,
+        }
+    }
+
+    // Effective mass of the body region (kg)
+    pub fn effective_mass(&self) -> f64 {
+        match self {
+            BodyRegion::Hand => 0.6,
+            BodyRegion::LowerArm => 2.0,
+            BodyRegion::UpperArm => 3.0,
+            BodyRegion::Chest => 40.0,
+        }
+    }
+}
+
+// How a robot is kept from hurting the people around it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SafetyMode {
+    // Guarded: it never works in a zone while a person is in it
+    #[default]
+    Fenced,
+    // Speed and separation monitoring: it slows down while a person is in the zone
+    SpeedSeparation,
+    // Power and force limiting: it always moves slowly enough that contact is harmless
+    PowerForceLimited {
+        body_region: BodyRegion,
+        moving_mass: f64, // kg
+    },
+}
+
+// Fastest relative speed (m/s) at which a transient contact stays within the body region's limits
+pub fn pfl_speed_limit(body_region: &BodyRegion, moving_mass: f64, payload: f64) -> f64 {
+    // Robot effective mass per ISO/TS 15066 A.3: half the moving mass plus the payload
+    let robot_mass = moving_mass / 2.0 + payload;
+    let reduced_mass = 1.0 / (1.0 / body_region.effective_mass() + 1.0 / robot_mass);
+    body_region.max_transient_force() / (reduced_mass * body_region.spring_constant()).sqrt()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafetyConfig {
+    // Hand POIs closer than this (m) are treated as one zone
+    pub separation_distance: f64,
+    pub human_speed: f64,         // m/s
+    pub reaction_time: f64,       // s, for the sensing system and controller
+    pub stopping_time: f64,       // s, for the robot to come to rest
+    pub intrusion_distance: f64,  // m, how far a body part can reach past the detection boundary
+    pub position_uncertainty: f64 // m, sensor and robot position uncertainty
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            separation_distance: 1.0,
+            human_speed: 1.6,
+            reaction_time: 0.1,
+            stopping_time: 0.3,
+            intrusion_distance: 0.2,
+            position_uncertainty: 0.05,
+        }
+    }
+}
+
+impl SafetyConfig {
+    // Robot speed (m/s) at which the protective separation distance fits within the zone's separation:
+    // S = v_h (T_r + T_s) + v_r T_r + v_r T_s / 2 + C + Z
+    pub fn ssm_speed_limit(&self) -> f64 {
+        let available = self.separation_distance
+            - self.human_speed * (self.reaction_time + self.stopping_time)
+            - self.intrusion_distance
+            - self.position_uncertainty;
+        (available / (self.reaction_time + self.stopping_time / 2.0)).max(0.0)
+    }
+}
+
+// Hand POIs close enough together that agents working at them could collide
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedZone {
+    pub id: Uuid,
+    pub pois: Vec<Uuid>,
+    // Humans that can reach into the zone
+    pub humans: Vec<Uuid>,
+}
+
+// (hand POI, agent) pairs a transition works at
+fn transition_hands(transition: &Transition) -> Vec<(Uuid, Uuid)> {
+    transition
+        .meta_data
+        .iter()
+        .filter(|d| matches!(d.tag(), DataTag::Hand | DataTag::FromHandPOI | DataTag::ToHandPOI))
+        .filter_map(|d| Some((d.id()?, d.secondary()?)))
+        .collect()
+}
+
+impl Job {
+    // Group hand POIs within the separation distance of each other into zones
+    pub fn shared_zones(&self) -> Vec<SharedZone> {
+        let hand_pois: Vec<Uuid> = self
+            .points_of_interest
+            .iter()
+            .filter(|(_, poi)| poi.is_hand())
+            .map(|(id, _)| *id)
+            .sorted()
+            .collect();
+
+        let mut zone_of: HashMap<Uuid, usize> = hand_pois.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        for (poi1, poi2) in hand_pois.iter().tuple_combinations() {
+            let position1 = self.points_of_interest.get(poi1).unwrap().position();
+            let position2 = self.points_of_interest.get(poi2).unwrap().position();
+            if (position1 - position2).norm() <= self.safety.separation_distance {
+                // Merge the later zone into the earlier one
+                let (keep, merge) = (zone_of[poi1].min(zone_of[poi2]), zone_of[poi1].max(zone_of[poi2]));
+                for zone in zone_of.values_mut() {
+                    if *zone == merge {
+                        *zone = keep;
+                    }
+                }
+            }
+        }
+
+        hand_pois
+            .iter()
+            .into_group_map_by(|id| zone_of[*id])
+            .into_iter()
+            .sorted_by_key(|(zone, _)| *zone)
+            .map(|(_, pois)| {
+                let pois: Vec<Uuid> = pois.into_iter().cloned().collect();
+                let humans = self
+                    .agents
+                    .iter()
+                    .filter(|(_, agent)| matches!(agent, Agent::Human(_)))
+                    .filter(|(_, agent)| {
+                        let agent = self.age_adjusted(agent);
+                        self.points_of_interest.values().filter(|poi| poi.is_standing()).any(|standing| {
+                            pois.iter()
+                                .any(|hand| standing.reachability(self.points_of_interest.get(hand).unwrap(), &agent))
+                        })
+                    })
+                    .map(|(id, _)| *id)
+                    .sorted()
+                    .collect();
+                SharedZone {
+                    // Named after its first POI, so that it is stable across calls
+                    id: pois[0],
+                    pois,
+                    humans,
+                }
+            })
+            .collect()
+    }
+
+    // Whether the robot has to keep out of a zone while a person is in it
+    fn requires_exclusion(&self, robot: &RobotInfo) -> bool {
+        match robot.safety {
+            SafetyMode::Fenced => true,
+            // Without room to slow down, the robot has to stop
+            SafetyMode::SpeedSeparation => self.safety.ssm_speed_limit() <= 0.0,
+            SafetyMode::PowerForceLimited { .. } => false,
+        }
+    }
+
+    // The robot with its speeds capped by its safety mode for this transition
+    pub fn safety_adjusted(&self, robot: &RobotInfo, transition: &Transition, zones: &[SharedZone]) -> RobotInfo {
+        let mut robot = robot.clone();
+        let limit = match &robot.safety {
+            SafetyMode::Fenced => None,
+            SafetyMode::SpeedSeparation => {
+                let hands: Vec<Uuid> = transition_hands(transition)
+                    .into_iter()
+                    .filter(|(_, agent)| *agent == robot.id)
+                    .map(|(poi, _)| poi)
+                    .collect();
+                let shared = zones
+                    .iter()
+                    .any(|zone| !zone.humans.is_empty() && zone.pois.iter().any(|poi| hands.contains(poi)));
+                if shared && self.safety.ssm_speed_limit() > 0.0 {
+                    Some(self.safety.ssm_speed_limit())
+                } else {
+                    None
+                }
+            }
+            SafetyMode::PowerForceLimited {
+                body_region,
+                moving_mass,
+            } => Some(pfl_speed_limit(body_region, *moving_mass, robot.payload)),
+        };
+        if let Some(limit) = limit {
+            robot.speed = robot.speed.min(limit);
+            robot.mobile_speed = robot.mobile_speed.min(limit);
+        }
+        robot
+    }
+
+    // Add a lock place for each zone where a guarded robot and a person could both work. Each action
+    // there is split in two: starting takes its inputs and the lock, and finishing (the original
+    // transition, with its time and cost) returns the lock with its outputs. The lock is held for as
+    // long as the action takes, so that they never work in the zone at the same time.
+    pub fn add_shared_zone_locks(&self, net: &mut PetriNet, zones: &[SharedZone]) {
+        // Transition UUID -> locks it has to hold, as an action may work at several zones
+        let mut locks: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for zone in zones.iter().filter(|zone| !zone.humans.is_empty()) {
+            let transitions: Vec<Uuid> = net
+                .transitions
+                .values()
+                .filter(|t| {
+                    transition_hands(t).iter().any(|(poi, agent)| {
+                        zone.pois.contains(poi)
+                            && t.has_data(&vec![Query::Data(Data::Action(*agent))])
+                            && match self.agents.get(agent) {
+                                Some(Agent::Human(_)) => zone.humans.contains(agent),
+                                Some(Agent::Robot(robot)) => self.requires_exclusion(robot),
+                                None => false,
+                            }
+                    })
+                })
+                .map(|t| t.id)
+                .sorted()
+                .collect();
+
+            let has_robot = transitions.iter().any(|id| {
+                transition_hands(net.transitions.get(id).unwrap())
+                    .iter()
+                    .any(|(_, agent)| matches!(self.agents.get(agent), Some(Agent::Robot(_))))
+            });
+            if !has_robot {
+                continue;
+            }
+
+            let names = zone
+                .pois
+                .iter()
+                .map(|poi| self.points_of_interest.get(poi).unwrap().name())
+                .join(",");
+            let lock = Place::new(
+                format!("Shared Zone: {} (lock)", names),
+                TokenSet::Finite,
+                vec![Data::SharedZone(zone.id)],
+            );
+            net.initial_marking.insert(lock.id, 1);
+            for id in transitions {
+                locks.entry(id).or_default().push(lock.id);
+                net.transitions.get_mut(&id).unwrap().add_data(Data::SharedZone(zone.id));
+            }
+            net.places.insert(lock.id, lock);
+        }
+
+        for (id, locks) in locks.into_iter().sorted_by_key(|(id, _)| *id) {
+            let action = net.transitions.get_mut(&id).unwrap();
+            let zone_data: Vec<Data> = action
+                .meta_data
+                .iter()
+                .filter(|d| d.tag() == DataTag::SharedZone)
+                .cloned()
+                .collect();
+            let busy = Place::new(format!("{} (in progress)", action.name), TokenSet::Finite, zone_data.clone());
+
+            let mut inputs = std::mem::take(&mut action.input);
+            action.input.insert(busy.id, Signature::Static(1));
+            for lock in locks {
+                inputs.insert(lock, Signature::Static(1));
+                action.output.insert(lock, Signature::Static(1));
+            }
+            let mut start = Transition::new(
+                format!("{} (start)", action.name),
+                inputs,
+                vec![(busy.id, Signature::Static(1))].into_iter().collect(),
+                zone_data,
+                0.0,
+                vec![],
+            );
+            start.set_timing(Timing::Immediate { weight: 1.0 });
+
+            net.initial_marking.insert(busy.id, 0);
+            net.places.insert(busy.id, busy);
+            net.transitions.insert(start.id, start);
+        }
+    }
+}
+
+#[test]
+fn safety_speed_test() {
+    // A light cobot may hit a hand faster than a heavy one
+    let light = pfl_speed_limit(&BodyRegion::Hand, 10.0, 1.0);
+    let heavy = pfl_speed_limit(&BodyRegion::Hand, 40.0, 5.0);
+    assert!(light > heavy);
+    assert!(pfl_speed_limit(&BodyRegion::Chest, 10.0, 1.0) < light);
+
+    let mut config = SafetyConfig::default();
+    let expected = (1.0 - 1.6 * 0.4 - 0.2 - 0.05) / 0.25;
+    assert!((config.ssm_speed_limit() - expected).abs() < 1e-9);
+    // A person walking in covers 0.64m before the robot stops, so half a meter leaves no room at all
+    config.separation_distance = 0.5;
+    assert_eq!(config.ssm_speed_limit(), 0.0);
+}
+
+#[test]
+fn shared_zone_lock_test() {
+    use crate::analysis::fixtures::{add_arm, add_assembly, add_worker, bench_job};
+
+    // A worker and a fenced arm that can both do the one task at the bench
+    let (mut job, bench) = bench_job("Zone");
+    let human = add_worker(&mut job, "Worker", 20.0);
+    let robot = add_arm(&mut job);
+    add_assembly(&mut job, bench);
+    job.create_petri_nets();
+    let zones = job.shared_zones();
+    assert_eq!(zones.len(), 1);
+    assert_eq!(zones[0].humans, vec![human]);
+
+    let net = job.cost_net.as_ref().unwrap();
+    let action = |agent: Uuid| net.query_transitions(&vec![Query::Data(Data::Action(agent))])[0];
+    let start = |action: &Transition| {
+        let busy = action.input.keys().next().unwrap();
+        net.transitions.values().find(|t| t.output.contains_key(busy)).unwrap()
+    };
+    let (human_finish, robot_finish) = (action(human), action(robot));
+    let (human_start, robot_start) = (start(human_finish), start(robot_finish));
+
+    // Everything but the lock and the actions in progress is plentiful
+    let mut marking: HashMap<Uuid, usize> = net
+        .places
+        .values()
+        .map(|place| match place.meta_data.iter().any(|d| d.tag() == DataTag::SharedZone) {
+            true => (place.id, net.initial_marking[&place.id]),
+            false => (place.id, 10),
+        })
+        .collect();
+    let enabled = |t: &Transition, marking: &HashMap<Uuid, usize>| {
+        t.input.iter().all(|(place, signature)| match signature {
+            Signature::Static(count) | Signature::Range(count, _) => marking[place] >= *count,
+        })
+    };
+    let fire = |t: &Transition, marking: &mut HashMap<Uuid, usize>| {
+        for (place, signature) in t.input.iter() {
+            let (Signature::Static(count) | Signature::Range(count, _)) = signature;
+            *marking.get_mut(place).unwrap() -= count;
+        }
+        for (place, signature) in t.output.iter() {
+            let (Signature::Static(count) | Signature::Range(count, _)) = signature;
+            *marking.get_mut(place).unwrap() += count;
+        }
+    };
+    assert!(enabled(human_start, &marking) && enabled(robot_start, &marking));
+    assert!(!enabled(human_finish, &marking));
+
+    // While the worker is at the bench, the arm can't start, and it can once the worker is done
+    fire(human_start, &mut marking);
+    assert!(enabled(human_finish, &marking));
+    assert!(!enabled(robot_start, &marking));
+    fire(human_finish, &mut marking);
+    assert!(enabled(robot_start, &marking));
+
+    // A power and force limited arm may share the bench, so nothing is locked
+    job.set_robot_safety(
+        robot,
+        SafetyMode::PowerForceLimited {
+            body_region: BodyRegion::Chest,
+            moving_mass: 10.0,
+        },
+    );
+    job.create_petri_nets();
+    let net = job.cost_net.as_ref().unwrap();
+    assert!(net.query_places(&vec![Query::Tag(DataTag::SharedZone)]).is_empty());
+    let arm_action = net.query_transitions(&vec![Query::Data(Data::Action(robot))])[0];
+    let limited = match &job.agents[&robot] {
+        Agent::Robot(robot) => job.safety_adjusted(robot, arm_action, &zones),
+        Agent::Human(_) => unreachable!(),
+    };
+    assert_eq!(limited.speed, pfl_speed_limit(&BodyRegion::Chest, 10.0, 3.0));
+    assert!(limited.speed < 1.0);
+}
@@ -1,3 +1,4 @@
+use crate::constants::GRAVITY;
 use crate::description::agent::Agent;
 use enum_tag::EnumTag;
 use serde::{Deserialize, Serialize};
@@ -162,12 +163,13 @@ impl Target {
         }
     }
 
+    // In Newtons, from the mass in kg
     pub fn weight(&self) -> f64 {
         match self {
-            Target::Precursor { weight, .. } => *weight * 9.81,
-            Target::Intermediate { weight, .. } => *weight * 9.81,
-            Target::Product { weight, .. } => *weight * 9.81,
-            Target::Reusable { weight, .. } => *weight * 9.81,
+            Target::Precursor { weight, .. } => *weight * GRAVITY,
+            Target::Intermediate { weight, .. } => *weight * GRAVITY,
+            Target::Product { weight, .. } => *weight * GRAVITY,
+            Target::Reusable { weight, .. } => *weight * GRAVITY,
         }
     }
 
@@ -0,0 +1,212 @@
+use enum_tag::EnumTag;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::constants::GRAVITY;
+use crate::petri::data::{Data, DataTag, Query};
+use crate::petri::net::PetriNet;
+use crate::petri::place::Place;
+use crate::petri::token::TokenSet;
+use crate::petri::transition::{Signature, Transition};
+
+use super::agent::{Agent, RobotInfo};
+use super::job::Job;
+use super::rating::Rating;
+use super::target::Target;
+use super::units::{Time, USD};
+
+// Closing time of the stock gripper assumed when a robot has no tools
+pub const DEFAULT_ACTUATION_TIME: Time = 0.5;
+
+// A gripper or other end-of-arm tool
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tool {
+    pub id: Uuid,
+    pub name: String,
+    pub mass: f64,  // kg, counts against the robot's payload
+    pub price: USD,
+    pub min_size: f64, // meters
+    pub max_size: f64, // meters
+    pub symmetries: Vec<Rating>,
+    pub actuation_time: Time, // to close on (or release) a part
+    pub change_time: Time,    // to mount it in place of another tool
+}
+
+impl Tool {
+    // A stock two-finger gripper. Other tools update its fields.
+    pub fn new(name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            mass: 0.5,
+            price: 1000.0,
+            min_size: 0.01,
+            max_size: 0.08,
+            symmetries: vec![Rating::Low, Rating::Medium, Rating::High],
+            actuation_time: 0.3,
+            change_time: 5.0,
+        }
+    }
+
+    pub fn supports(&self, target: &Target) -> bool {
+        target.size() >= self.min_size
+            && target.size() <= self.max_size
+            && self.symmetries.contains(&target.symmetry())
+    }
+
+    // Whether the robot can carry the target with this tool mounted
+    pub fn can_handle(&self, robot: &RobotInfo, target: &Target) -> bool {
+        self.supports(target) && target.weight() + self.mass * GRAVITY <= robot.payload * GRAVITY
+    }
+}
+
+// Targets the robot handles in the transition
+fn transition_targets(transition: &Transition, job: &Job, robot: Uuid) -> Vec<Uuid> {
+    transition
+        .meta_data
+        .iter()
+        .filter(|d| d.tag() == DataTag::PrimitiveAssignment && d.id() == Some(robot))
+        .filter_map(|d| job.primitives.get(&d.secondary()?)?.target())
+        .collect()
+}
+
+impl Job {
+    pub fn robot_tools(&self, robot: &RobotInfo) -> Vec<&Tool> {
+        robot.tools.iter().filter_map(|id| self.tools.get(id)).collect()
+    }
+
+    // Whether the robot can handle the target with any of its tools, or within its payload if it has none
+    pub fn robot_can_handle(&self, robot: &RobotInfo, target: &Target) -> bool {
+        let tools = self.robot_tools(robot);
+        if tools.is_empty() {
+            return target.weight() <= robot.payload * GRAVITY;
+        }
+        tools.iter().any(|tool| tool.can_handle(robot, target))
+    }
+
+    // Tools the robot mounts for any of the transitions, in the order it lists them
+    pub fn transitions_tools(&self, robot: &RobotInfo, transitions: &Vec<&Transition>) -> Vec<&Tool> {
+        self.robot_tools(robot)
+            .into_iter()
+            .filter(|tool| {
+                transitions
+                    .iter()
+                    .any(|t| t.meta_data.contains(&Data::Tool(tool.id, robot.id)))
+            })
+            .collect()
+    }
+
+    // The tool mounted for the transition, if the robot has tools
+    pub fn transition_tool(&self, transition: &Transition, robot: Uuid) -> Option<&Tool> {
+        transition.meta_data.iter().find_map(|d| match d {
+            Data::Tool(tool, agent) if *agent == robot => self.tools.get(tool),
+            _ => None,
+        })
+    }
+
+    // Tag each robot action with a tool able to handle its targets, splitting it when there are several.
+    // Robots with more than one tool get a place for each mounted tool and transitions to change between them.
+    pub fn add_robot_tools(&self, net: &mut PetriNet) {
+        for (robot_id, agent) in self.agents.iter() {
+            let robot = match agent {
+                Agent::Robot(robot) => robot,
+                Agent::Human(_) => continue,
+            };
+            let tools = self.robot_tools(robot);
+            if tools.is_empty() {
+                continue;
+            }
+
+            let mut mounted: HashMap<Uuid, Uuid> = HashMap::new();
+            if tools.len() > 1 {
+                for (index, tool) in tools.iter().enumerate() {
+                    let place = Place::new(
+                        format!("{}: {} (mounted)", robot.name, tool.name),
+                        TokenSet::Finite,
+                        vec![Data::Agent(*robot_id), Data::Tool(tool.id, *robot_id)],
+                    );
+                    // Starts out with the first tool
+                    net.initial_marking.insert(place.id, if index == 0 { 1 } else { 0 });
+                    mounted.insert(tool.id, place.id);
+                    net.name_lookup.insert(tool.id, tool.name.clone());
+                    net.places.insert(place.id, place);
+                }
+                for from in tools.iter() {
+                    for to in tools.iter().filter(|to| to.id != from.id) {
+                        let change = Transition::new(
+                            format!("{}:Tool Change:{}->{}", robot.name, from.name, to.name),
+                            vec![(mounted[&from.id], Signature::Static(1))].into_iter().collect(),
+                            vec![(mounted[&to.id], Signature::Static(1))].into_iter().collect(),
+                            vec![
+                                Data::Simulation,
+                                Data::Agent(*robot_id),
+                                Data::ToolChange(*robot_id),
+                                Data::Tool(to.id, *robot_id),
+                            ],
+                            to.change_time,
+                            vec![],
+                        );
+                        net.transitions.insert(change.id, change);
+                    }
+                }
+            }
+
+            let actions: Vec<Transition> = net
+                .query_transitions(&vec![Query::Data(Data::Action(*robot_id))])
+                .into_iter()
+                .cloned()
+                .collect();
+            for action in actions {
+                let targets: Vec<&Target> = transition_targets(&action, self, *robot_id)
+                    .iter()
+                    .filter_map(|id| self.targets.get(id))
+                    .collect();
+                let able: Vec<&&Tool> = tools
+                    .iter()
+                    .filter(|tool| targets.iter().all(|target| tool.can_handle(robot, target)))
+                    .collect();
+                // Left untagged when no tool fits, so that the cost net prunes it
+                if targets.is_empty() || able.is_empty() {
+                    continue;
+                }
+                net.transitions.remove(&action.id);
+                for tool in able {
+                    let mut tooled = action.clone();
+                    tooled.id = Uuid::new_v4();
+                    tooled.add_data(Data::Tool(tool.id, *robot_id));
+                    if let Some(place) = mounted.get(&tool.id) {
+                        tooled.input.insert(*place, Signature::Static(1));
+                        tooled.output.insert(*place, Signature::Static(1));
+                    }
+                    net.transitions.insert(tooled.id, tooled);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn tool_support_test() {
+    let gripper = Tool {
+        mass: 1.0,
+        symmetries: vec![Rating::High, Rating::Medium],
+        ..Tool::new("Parallel".into())
+    };
+    let part = Target::new_precursor("Part".into(), 0.05, 1.0, Rating::High, vec![], 1.0);
+    let plate = Target::new_precursor("Plate".into(), 0.3, 1.0, Rating::Low, vec![], 1.0);
+    assert!(gripper.supports(&part));
+    assert!(!gripper.supports(&plate));
+
+    let mut robot = match Agent::new_robot(
+        "Arm".into(), 0.8, 0.0, 3.0, Rating::Medium, 1.0, 0.001, Rating::Medium, 0.0, 20000.0, 300.0, 1000.0,
+    ) {
+        Agent::Robot(robot) => robot,
+        Agent::Human(_) => unreachable!(),
+    };
+    assert!(gripper.can_handle(&robot, &part));
+    // The gripper's own mass leaves too little payload for the part
+    robot.payload = 1.5;
+    assert!(!gripper.can_handle(&robot, &part));
+}
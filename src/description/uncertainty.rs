@@ -0,0 +1,63 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::petri::cost::{CostDistribution, Uncertainty};
+
+use super::job::Job;
+
+// How uncertain each kind of cost is. Costs of a kind left as None are point estimates.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CostUncertainty {
+    // Wages, burden and hiring
+    pub labor: Option<CostDistribution>,
+    // Exertion, from where the worker falls in the anthropometric percentiles
+    pub ergonomic: Option<CostDistribution>,
+    pub human_error: Option<CostDistribution>,
+    pub robot_error: Option<CostDistribution>,
+    pub energy: Option<CostDistribution>,
+    // Integration of robots, beyond their purchase price
+    pub integration: Option<CostDistribution>,
+}
+
+const KINDS: [Uncertainty; 6] = [
+    Uncertainty::Labor,
+    Uncertainty::Ergonomic,
+    Uncertainty::HumanError,
+    Uncertainty::RobotError,
+    Uncertainty::Energy,
+    Uncertainty::Integration,
+];
+
+impl CostUncertainty {
+    pub fn distribution(&self, kind: &Uncertainty) -> Option<&CostDistribution> {
+        match kind {
+            Uncertainty::Labor => self.labor.as_ref(),
+            Uncertainty::Ergonomic => self.ergonomic.as_ref(),
+            Uncertainty::HumanError => self.human_error.as_ref(),
+            Uncertainty::RobotError => self.robot_error.as_ref(),
+            Uncertainty::Energy => self.energy.as_ref(),
+            Uncertainty::Integration => self.integration.as_ref(),
+        }
+    }
+
+    // What to mark a cost of the kind with: None if costs of the kind are point estimates
+    pub fn of(&self, kind: Uncertainty) -> Option<Uncertainty> {
+        self.distribution(&kind).map(|_| kind)
+    }
+
+    // One draw of the factor of each uncertain kind of cost
+    pub fn sample_factors<R: Rng>(&self, rng: &mut R) -> HashMap<Uncertainty, f64> {
+        KINDS
+            .iter()
+            .filter_map(|kind| Some((*kind, self.distribution(kind)?.sample_factor(rng))))
+            .collect()
+    }
+}
+
+impl Job {
+    pub fn set_cost_uncertainty(&mut self, uncertainty: CostUncertainty) {
+        self.cost_uncertainty = uncertainty;
+    }
+}
@@ -1,4 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
 pub type Time = f64; // seconds
 pub type TokenCount = usize; // strict count
 pub type USD = f64; // dollars
-pub type Watts = f64; // watts
\ No newline at end of file
+pub type Watts = f64; // watts
+
+// What a quantity measures. Each is stored in the job in one unit: m, kg, N, s, m/s, W and USD.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Dimension {
+    Length,
+    Mass,
+    Force,
+    Time,
+    Speed,
+    Power,
+    Currency,
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Force => "force",
+            Dimension::Time => "time",
+            Dimension::Speed => "speed",
+            Dimension::Power => "power",
+            Dimension::Currency => "currency",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Serialized as its symbol
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Unit {
+    M,
+    Mm,
+    Cm,
+    In,
+    Ft,
+    Kg,
+    G,
+    Lb,
+    N,
+    Kgf,
+    Lbf,
+    S,
+    Ms,
+    Min,
+    H,
+    MPerS,
+    MmPerS,
+    InPerS,
+    W,
+    Kw,
+    Usd,
+    Eur,
+    Gbp,
+}
+
+const UNITS: [Unit; 23] = [
+    Unit::M,
+    Unit::Mm,
+    Unit::Cm,
+    Unit::In,
+    Unit::Ft,
+    Unit::Kg,
+    Unit::G,
+    Unit::Lb,
+    Unit::N,
+    Unit::Kgf,
+    Unit::Lbf,
+    Unit::S,
+    Unit::Ms,
+    Unit::Min,
+    Unit::H,
+    Unit::MPerS,
+    Unit::MmPerS,
+    Unit::InPerS,
+    Unit::W,
+    Unit::Kw,
+    Unit::Usd,
+    Unit::Eur,
+    Unit::Gbp,
+];
+
+impl Unit {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::M => "m",
+            Unit::Mm => "mm",
+            Unit::Cm => "cm",
+            Unit::In => "in",
+            Unit::Ft => "ft",
+            Unit::Kg => "kg",
+            Unit::G => "g",
+            Unit::Lb => "lb",
+            Unit::N => "N",
+            Unit::Kgf => "kgf",
+            Unit::Lbf => "lbf",
+            Unit::S => "s",
+            Unit::Ms => "ms",
+            Unit::Min => "min",
+            Unit::H => "h",
+            Unit::MPerS => "m/s",
+            Unit::MmPerS => "mm/s",
+            Unit::InPerS => "in/s",
+            Unit::W => "W",
+            Unit::Kw => "kW",
+            Unit::Usd => "USD",
+            Unit::Eur => "EUR",
+            Unit::Gbp => "GBP",
+        }
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Unit::M | Unit::Mm | Unit::Cm | Unit::In | Unit::Ft => Dimension::Length,
+            Unit::Kg | Unit::G | Unit::Lb => Dimension::Mass,
+            Unit::N | Unit::Kgf | Unit::Lbf => Dimension::Force,
+            Unit::S | Unit::Ms | Unit::Min | Unit::H => Dimension::Time,
+            Unit::MPerS | Unit::MmPerS | Unit::InPerS => Dimension::Speed,
+            Unit::W | Unit::Kw => Dimension::Power,
+            Unit::Usd | Unit::Eur | Unit::Gbp => Dimension::Currency,
+        }
+    }
+
+    // Multiplier into the job's unit for the dimension. None for a currency, which needs an exchange rate.
+    fn factor(&self) -> Option<f64> {
+        match self {
+            Unit::M | Unit::Kg | Unit::N | Unit::S | Unit::MPerS | Unit::W | Unit::Usd => Some(1.0),
+            Unit::Mm | Unit::MmPerS => Some(0.001),
+            Unit::Cm => Some(0.01),
+            Unit::In | Unit::InPerS => Some(0.0254),
+            Unit::Ft => Some(0.3048),
+            Unit::G => Some(0.001),
+            Unit::Lb => Some(0.45359237),
+            Unit::Kgf => Some(9.80665),
+            Unit::Lbf => Some(4.4482216),
+            Unit::Ms => Some(0.001),
+            Unit::Min => Some(60.0),
+            Unit::H => Some(3600.0),
+            Unit::Kw => Some(1000.0),
+            Unit::Eur | Unit::Gbp => None,
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UNITS
+            .iter()
+            .find(|unit| unit.symbol() == s.trim())
+            .cloned()
+            .ok_or(format!("Unknown unit '{}'", s.trim()))
+    }
+}
+
+impl TryFrom<String> for Unit {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Unit> for String {
+    fn from(unit: Unit) -> String {
+        unit.symbol().to_string()
+    }
+}
+
+// A value with its unit, as entered
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Quantity {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Self { value, unit }
+    }
+
+    // The value in the job's unit for the expected dimension. Currencies other than USD are converted
+    // with the exchange rates (USD per unit, by currency symbol).
+    pub fn normalize(&self, expected: Dimension, exchange_rates: &HashMap<String, USD>) -> Result<f64, String> {
+        if self.unit.dimension() != expected {
+            return Err(format!(
+                "Expected a {}, but {} {} is a {}",
+                expected,
+                self.value,
+                self.unit.symbol(),
+                self.unit.dimension()
+            ));
+        }
+        match self.unit.factor() {
+            Some(factor) => Ok(self.value * factor),
+            None => exchange_rates
+                .get(self.unit.symbol())
+                .map(|rate| self.value * rate)
+                .ok_or(format!("No exchange rate for {}", self.unit.symbol())),
+        }
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = String;
+
+    // A number followed by a unit symbol, as in "250 mm" or "12.5EUR"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+' || c == 'e' || c == 'E'))
+            .unwrap_or(s.len());
+        // An exponent marker only belongs to the number when digits follow it
+        let split = match s[..split].rfind(['e', 'E']) {
+            Some(e) if e + 1 == split => e,
+            _ => split,
+        };
+        let value: f64 = s[..split]
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' does not start with a number", s))?;
+        if s[split..].trim().is_empty() {
+            return Err(format!("'{}' has no unit", s));
+        }
+        Ok(Self::new(value, s[split..].parse()?))
+    }
+}
+
+#[test]
+fn quantity_test() {
+    let rates: HashMap<String, USD> = vec![("EUR".to_string(), 1.1)].into_iter().collect();
+    let size: Quantity = "250 mm".parse().unwrap();
+    assert!((size.normalize(Dimension::Length, &rates).unwrap() - 0.25).abs() < 1e-12);
+    let weight: Quantity = "2lb".parse().unwrap();
+    assert!((weight.normalize(Dimension::Mass, &rates).unwrap() - 0.90718474).abs() < 1e-9);
+    let price: Quantity = "1e3 EUR".parse().unwrap();
+    assert!((price.normalize(Dimension::Currency, &rates).unwrap() - 1100.0).abs() < 1e-9);
+
+    // A weight entered as a force is rejected rather than taken as kilograms
+    let force: Quantity = "20 N".parse().unwrap();
+    assert!(force.normalize(Dimension::Mass, &rates).is_err());
+    assert!("5 GBP".parse::<Quantity>().unwrap().normalize(Dimension::Currency, &rates).is_err());
+    assert!("5 furlongs".parse::<Quantity>().is_err());
+}
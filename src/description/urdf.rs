@@ -0,0 +1,361 @@
+use nalgebra::{Isometry3, Matrix3, Translation3, Unit, UnitQuaternion, Vector3};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fs;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JointType {
+    Revolute,
+    Continuous,
+    Prismatic,
+    Fixed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrdfJoint {
+    pub name: String,
+    pub joint_type: JointType,
+    pub parent: String,
+    pub child: String,
+    pub origin_xyz: Vector3<f64>,
+    pub origin_rpy: Vector3<f64>,
+    pub axis: Vector3<f64>,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl UrdfJoint {
+    pub fn is_movable(&self) -> bool {
+        self.joint_type != JointType::Fixed
+    }
+
+    // Joint range to sample. Continuous joints and revolute joints without limits get a full turn.
+    pub fn range(&self) -> (f64, f64) {
+        match self.joint_type {
+            JointType::Continuous => (-PI, PI),
+            JointType::Revolute if self.upper <= self.lower => (-PI, PI),
+            _ => (self.lower, self.upper),
+        }
+    }
+
+    fn origin(&self) -> Isometry3<f64> {
+        Isometry3::from_parts(
+            Translation3::from(self.origin_xyz),
+            UnitQuaternion::from_euler_angles(self.origin_rpy.x, self.origin_rpy.y, self.origin_rpy.z),
+        )
+    }
+
+    fn motion(&self, q: f64) -> Isometry3<f64> {
+        let axis = Unit::new_normalize(self.axis);
+        match self.joint_type {
+            JointType::Revolute | JointType::Continuous => {
+                Isometry3::from_parts(Translation3::identity(), UnitQuaternion::from_axis_angle(&axis, q))
+            }
+            JointType::Prismatic => {
+                Isometry3::from_parts(Translation3::from(axis.into_inner() * q), UnitQuaternion::identity())
+            }
+            JointType::Fixed => Isometry3::identity(),
+        }
+    }
+}
+
+// The serial chain of a URDF from its root link to the tool link
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrdfChain {
+    pub name: String,
+    pub base_link: String,
+    pub tip_link: String,
+    pub joints: Vec<UrdfJoint>,
+}
+
+fn parse_vector(text: Option<&str>, default: Vector3<f64>) -> Result<Vector3<f64>, String> {
+    let text = match text {
+        Some(text) => text,
+        None => return Ok(default),
+    };
+    let values: Vec<f64> = text
+        .split_whitespace()
+        .map(|v| v.parse::<f64>().map_err(|_| format!("Invalid number '{}'", v)))
+        .collect::<Result<Vec<f64>, String>>()?;
+    if values.len() != 3 {
+        return Err(format!("Expected 3 values, found '{}'", text));
+    }
+    Ok(Vector3::new(values[0], values[1], values[2]))
+}
+
+fn parse_joint(node: roxmltree::Node) -> Result<UrdfJoint, String> {
+    let name = node.attribute("name").unwrap_or("").to_string();
+    let joint_type = match node.attribute("type") {
+        Some("revolute") => JointType::Revolute,
+        Some("continuous") => JointType::Continuous,
+        Some("prismatic") => JointType::Prismatic,
+        Some("fixed") => JointType::Fixed,
+        other => return Err(format!("Joint '{}': unsupported type {:?}", name, other)),
+    };
+    let child_attribute = |tag: &str, attribute: &str| -> Option<&str> {
+        node.children()
+            .find(|c| c.has_tag_name(tag))
+            .and_then(|c| c.attribute(attribute))
+    };
+    let parent = child_attribute("parent", "link")
+        .ok_or(format!("Joint '{}' has no parent link", name))?
+        .to_string();
+    let child = child_attribute("child", "link")
+        .ok_or(format!("Joint '{}' has no child link", name))?
+        .to_string();
+    let origin_xyz = parse_vector(child_attribute("origin", "xyz"), Vector3::zeros())?;
+    let origin_rpy = parse_vector(child_attribute("origin", "rpy"), Vector3::zeros())?;
+    let axis = parse_vector(child_attribute("axis", "xyz"), Vector3::x())?;
+    let limit = |attribute: &str| -> Result<f64, String> {
+        match child_attribute("limit", attribute) {
+            Some(v) => v
+                .parse()
+                .map_err(|_| format!("Joint '{}': invalid limit '{}'", name, v)),
+            None => Ok(0.0),
+        }
+    };
+    Ok(UrdfJoint {
+        joint_type,
+        parent,
+        child,
+        origin_xyz,
+        origin_rpy,
+        axis,
+        lower: limit("lower")?,
+        upper: limit("upper")?,
+        name: name.clone(),
+    })
+}
+
+// World axis and origin of a movable joint, and whether it is prismatic
+type JointFrame = (Vector3<f64>, Vector3<f64>, bool);
+
+impl UrdfChain {
+    // Parses the chain ending at `tip_link`, or at the deepest link if none is given
+    pub fn from_str(text: &str, tip_link: Option<&str>) -> Result<Self, String> {
+        let document = roxmltree::Document::parse(text).map_err(|e| e.to_string())?;
+        let robot = document.root_element();
+        if !robot.has_tag_name("robot") {
+            return Err("URDF root element must be <robot>".into());
+        }
+        let joints: Vec<UrdfJoint> = robot
+            .children()
+            .filter(|n| n.has_tag_name("joint"))
+            .map(parse_joint)
+            .collect::<Result<Vec<UrdfJoint>, String>>()?;
+        if joints.is_empty() {
+            return Err("URDF has no joints".into());
+        }
+
+        let by_child: HashMap<&str, &UrdfJoint> =
+            joints.iter().map(|j| (j.child.as_str(), j)).collect();
+        let chain_to = |link: &str| -> Vec<UrdfJoint> {
+            let mut chain = vec![];
+            let mut current = link;
+            while let Some(joint) = by_child.get(current) {
+                if chain.len() > joints.len() {
+                    break; // cyclic
+                }
+                chain.push((*joint).clone());
+                current = joint.parent.as_str();
+            }
+            chain.reverse();
+            chain
+        };
+
+        let tip = match tip_link {
+            Some(tip) => {
+                if !by_child.contains_key(tip) {
+                    return Err(format!("Link '{}' is not the child of any joint", tip));
+                }
+                tip.to_string()
+            }
+            None => joints
+                .iter()
+                .map(|j| j.child.as_str())
+                .max_by_key(|link| chain_to(link).len())
+                .unwrap()
+                .to_string(),
+        };
+        let chain = chain_to(&tip);
+        Ok(Self {
+            name: robot.attribute("name").unwrap_or("").to_string(),
+            base_link: chain[0].parent.clone(),
+            tip_link: tip,
+            joints: chain,
+        })
+    }
+
+    pub fn from_file(path: &str, tip_link: Option<&str>) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::from_str(&text, tip_link).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn degrees_of_freedom(&self) -> usize {
+        self.joints.iter().filter(|j| j.is_movable()).count()
+    }
+
+    // Tool pose in the base frame. `q` has one value per movable joint.
+    pub fn forward_kinematics(&self, q: &[f64]) -> Isometry3<f64> {
+        self.kinematics(q).0
+    }
+
+    // Tool pose, and the frame of each movable joint
+    fn kinematics(&self, q: &[f64]) -> (Isometry3<f64>, Vec<JointFrame>) {
+        let mut pose = Isometry3::identity();
+        let mut frames = vec![];
+        let mut idx = 0;
+        for joint in self.joints.iter() {
+            pose *= joint.origin();
+            if joint.is_movable() {
+                let value = *q.get(idx).unwrap_or(&0.0);
+                let axis = pose.rotation * Unit::new_normalize(joint.axis).into_inner();
+                frames.push((axis, pose.translation.vector, joint.joint_type == JointType::Prismatic));
+                pose *= joint.motion(value);
+                idx += 1;
+            }
+        }
+        (pose, frames)
+    }
+
+    // Yoshikawa manipulability of the tool position, sqrt(det(J J^T))
+    pub fn manipulability(&self, q: &[f64]) -> f64 {
+        let (pose, frames) = self.kinematics(q);
+        let tip = pose.translation.vector;
+        let mut jjt = Matrix3::zeros();
+        for (axis, origin, prismatic) in frames {
+            let column = if prismatic { axis } else { axis.cross(&(tip - origin)) };
+            jjt += column * column.transpose();
+        }
+        jjt.determinant().max(0.0).sqrt()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReachabilityMap {
+    pub voxel_size: f64, // meters
+    // Rotation of the robot base about z relative to the world, in radians
+    pub mount_yaw: f64,
+    // Voxels with a manipulability below this are treated as unreachable
+    pub min_manipulability: f64,
+    // (voxel index, best manipulability seen in the voxel), sorted by index
+    pub voxels: Vec<([i32; 3], f64)>,
+}
+
+impl ReachabilityMap {
+    // Samples the joint space uniformly and records every voxel the tool reaches
+    pub fn build<R: Rng>(chain: &UrdfChain, samples: usize, voxel_size: f64, rng: &mut R) -> Self {
+        let mut best: HashMap<[i32; 3], f64> = HashMap::new();
+        let ranges: Vec<(f64, f64)> = chain
+            .joints
+            .iter()
+            .filter(|j| j.is_movable())
+            .map(|j| j.range())
+            .collect();
+        for _ in 0..samples {
+            let q: Vec<f64> = ranges
+                .iter()
+                .map(|(lower, upper)| if upper > lower { rng.gen_range(*lower..*upper) } else { *lower })
+                .collect();
+            let tip = chain.forward_kinematics(&q).translation.vector;
+            let index = Self::index_of(voxel_size, &tip);
+            let score = chain.manipulability(&q);
+            let entry = best.entry(index).or_insert(score);
+            *entry = entry.max(score);
+        }
+        let mut voxels: Vec<([i32; 3], f64)> = best.into_iter().collect();
+        voxels.sort_by_key(|voxel| voxel.0);
+        Self {
+            voxel_size,
+            mount_yaw: 0.0,
+            min_manipulability: 0.0,
+            voxels,
+        }
+    }
+
+    fn index_of(voxel_size: f64, point: &Vector3<f64>) -> [i32; 3] {
+        [
+            (point.x / voxel_size).floor() as i32,
+            (point.y / voxel_size).floor() as i32,
+            (point.z / voxel_size).floor() as i32,
+        ]
+    }
+
+    // Manipulability at a point given in the world frame relative to the robot base
+    pub fn manipulability(&self, offset: &Vector3<f64>) -> Option<f64> {
+        let local = UnitQuaternion::from_euler_angles(0.0, 0.0, -self.mount_yaw) * offset;
+        let index = Self::index_of(self.voxel_size, &local);
+        self.voxels
+            .binary_search_by(|(i, _)| i.cmp(&index))
+            .ok()
+            .map(|idx| self.voxels[idx].1)
+    }
+
+    pub fn contains(&self, offset: &Vector3<f64>) -> bool {
+        match self.manipulability(offset) {
+            Some(score) => score >= self.min_manipulability,
+            None => false,
+        }
+    }
+
+    // Distance from the base to the farthest reachable voxel center
+    pub fn max_reach(&self) -> f64 {
+        self.voxels
+            .iter()
+            .map(|(i, _)| {
+                Vector3::new(i[0] as f64 + 0.5, i[1] as f64 + 0.5, i[2] as f64 + 0.5).norm()
+                    * self.voxel_size
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+#[test]
+fn urdf_reachability_test() {
+    use rand::SeedableRng;
+
+    // A base yaw joint limited to the front half, then a 0.4m and a 0.3m link pitching in the plane
+    let chain = UrdfChain::from_str(
+        r#"<robot name="planar">
+            <link name="base"/><link name="turret"/><link name="upper"/><link name="lower"/><link name="tool"/>
+            <joint name="yaw" type="revolute">
+                <parent link="base"/><child link="turret"/>
+                <axis xyz="0 0 1"/><limit lower="-1.5707" upper="1.5707"/>
+            </joint>
+            <joint name="shoulder" type="revolute">
+                <parent link="turret"/><child link="upper"/>
+                <axis xyz="0 1 0"/><limit lower="-1.5707" upper="1.5707"/>
+            </joint>
+            <joint name="elbow" type="revolute">
+                <parent link="upper"/><child link="lower"/>
+                <origin xyz="0.4 0 0"/><axis xyz="0 1 0"/><limit lower="-2.5" upper="2.5"/>
+            </joint>
+            <joint name="flange" type="fixed">
+                <parent link="lower"/><child link="tool"/><origin xyz="0.3 0 0"/>
+            </joint>
+        </robot>"#,
+        None,
+    )
+    .unwrap();
+    assert_eq!(chain.tip_link, "tool");
+    assert_eq!(chain.degrees_of_freedom(), 3);
+    let home = chain.forward_kinematics(&[0.0, 0.0, 0.0]).translation.vector;
+    assert!((home - Vector3::new(0.7, 0.0, 0.0)).norm() < 1e-9);
+    // Fully stretched out, the arm is singular
+    assert!(chain.manipulability(&[0.0, 0.0, 0.0]) < 1e-9);
+    assert!(chain.manipulability(&[0.0, 0.0, 1.0]) > 0.0);
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let map = ReachabilityMap::build(&chain, 20000, 0.05, &mut rng);
+    assert!(map.contains(&Vector3::new(0.5, 0.0, 0.1)));
+    // Within the sphere of the arm's reach, but behind the base's yaw range
+    assert!(!map.contains(&Vector3::new(-0.5, 0.0, 0.1)));
+    assert!(!map.contains(&Vector3::new(1.0, 0.0, 0.0)));
+    assert!(map.max_reach() <= 0.7 + 0.05 * 3.0_f64.sqrt());
+}
@@ -1,3 +1,4 @@
+pub mod analysis;
 pub mod constants;
 pub mod description;
 pub mod petri;
@@ -1,4 +1,8 @@
+use rand::distributions::Distribution;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use statrs::distribution::{LogNormal, Normal, Triangular, Uniform};
+use std::collections::HashMap;
 use std::ops::Add;
 use std::vec::Vec;
 
@@ -16,40 +20,114 @@ pub enum CostCategory {
     Monetary,
 }
 
+// Uncertainty in a cost, as a factor on its value. The factor has a mean of 1 for LogNormal. Clamping
+// the Normal at zero raises its mean above 1, by under 0.005 while std_dev is at most 0.5.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CostDistribution {
+    // Clamped at zero
+    Normal { std_dev: f64 },
+    // Sigma of the log of the factor
+    LogNormal { sigma: f64 },
+    Uniform { min: f64, max: f64 },
+    Triangular { min: f64, mode: f64, max: f64 },
+}
+
+impl CostDistribution {
+    // A draw of the factor. Invalid parameters leave the value as it is.
+    pub fn sample_factor<R: Rng>(&self, rng: &mut R) -> f64 {
+        match self {
+            CostDistribution::Normal { std_dev } => Normal::new(1.0, *std_dev)
+                .map_or(1.0, |d| d.sample(rng).max(0.0)),
+            CostDistribution::LogNormal { sigma } => LogNormal::new(-sigma * sigma / 2.0, *sigma)
+                .map_or(1.0, |d| d.sample(rng)),
+            CostDistribution::Uniform { min, max } => Uniform::new(*min, *max)
+                .map_or(1.0, |d| d.sample(rng)),
+            CostDistribution::Triangular { min, mode, max } => Triangular::new(*min, *max, *mode)
+                .map_or(1.0, |d| d.sample(rng)),
+        }
+    }
+}
+
+// Kind of an uncertain cost. Every cost of a kind is scaled by the same draw of its factor, so a kind
+// has one spread for the whole job: costs cannot carry their own, such as a different wage spread for
+// each worker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Uncertainty {
+    Labor,
+    Ergonomic,
+    HumanError,
+    RobotError,
+    Energy,
+    Integration,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Cost {
     pub frequency: CostFrequency,
     pub value: f64,
     pub category: CostCategory,
+    // None for a point estimate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uncertainty: Option<Uncertainty>,
+}
+
+impl Cost {
+    // The value under one draw of the factor of each kind of cost
+    pub fn sample(&self, factors: &HashMap<Uncertainty, f64>) -> f64 {
+        match &self.uncertainty {
+            Some(uncertainty) => self.value * factors.get(uncertainty).unwrap_or(&1.0),
+            None => self.value,
+        }
+    }
 }
 
 pub type CostSet = Vec<Cost>;
 
+// Costs are summed by frequency, category and kind of uncertainty, so that the uncertain ones can
+// still be sampled.
 pub fn add_cost_sets(a: &CostSet, b: &CostSet) -> CostSet {
-    let mut result = vec![];
-    for freq in vec![CostFrequency::Once, CostFrequency::Extrapolated].iter() {
-        for cat in vec![CostCategory::Ergonomic, CostCategory::Monetary].iter() {
-            let mut sum = 0.0;
-            for cost in a
-                .iter()
-                .filter(|c| c.frequency == *freq && c.category == *cat)
-            {
-                sum += cost.value;
-            }
-            for cost in b
-                .iter()
-                .filter(|c| c.frequency == *freq && c.category == *cat)
-            {
-                sum += cost.value;
-            }
-            if sum > 0.0 {
-                result.push(Cost {
-                    frequency: freq.clone(),
-                    value: sum,
-                    category: cat.clone(),
-                });
-            }
+    let mut result: CostSet = vec![];
+    for cost in a.iter().chain(b.iter()) {
+        match result.iter_mut().find(|c| {
+            c.frequency == cost.frequency && c.category == cost.category && c.uncertainty == cost.uncertainty
+        }) {
+            Some(sum) => sum.value += cost.value,
+            None => result.push(cost.clone()),
         }
     }
-    return result;
+    result.retain(|c| c.value > 0.0);
+    result
+}
+
+#[test]
+fn cost_distribution_test() {
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let factors: Vec<f64> = (0..2000)
+        .map(|_| CostDistribution::Uniform { min: 0.5, max: 1.5 }.sample_factor(&mut rng))
+        .collect();
+    assert!(factors.iter().all(|f| (0.5..=1.5).contains(f)));
+    assert!((factors.iter().sum::<f64>() / 2000.0 - 1.0).abs() < 0.05);
+
+    let uncertain = Cost {
+        frequency: CostFrequency::Extrapolated,
+        value: 10.0,
+        category: CostCategory::Monetary,
+        uncertainty: Some(Uncertainty::Labor),
+    };
+    let drawn: HashMap<Uncertainty, f64> = vec![(Uncertainty::Labor, 1.2)].into_iter().collect();
+    assert!((uncertain.sample(&drawn) - 12.0).abs() < 1e-12);
+    assert_eq!(uncertain.sample(&HashMap::new()), 10.0);
+
+    // Summing keeps the uncertain costs apart from the point estimates and from other kinds
+    let point = Cost { uncertainty: None, ..uncertain.clone() };
+    let energy = Cost { uncertainty: Some(Uncertainty::Energy), ..uncertain.clone() };
+    let sum = add_cost_sets(&vec![uncertain.clone(), point.clone(), energy.clone()], &vec![point, uncertain.clone()]);
+    assert_eq!(sum.len(), 3);
+    assert!(sum.contains(&Cost { value: 20.0, ..uncertain.clone() }));
+    assert!(sum.contains(&Cost { value: 20.0, uncertainty: None, ..uncertain }));
+    assert!(sum.contains(&energy));
 }
@@ -41,6 +41,24 @@ pub enum Data {
     FromHandPOI(Uuid, Uuid),
     ToHandPOI(Uuid, Uuid),
 
+    // Contain Shared Zone UUID
+    SharedZone(Uuid),
+
+    // Encoded as Tool UUID, Agent UUID
+    Tool(Uuid, Uuid),
+    // Contain Agent UUID
+    ToolChange(Uuid),
+
+    // Quality
+    // Contain Target UUID, marking places that hold defective parts
+    Defect(Uuid),
+    // Encoded as Task UUID, probability that a defect is caught there
+    Reject(Uuid, f64),
+    // Contain Task UUID
+    Rework(Uuid),
+    // Encoded as Target UUID, value of the material lost
+    Scrap(Uuid, f64),
+
     // Primitive Assignments
     // Encoded as Agent UUID, Primitive UUID
     PrimitiveAssignment(Uuid, Uuid),
@@ -95,6 +113,13 @@ impl Data {
             Data::ToStandingPOI(id, _) => Some(*id),
             Data::FromHandPOI(id, _) => Some(*id),
             Data::ToHandPOI(id, _) => Some(*id),
+            Data::SharedZone(id) => Some(*id),
+            Data::Tool(id, _) => Some(*id),
+            Data::ToolChange(id) => Some(*id),
+            Data::Defect(id) => Some(*id),
+            Data::Reject(id, _) => Some(*id),
+            Data::Rework(id) => Some(*id),
+            Data::Scrap(id, _) => Some(*id),
             // PrimitiveAssignment returns the Agent UUID
             Data::PrimitiveAssignment(id, _) => Some(*id),
             Data::AgentAgnostic => None,
@@ -144,6 +169,13 @@ impl Data {
             Data::ToStandingPOI(_, id) => Some(*id),
             Data::FromHandPOI(_, id) => Some(*id),
             Data::ToHandPOI(_, id) => Some(*id),
+            Data::SharedZone(_id) => None,
+            Data::Tool(_, id) => Some(*id),
+            Data::ToolChange(_id) => None,
+            Data::Defect(_id) => None,
+            Data::Reject(_id, _) => None,
+            Data::Rework(_id) => None,
+            Data::Scrap(_id, _) => None,
             // PrimitiveAssignment returns the Primitive UUID
             Data::PrimitiveAssignment(_, id) => Some(*id),
             Data::AgentAgnostic => None,
@@ -193,6 +225,13 @@ impl Data {
             Data::ToStandingPOI(_id, _) => None,
             Data::FromHandPOI(_id, _) => None,
             Data::ToHandPOI(_id, _) => None,
+            Data::SharedZone(_id) => None,
+            Data::Tool(_id, _) => None,
+            Data::ToolChange(_id) => None,
+            Data::Defect(_id) => None,
+            Data::Reject(_id, probability) => Some(*probability),
+            Data::Rework(_id) => None,
+            Data::Scrap(_id, value) => Some(*value),
             // PrimitiveAssignment returns the Primitive UUID
             Data::PrimitiveAssignment(_, _) => None,
             Data::AgentAgnostic => None,
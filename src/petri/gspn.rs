@@ -0,0 +1,349 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+use crate::description::units::TokenCount;
+use crate::petri::net::PetriNet;
+use crate::petri::token::TokenSet;
+use crate::petri::transition::{Signature, Timing, Transition};
+
+// Longest chain of immediate firings followed before giving up on a loop of them
+const MAX_IMMEDIATE_DEPTH: usize = 1000;
+const MAX_SWEEPS: usize = 100000;
+const TOLERANCE: f64 = 1e-12;
+
+type Marking = Vec<TokenCount>;
+// Probability of ending in each tangible marking, and expected firings of each immediate transition
+type Resolution = (HashMap<Marking, f64>, HashMap<Uuid, f64>);
+// A timed transition out of a state: its rate, destinations with probabilities, and immediate firings
+type Move = (Uuid, f64, Vec<(usize, f64)>, HashMap<Uuid, f64>);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteadyState {
+    // Tangible markings in the reachability graph
+    pub states: usize,
+    // Transition UUID -> mean firings per second
+    pub throughput: HashMap<Uuid, f64>,
+    // Place UUID -> mean tokens, for the finite places
+    pub mean_tokens: HashMap<Uuid, f64>,
+}
+
+// Tokens moved along an arc. Ranges are taken at their lower bound.
+fn arc_tokens(signature: &Signature) -> TokenCount {
+    match signature {
+        Signature::Static(count) => *count,
+        Signature::Range(min, _) => *min,
+    }
+}
+
+struct Reachability<'a> {
+    net: &'a PetriNet,
+    // Finite places, in the order of the marking vectors. Infinite and sink places are not tracked.
+    places: Vec<Uuid>,
+    index: HashMap<Uuid, usize>,
+    capacities: Vec<Option<TokenCount>>,
+    transitions: Vec<(&'a Transition, Timing)>,
+    // Resolutions of the vanishing markings seen so far
+    vanishing: RefCell<HashMap<Marking, Resolution>>,
+}
+
+impl<'a> Reachability<'a> {
+    fn enabled(&self, transition: &Transition, marking: &Marking) -> bool {
+        let mut next = marking.clone();
+        for (place, signature) in transition.input.iter() {
+            if let Some(i) = self.index.get(place) {
+                let tokens = arc_tokens(signature);
+                if next[*i] < tokens {
+                    return false;
+                }
+                next[*i] -= tokens;
+            }
+        }
+        for (place, signature) in transition.output.iter() {
+            if let Some(i) = self.index.get(place) {
+                next[*i] += arc_tokens(signature);
+                if self.capacities[*i].is_some_and(|capacity| next[*i] > capacity) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn fire(&self, transition: &Transition, marking: &Marking) -> Marking {
+        let mut next = marking.clone();
+        for (place, signature) in transition.input.iter() {
+            if let Some(i) = self.index.get(place) {
+                next[*i] -= arc_tokens(signature);
+            }
+        }
+        for (place, signature) in transition.output.iter() {
+            if let Some(i) = self.index.get(place) {
+                next[*i] += arc_tokens(signature);
+            }
+        }
+        next
+    }
+
+    // Enabled immediate transitions with their weights. Timed transitions are disabled while any are.
+    fn immediate(&self, marking: &Marking) -> Vec<(&'a Transition, f64)> {
+        self.transitions
+            .iter()
+            .filter_map(|(transition, timing)| match timing {
+                Timing::Immediate { weight } if self.enabled(transition, marking) => Some((*transition, *weight)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Follow immediate firings out of a marking until only tangible markings are left. Returns the
+    // probability of ending in each, and the expected number of firings of each immediate transition.
+    fn resolve(&self, marking: Marking) -> Result<Resolution, String> {
+        self.resolve_within(marking, 0)
+    }
+
+    // Each vanishing marking is resolved once, so interleavings of independent immediate transitions
+    // share the markings they pass through instead of being followed one ordering at a time
+    fn resolve_within(&self, marking: Marking, depth: usize) -> Result<Resolution, String> {
+        let immediate = self.immediate(&marking);
+        if immediate.is_empty() {
+            return Ok((vec![(marking, 1.0)].into_iter().collect(), HashMap::new()));
+        }
+        if let Some(resolution) = self.vanishing.borrow().get(&marking) {
+            return Ok(resolution.clone());
+        }
+        if depth > MAX_IMMEDIATE_DEPTH {
+            return Err("Immediate transitions fire in a loop without reaching a tangible marking".into());
+        }
+        let total: f64 = immediate.iter().map(|(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return Err("Enabled immediate transitions all have zero weight".into());
+        }
+        let mut tangible: HashMap<Marking, f64> = HashMap::new();
+        let mut firings: HashMap<Uuid, f64> = HashMap::new();
+        for (transition, weight) in immediate {
+            let branch = weight / total;
+            *firings.entry(transition.id).or_insert(0.0) += branch;
+            let (next_tangible, next_firings) = self.resolve_within(self.fire(transition, &marking), depth + 1)?;
+            for (next, probability) in next_tangible {
+                *tangible.entry(next).or_insert(0.0) += branch * probability;
+            }
+            for (id, expected) in next_firings {
+                *firings.entry(id).or_insert(0.0) += branch * expected;
+            }
+        }
+        self.vanishing
+            .borrow_mut()
+            .insert(marking, (tangible.clone(), firings.clone()));
+        Ok((tangible, firings))
+    }
+}
+
+impl PetriNet {
+    // Steady state of the net as a generalized stochastic Petri net, by building the continuous-time
+    // Markov chain of its tangible markings. Finite places may be given a capacity, which blocks any
+    // transition that would overfill them. Each transition fires as a single server at its rate;
+    // deterministic delays are approximated by exponential ones with the same mean.
+    pub fn steady_state(
+        &self,
+        capacities: &HashMap<Uuid, TokenCount>,
+        max_states: usize,
+    ) -> Result<SteadyState, String> {
+        let places: Vec<Uuid> = self
+            .places
+            .values()
+            .filter(|place| place.tokens == TokenSet::Finite)
+            .map(|place| place.id)
+            .sorted()
+            .collect();
+        let reachability = Reachability {
+            net: self,
+            index: places.iter().enumerate().map(|(i, id)| (*id, i)).collect(),
+            capacities: places.iter().map(|id| capacities.get(id).cloned()).collect(),
+            transitions: self
+                .transitions
+                .values()
+                .sorted_by_key(|transition| transition.id)
+                .map(|transition| (transition, transition.timing()))
+                .collect(),
+            places,
+            vanishing: RefCell::new(HashMap::new()),
+        };
+        let initial: Marking = reachability
+            .places
+            .iter()
+            .map(|id| *reachability.net.initial_marking.get(id).unwrap_or(&0))
+            .collect();
+
+        // Explore the tangible markings, recording the rate of every move between them
+        let mut states: Vec<Marking> = vec![];
+        let mut lookup: HashMap<Marking, usize> = HashMap::new();
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        let mut moves: Vec<Vec<Move>> = vec![];
+
+        let (start, _) = reachability.resolve(initial)?;
+        for marking in start.into_keys().sorted() {
+            lookup.insert(marking.clone(), states.len());
+            queue.push_back(states.len());
+            states.push(marking);
+        }
+        while let Some(state) = queue.pop_front() {
+            let marking = states[state].clone();
+            let mut state_moves = vec![];
+            for (transition, timing) in reachability.transitions.iter() {
+                let rate = match timing.rate() {
+                    Some(rate) if rate > 0.0 => rate,
+                    _ => continue,
+                };
+                if !reachability.enabled(transition, &marking) {
+                    continue;
+                }
+                let (destinations, firings) = reachability.resolve(reachability.fire(transition, &marking))?;
+                let mut targets = vec![];
+                for (next, probability) in destinations.into_iter().sorted_by(|a, b| a.0.cmp(&b.0)) {
+                    let index = match lookup.get(&next) {
+                        Some(index) => *index,
+                        None => {
+                            if states.len() >= max_states {
+                                return Err(format!("The net has more than {} tangible markings", max_states));
+                            }
+                            lookup.insert(next.clone(), states.len());
+                            queue.push_back(states.len());
+                            states.push(next);
+                            states.len() - 1
+                        }
+                    };
+                    targets.push((index, probability));
+                }
+                state_moves.push((transition.id, rate, targets, firings));
+            }
+            if state_moves.is_empty() {
+                return Err(format!("The net deadlocks in marking {:?}", marking));
+            }
+            // States are queued in the order they are numbered
+            moves.push(state_moves);
+        }
+
+        // Solve pi Q = 0 by Gauss-Seidel over the incoming rates of each state
+        let count = states.len();
+        let mut incoming: Vec<Vec<(usize, f64)>> = vec![vec![]; count];
+        let mut outgoing: Vec<f64> = vec![0.0; count];
+        for (from, state_moves) in moves.iter().enumerate() {
+            for (_, rate, targets, _) in state_moves.iter() {
+                for (to, probability) in targets.iter() {
+                    if *to != from {
+                        incoming[*to].push((from, rate * probability));
+                        outgoing[from] += rate * probability;
+                    }
+                }
+            }
+        }
+        let mut pi = vec![1.0 / count as f64; count];
+        let mut converged = false;
+        for _ in 0..MAX_SWEEPS {
+            let mut change: f64 = 0.0;
+            for state in 0..count {
+                if outgoing[state] <= 0.0 {
+                    continue;
+                }
+                let inflow: f64 = incoming[state].iter().map(|(from, rate)| pi[*from] * rate).sum();
+                let next = inflow / outgoing[state];
+                change = change.max((next - pi[state]).abs());
+                pi[state] = next;
+            }
+            let total: f64 = pi.iter().sum();
+            if total <= 0.0 {
+                return Err("The Markov chain has no steady state".into());
+            }
+            pi.iter_mut().for_each(|p| *p /= total);
+            if change < TOLERANCE {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return Err("The steady state did not converge".into());
+        }
+
+        let mut throughput: HashMap<Uuid, f64> = self.transitions.keys().map(|id| (*id, 0.0)).collect();
+        for (state, state_moves) in moves.iter().enumerate() {
+            for (transition, rate, _, firings) in state_moves.iter() {
+                *throughput.entry(*transition).or_insert(0.0) += pi[state] * rate;
+                for (immediate, expected) in firings.iter() {
+                    *throughput.entry(*immediate).or_insert(0.0) += pi[state] * rate * expected;
+                }
+            }
+        }
+        let mean_tokens = reachability
+            .places
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, states.iter().zip(pi.iter()).map(|(m, p)| m[i] as f64 * p).sum()))
+            .collect();
+
+        Ok(SteadyState {
+            states: count,
+            throughput,
+            mean_tokens,
+        })
+    }
+}
+
+#[test]
+fn steady_state_test() {
+    use crate::petri::place::Place;
+
+    // A machine that breaks down at rate 1 and is repaired at rate 3 is up three quarters of the time
+    let mut net = PetriNet::new("Machine".into());
+    let up = Place::new("Up".into(), TokenSet::Finite, vec![]);
+    let down = Place::new("Down".into(), TokenSet::Finite, vec![]);
+    let (up_id, down_id) = (up.id, down.id);
+    net.places.insert(up_id, up);
+    net.places.insert(down_id, down);
+    net.initial_marking.insert(up_id, 1);
+    let mut fail = Transition::new("Fail".into(), HashMap::new(), HashMap::new(), vec![], 1.0, vec![])
+        .add_input(&up_id, 1)
+        .add_output(&down_id, 1);
+    fail.set_timing(Timing::Exponential { rate: 1.0 });
+    let repair = Transition::new("Repair".into(), HashMap::new(), HashMap::new(), vec![], 1.0 / 3.0, vec![])
+        .add_input(&down_id, 1)
+        .add_output(&up_id, 1);
+    let fail_id = fail.id;
+    net.transitions.insert(fail_id, fail);
+    net.transitions.insert(repair.id, repair);
+
+    let steady = net.steady_state(&HashMap::new(), 100).unwrap();
+    assert_eq!(steady.states, 2);
+    assert!((steady.mean_tokens[&up_id] - 0.75).abs() < 1e-9);
+    assert!((steady.throughput[&fail_id] - 0.75).abs() < 1e-9);
+
+    // Twelve parts settle one at a time into a bin. Without sharing the markings in between, the
+    // 12! orders they could settle in would each be followed.
+    let mut net = PetriNet::new("Bin".into());
+    let bin = Place::new("Bin".into(), TokenSet::Finite, vec![]);
+    let bin_id = bin.id;
+    net.places.insert(bin_id, bin);
+    net.initial_marking.insert(bin_id, 0);
+    let mut empty = Transition::new("Empty".into(), HashMap::new(), HashMap::new(), vec![], 1.0, vec![])
+        .add_input(&bin_id, 12);
+    for i in 0..12 {
+        let part = Place::new(format!("Part {}", i), TokenSet::Finite, vec![]);
+        empty = empty.add_output(&part.id, 1);
+        let mut settle = Transition::new(format!("Settle {}", i), HashMap::new(), HashMap::new(), vec![], 0.0, vec![])
+            .add_input(&part.id, 1)
+            .add_output(&bin_id, 1);
+        settle.set_timing(Timing::Deterministic { delay: 0.0 });
+        assert_eq!(settle.timing(), Timing::Immediate { weight: 1.0 });
+        net.transitions.insert(settle.id, settle);
+        net.initial_marking.insert(part.id, 1);
+        net.places.insert(part.id, part);
+    }
+    let empty_id = empty.id;
+    net.transitions.insert(empty_id, empty);
+    let steady = net.steady_state(&HashMap::new(), 100).unwrap();
+    assert_eq!(steady.states, 1);
+    assert!((steady.throughput[&empty_id] - 1.0).abs() < 1e-9);
+}
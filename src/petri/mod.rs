@@ -4,4 +4,5 @@ pub mod place;
 pub mod token;
 pub mod transition;
 pub mod matrix;
-pub mod cost;
\ No newline at end of file
+pub mod cost;
+pub mod gspn;
\ No newline at end of file
@@ -49,6 +49,29 @@ impl Sub for Signature {
     }
 }
 
+// How long a transition takes to fire once enabled, for stochastic analysis
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Timing {
+    // Fires as soon as it is enabled. Conflicting immediate transitions are chosen in proportion to weight.
+    Immediate { weight: f64 },
+    // Exponentially distributed delay with the given rate (firings per second)
+    Exponential { rate: f64 },
+    // Fixed delay (seconds)
+    Deterministic { delay: Time },
+}
+
+impl Timing {
+    // Rate of the exponential delay with the same mean. None for immediate transitions.
+    pub fn rate(&self) -> Option<f64> {
+        match self {
+            Timing::Immediate { .. } => None,
+            Timing::Exponential { rate } => Some(*rate),
+            Timing::Deterministic { delay } => Some(1.0 / delay.max(f64::MIN_POSITIVE)),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Transition {
@@ -58,6 +81,9 @@ pub struct Transition {
     pub output: HashMap<Uuid,Signature>,
     pub meta_data: Vec<Data>,
     pub time: Time,
+    // Overrides the timing derived from `time`
+    #[serde(default)]
+    pub timing: Option<Timing>,
     pub cost: CostSet,
 }
 
@@ -77,10 +103,31 @@ impl Transition {
             output,
             meta_data,
             time,
+            timing: None,
             cost,
         }
     }
 
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.timing = Some(timing);
+    }
+
+    // The explicit timing, or else immediate for setup and decision steps and anything that takes no
+    // time, and an exponential delay with a mean of `time` for the rest
+    pub fn timing(&self) -> Timing {
+        match &self.timing {
+            // A fixed delay of zero fires as soon as it is enabled
+            Some(Timing::Deterministic { delay }) if *delay <= 0.0 => return Timing::Immediate { weight: 1.0 },
+            Some(timing) => return timing.clone(),
+            None => {}
+        }
+        if self.time <= 0.0 || self.has_any_data(&vec![Query::Data(Data::Setup), Query::Data(Data::Decide)]) {
+            Timing::Immediate { weight: 1.0 }
+        } else {
+            Timing::Exponential { rate: 1.0 / self.time }
+        }
+    }
+
     pub fn add_input(mut self, place_id: &Uuid, tokens: usize) -> Self {
         if self.input.contains_key(place_id) {
             self.input.insert(*place_id, self.input.get(place_id).unwrap().clone() + Signature::Static(tokens));
@@ -86,8 +86,9 @@ pub fn fitz_law(a: f64, b: f64, d: f64, w: f64) -> f64 {
     a + b * index_of_difficulty(d, w)
 }
 
+// Shannon form, which stays non-negative for moves shorter than the target is wide
 pub fn index_of_difficulty(d: f64, w: f64) -> f64 {
-    (2.0 * d / w).log2()
+    (d / w + 1.0).log2()
 }
 
 #[test]